@@ -9,11 +9,14 @@ use gpui::{
 };
 use gpui_component::{
     button::{Button, ButtonCustomVariant, ButtonVariants},
-    h_flex, ActiveTheme, Icon, IconName, Selectable, Sizable,
+    h_flex, ActiveTheme, Icon, IconName, Selectable, Sizable, WindowExt,
 };
 use uuid::Uuid;
 
-use crate::ui::{pages::PageRoute, state::AppState};
+use crate::{
+    core::I18n,
+    ui::{pages::PageRoute, state::AppState},
+};
 
 /// Maximum number of connection labels displayed
 const MAX_VISIBLE_TABS: usize = 8;
@@ -139,9 +142,9 @@ impl ConnectionTabs {
                             .hover(|this| this.bg(cx.theme().border))
                             .id(SharedString::from(format!("close-{}", config_id)))
                             .child(Icon::new(IconName::Close))
-                            .on_click(move |_, _, cx| {
+                            .on_click(move |_, window, cx| {
                                 cx.update_entity(&entity_for_close, |tabs: &mut Self, cx| {
-                                    tabs.remove_tab(config_id, cx);
+                                    tabs.close_tab(config_id, window, cx);
                                 });
                             }),
                     ),
@@ -154,6 +157,123 @@ impl ConnectionTabs {
             .tooltip(full_name)
     }
 
+    /// Close the tab, asking first when the connection has an uncommitted transaction
+    fn close_tab(&mut self, config_id: Uuid, window: &mut Window, cx: &mut Context<Self>) {
+        let db_manager = cx.global::<DBManager>().clone();
+        cx.spawn_in(window, async move |this, cx| {
+            if db_manager.in_transaction(&config_id).await {
+                this.update_in(cx, |this, window, cx| {
+                    this.confirm_close(config_id, db_manager, window, cx)
+                })?;
+            } else {
+                this.update(cx, |this, cx| this.remove_tab(config_id, cx))?;
+            }
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn confirm_close(
+        &mut self,
+        config_id: Uuid,
+        db_manager: DBManager,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let entity = cx.entity();
+        let name = self
+            .active_configs
+            .iter()
+            .find(|c| c.id == config_id)
+            .map(|c| c.name.clone())
+            .unwrap_or_default();
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let i18n = cx.global::<I18n>();
+            let entity = entity.clone();
+            let db_manager = db_manager.clone();
+
+            dialog
+                .width(px(420.))
+                .title(i18n.t("connection.uncommitted-title"))
+                .child(i18n.t_with("connection.uncommitted-message", &[("name", &name)]))
+                .footer(move |_, _, _, cx| {
+                    let i18n = cx.global::<I18n>();
+                    let commit_entity = entity.clone();
+                    let rollback_entity = entity.clone();
+                    let commit_manager = db_manager.clone();
+                    let rollback_manager = db_manager.clone();
+                    vec![
+                        Button::new("cancel-close")
+                            .small()
+                            .px_2()
+                            .label(i18n.t("connection.cancel"))
+                            .on_click(move |_, window, cx| window.close_dialog(cx)),
+                        Button::new("rollback-close")
+                            .small()
+                            .px_2()
+                            .label(i18n.t("connection.rollback-and-close"))
+                            .on_click(move |_, window, cx| {
+                                window.close_dialog(cx);
+                                Self::end_and_remove(
+                                    &rollback_entity,
+                                    rollback_manager.clone(),
+                                    config_id,
+                                    false,
+                                    cx,
+                                );
+                            }),
+                        Button::new("commit-close")
+                            .small()
+                            .px_2()
+                            .primary()
+                            .label(i18n.t("connection.commit-and-close"))
+                            .on_click(move |_, window, cx| {
+                                window.close_dialog(cx);
+                                Self::end_and_remove(
+                                    &commit_entity,
+                                    commit_manager.clone(),
+                                    config_id,
+                                    true,
+                                    cx,
+                                );
+                            }),
+                    ]
+                })
+        });
+    }
+
+    /// Commit or roll back the open transaction, the tab stays open if that fails
+    fn end_and_remove(
+        entity: &Entity<Self>,
+        db_manager: DBManager,
+        config_id: Uuid,
+        commit: bool,
+        cx: &mut App,
+    ) {
+        let entity = entity.clone();
+        cx.spawn(async move |cx| {
+            let result = async {
+                let driver = db_manager.connect(&config_id).await?;
+                match driver.as_transactional() {
+                    Some(transactional) if commit => transactional.commit().await,
+                    Some(transactional) => transactional.rollback().await,
+                    None => Ok(()),
+                }
+            }
+            .await;
+            match result {
+                Ok(()) => {
+                    cx.update_entity(&entity, |tabs, cx| tabs.remove_tab(config_id, cx))?;
+                }
+                Err(e) => eprintln!("Failed to end transaction: {}", e),
+            }
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
     fn remove_tab(&mut self, config_id: Uuid, cx: &mut Context<Self>) {
         self.active_configs.retain(|c| c.id != config_id);
         if self.selected_id == Some(config_id) {
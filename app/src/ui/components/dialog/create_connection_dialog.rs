@@ -6,7 +6,12 @@ use crate::{
     core::I18n,
     ui::components::{
         dialog::{
+            create_mongo_connection_dialog::CreateMongoConnectionDialog,
+            create_mssql_connection_dialog::CreateMsSqlConnectionDialog,
             create_mysql_connection_dialog::CreateMySQLConnectionDialog,
+            create_postgres_connection_dialog::CreatePostgresConnectionDialog,
+            create_redis_connection_dialog::CreateRedisConnectionDialog,
+            create_sqlite_connection_dialog::CreateSQLiteConnectionDialog,
             import_url_dialog::ImportUrlDialog,
         },
         list_database::DatabaseList,
@@ -61,9 +66,34 @@ impl CreateConnectionDialog {
                                 cx.update_entity(&dialog_entity_footer, |this, cx| {
                                     cx.update_entity(&this.db_list, |db_list, cx| {
                                         match db_list.active_item {
-                                            Some(DatabaseType::MySql) => {
+                                            Some(
+                                                db_type @ (DatabaseType::MySql
+                                                | DatabaseType::MariaDB),
+                                            ) => {
                                                 window.close_dialog(cx);
-                                                CreateMySQLConnectionDialog::open(window, cx)
+                                                CreateMySQLConnectionDialog::open(
+                                                    db_type, window, cx,
+                                                )
+                                            }
+                                            Some(DatabaseType::Postgre) => {
+                                                window.close_dialog(cx);
+                                                CreatePostgresConnectionDialog::open(window, cx)
+                                            }
+                                            Some(DatabaseType::Sqlite) => {
+                                                window.close_dialog(cx);
+                                                CreateSQLiteConnectionDialog::open(window, cx)
+                                            }
+                                            Some(DatabaseType::Redis) => {
+                                                window.close_dialog(cx);
+                                                CreateRedisConnectionDialog::open(window, cx)
+                                            }
+                                            Some(DatabaseType::MongoDB) => {
+                                                window.close_dialog(cx);
+                                                CreateMongoConnectionDialog::open(window, cx)
+                                            }
+                                            Some(DatabaseType::MicrosoftSQLServer) => {
+                                                window.close_dialog(cx);
+                                                CreateMsSqlConnectionDialog::open(window, cx)
                                             }
                                             _ => {
                                                 eprintln!("Not supported database type");
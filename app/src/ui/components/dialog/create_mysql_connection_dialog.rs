@@ -17,24 +17,23 @@ use crate::{
         state::{AppConnectionTabsState, AppLoadingState, AppNotificationState, AppState},
     },
 };
-use db_sight_core::{
-    ConnectionConfig, DBError, DBManager, DatabaseDriver, DatabaseType, Endpoint, MySqlDriver,
-};
+use db_sight_core::{ConnectionConfig, DBError, DBManager, DatabaseType, DriverFactory, Endpoint};
 
+/// Connection dialog for servers speaking the MySQL protocol (MySQL and MariaDB)
 pub struct CreateMySQLConnectionDialog {
+    db_type: DatabaseType,
     name: Entity<InputState>,
     host: Entity<InputState>,
     port: Entity<InputState>,
     username: Entity<InputState>,
     password: Entity<InputState>,
     is_remember_password: bool,
+    is_production: bool,
+    is_read_only: bool,
 }
 
 impl CreateMySQLConnectionDialog {
-    pub fn db_name() -> &'static str {
-        "MySQL"
-    }
-    pub fn new(window: &mut Window, cx: &mut App) -> Self {
+    pub fn new(db_type: DatabaseType, window: &mut Window, cx: &mut App) -> Self {
         let placeholder = {
             let i18n = cx.global::<I18n>();
             i18n.t("connection.name-placeholder").to_string()
@@ -85,21 +84,25 @@ impl CreateMySQLConnectionDialog {
         };
         let password = cx.new(|cx| InputState::new(window, cx).placeholder(placeholder));
         Self {
+            db_type,
             name,
             host,
             port,
             username,
             password,
             is_remember_password: false,
+            is_production: false,
+            is_read_only: false,
         }
     }
 
-    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
-        cx.new(|cx| Self::new(window, cx))
+    pub fn view(db_type: DatabaseType, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(db_type, window, cx))
     }
 
-    pub fn open(window: &mut Window, cx: &mut App) {
-        let dialog_entity = Self::view(window, cx);
+    pub fn open(db_type: DatabaseType, window: &mut Window, cx: &mut App) {
+        let dialog_entity = Self::view(db_type, window, cx);
+        let db_name = db_type.to_string();
         window.open_dialog(cx, move |dialog, _, cx| {
             let dialog_entity_clone = dialog_entity.clone();
             let i18n = cx.global::<I18n>();
@@ -110,7 +113,7 @@ impl CreateMySQLConnectionDialog {
                 .h(px(530.))
                 .title(i18n.t_with(
                     "connection.create-new-connection",
-                    &[("db", Self::db_name())],
+                    &[("db", db_name.as_str())],
                 ))
                 .child(dialog_entity_clone.clone())
                 .footer(move |_, _, _, cx| {
@@ -160,17 +163,28 @@ impl CreateMySQLConnectionDialog {
                                         return;
                                     }
 
-                                    let uri = format!(
-                                        "mysql://{}:{}@{}:{}",
-                                        username, password, host, port
+                                    let db_type = this.db_type;
+                                    let config = ConnectionConfig::new(
+                                        "",
+                                        db_type,
+                                        Endpoint::Tcp(host, port),
+                                        false,
+                                        &username,
+                                        None,
                                     );
+                                    let password = Some(password);
                                     let app_state = cx.global_mut::<AppLoadingState>();
                                     let loading = app_state.loading.clone();
                                     Loading::open(&loading, cx);
                                     cx.spawn(async move |_, cx| {
-                                        let driver = MySqlDriver::new(uri);
-
-                                        let notification = match driver.test_connection().await {
+                                        let result = match DriverFactory::build(
+                                            &config,
+                                            password.as_deref(),
+                                        ) {
+                                            Ok(driver) => driver.test_connection().await,
+                                            Err(e) => Err(e),
+                                        };
+                                        let notification = match result {
                                             Ok(_) => Notification::success(connection_success),
                                             Err(e) => {
                                                 eprintln!("Connection Failed: {}", e);
@@ -219,6 +233,9 @@ impl CreateMySQLConnectionDialog {
                                         let username = read_value(&this.username);
                                         let password = read_value(&this.password);
                                         let is_remember_password = this.is_remember_password;
+                                        let is_production = this.is_production;
+                                        let is_read_only = this.is_read_only;
+                                        let db_type = this.db_type;
 
                                         let empty_input_message = {
                                             let i18n = cx.global::<I18n>();
@@ -248,12 +265,14 @@ impl CreateMySQLConnectionDialog {
                                         cx.spawn(async move |_, cx| {
                                             let config = ConnectionConfig::new(
                                                 &name,
-                                                DatabaseType::MySql,
+                                                db_type,
                                                 Endpoint::Tcp(host, port),
                                                 is_remember_password,
                                                 &username,
                                                 password_opt.as_ref().map(|p| p.len() as u8),
-                                            );
+                                            )
+                                            .with_production(is_production)
+                                            .with_read_only(is_read_only);
                                             let result = db_manager
                                                 .save_and_activate_connection(config, password_opt)
                                                 .await;
@@ -349,15 +368,35 @@ impl Render for CreateMySQLConnectionDialog {
                     .child(Input::new(&self.password)),
             )
             .child(
-                h_flex().gap_2().child(
-                    Checkbox::new("save-password")
-                        .checked(self.is_remember_password)
-                        .on_click(cx.listener(|this, checked, _, cx| {
-                            this.is_remember_password = *checked;
-                            cx.notify();
-                        }))
-                        .label(i18n.t("connection.save-password")),
-                ),
+                h_flex()
+                    .gap_4()
+                    .child(
+                        Checkbox::new("save-password")
+                            .checked(self.is_remember_password)
+                            .on_click(cx.listener(|this, checked, _, cx| {
+                                this.is_remember_password = *checked;
+                                cx.notify();
+                            }))
+                            .label(i18n.t("connection.save-password")),
+                    )
+                    .child(
+                        Checkbox::new("production")
+                            .checked(self.is_production)
+                            .on_click(cx.listener(|this, checked, _, cx| {
+                                this.is_production = *checked;
+                                cx.notify();
+                            }))
+                            .label(i18n.t("connection.production")),
+                    )
+                    .child(
+                        Checkbox::new("read-only")
+                            .checked(self.is_read_only)
+                            .on_click(cx.listener(|this, checked, _, cx| {
+                                this.is_read_only = *checked;
+                                cx.notify();
+                            }))
+                            .label(i18n.t("connection.read-only")),
+                    ),
             )
     }
 }
@@ -0,0 +1,323 @@
+use gpui::{px, App, AppContext, Entity, ParentElement, PathPromptOptions, Render, Styled, Window};
+use gpui_component::{
+    button::Button,
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputState},
+    label::Label,
+    notification::Notification,
+    v_flex, Sizable, WindowExt,
+};
+use std::path::PathBuf;
+
+use crate::{
+    core::I18n,
+    ui::{
+        components::Loading,
+        pages::PageRoute,
+        state::{AppConnectionTabsState, AppLoadingState, AppNotificationState, AppState},
+    },
+};
+use db_sight_core::{
+    ConnectionConfig, DBManager, DatabaseDriver, DatabaseType, Endpoint, SqliteDriver,
+};
+
+pub struct CreateSQLiteConnectionDialog {
+    name: Entity<InputState>,
+    path: Entity<InputState>,
+    is_production: bool,
+    is_read_only: bool,
+}
+
+impl CreateSQLiteConnectionDialog {
+    pub fn db_name() -> &'static str {
+        "SQLite"
+    }
+    pub fn new(window: &mut Window, cx: &mut App) -> Self {
+        let placeholder = {
+            let i18n = cx.global::<I18n>();
+            i18n.t("connection.name-placeholder").to_string()
+        };
+        let name = cx.new(|cx| InputState::new(window, cx).placeholder(placeholder));
+        let placeholder = {
+            let i18n = cx.global::<I18n>();
+            i18n.t("connection.file-path-placeholder").to_string()
+        };
+        let path = cx.new(|cx| InputState::new(window, cx).placeholder(placeholder));
+        Self {
+            name,
+            path,
+            is_production: false,
+            is_read_only: false,
+        }
+    }
+
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    pub fn open(window: &mut Window, cx: &mut App) {
+        let dialog_entity = Self::view(window, cx);
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let dialog_entity_clone = dialog_entity.clone();
+            let i18n = cx.global::<I18n>();
+
+            dialog
+                .overlay_closable(false)
+                .width(px(444.))
+                .h(px(340.))
+                .title(i18n.t_with(
+                    "connection.create-new-connection",
+                    &[("db", Self::db_name())],
+                ))
+                .child(dialog_entity_clone.clone())
+                .footer(move |_, _, _, cx| {
+                    let i18n = cx.global::<I18n>();
+                    let dialog_entity_footer = dialog_entity_clone.clone();
+                    let dialog_entity_footer_clone = dialog_entity_footer.clone();
+                    vec![
+                        Button::new("cancel-button")
+                            .small()
+                            .px_2()
+                            .label(i18n.t("connection.cancel"))
+                            .on_click(move |_, window, cx| window.close_dialog(cx)),
+                        Button::new("test-button")
+                            .px_2()
+                            .small()
+                            .label(i18n.t("connection.test-connection"))
+                            .on_click(move |_, window, cx| {
+                                cx.update_entity(&dialog_entity_footer.clone(), |this, cx| {
+                                    let (
+                                        connection_failed_message,
+                                        connection_success,
+                                        empty_input_message,
+                                    ) = {
+                                        let i18n = cx.global::<I18n>();
+                                        (
+                                            i18n.t("connection-error.connection-failed"),
+                                            i18n.t("connection.connection-success"),
+                                            i18n.t("connection-error.check-input"),
+                                        )
+                                    };
+                                    let path = cx.read_entity(&this.path, |input, _| {
+                                        input.value().trim().to_string()
+                                    });
+                                    if path.is_empty() {
+                                        window.push_notification(
+                                            Notification::error(empty_input_message),
+                                            cx,
+                                        );
+                                        return;
+                                    }
+
+                                    let app_state = cx.global_mut::<AppLoadingState>();
+                                    let loading = app_state.loading.clone();
+                                    Loading::open(&loading, cx);
+                                    cx.spawn(async move |_, cx| {
+                                        let driver = SqliteDriver::new(path);
+
+                                        let notification = match driver.test_connection().await {
+                                            Ok(_) => Notification::success(connection_success),
+                                            Err(e) => {
+                                                eprintln!("Connection Failed: {}", e);
+                                                Notification::error(connection_failed_message)
+                                            }
+                                        };
+                                        // Show notification & Hide loading
+                                        cx.update(|app| {
+                                            app.global_mut::<AppNotificationState>()
+                                                .push(notification)
+                                        })?;
+                                        cx.update(|app| Loading::hide(&loading, app))?;
+
+                                        Ok::<_, anyhow::Error>(())
+                                    })
+                                    .detach();
+                                })
+                            }),
+                        Button::new("save-button")
+                            .small()
+                            .px_2()
+                            .label(i18n.t("connection.save-connection"))
+                            .on_click(move |_, window, cx| {
+                                cx.update_entity(
+                                    &dialog_entity_footer_clone.clone(),
+                                    |this, cx| {
+                                        let read_value = |entity: &Entity<InputState>| -> String {
+                                            cx.read_entity(entity, |input, _| {
+                                                input.value().trim().to_string()
+                                            })
+                                        };
+
+                                        let name = read_value(&this.name);
+                                        let path = read_value(&this.path);
+                                        let is_production = this.is_production;
+                                        let is_read_only = this.is_read_only;
+
+                                        let empty_input_message = {
+                                            let i18n = cx.global::<I18n>();
+                                            i18n.t("connection-error.check-input")
+                                        };
+
+                                        if name.is_empty() || path.is_empty() {
+                                            window.push_notification(
+                                                Notification::error(empty_input_message),
+                                                cx,
+                                            );
+                                            return;
+                                        }
+
+                                        let app_state = cx.global_mut::<AppLoadingState>();
+                                        let loading = app_state.loading.clone();
+                                        Loading::open(&loading, cx);
+
+                                        let db_manager = cx.global::<DBManager>().clone();
+
+                                        cx.spawn(async move |_, cx| {
+                                            // Local files need no credentials
+                                            let config = ConnectionConfig::new(
+                                                &name,
+                                                DatabaseType::Sqlite,
+                                                Endpoint::Unix(PathBuf::from(path)),
+                                                false,
+                                                "",
+                                                None,
+                                            )
+                                            .with_production(is_production)
+                                            .with_read_only(is_read_only);
+                                            let result = db_manager
+                                                .save_and_activate_connection(config, None)
+                                                .await;
+
+                                            match result {
+                                                Ok(saved_config) => {
+                                                    cx.update(|app| {
+                                                        // Switch to DatabaseColumns page on successful connection
+                                                        app.global_mut::<AppState>().current_page =
+                                                            PageRoute::DatabaseColumns;
+
+                                                        app.windows().iter().for_each(|window| {
+                                                            let tabs_entity = app
+                                                                .global::<AppConnectionTabsState>()
+                                                                .clone()
+                                                                .connection_tabs;
+                                                            app.update_entity(
+                                                                &tabs_entity,
+                                                                |tabs, cx| {
+                                                                    tabs.add_config(
+                                                                        saved_config.clone(),
+                                                                        cx,
+                                                                    );
+                                                                    cx.notify();
+                                                                },
+                                                            );
+                                                            window
+                                                                .update(app, |_, window, cx| {
+                                                                    window.close_dialog(cx);
+                                                                })
+                                                                .ok();
+                                                        });
+                                                    })?;
+                                                }
+                                                Err(e) => {
+                                                    cx.update(|app| {
+                                                        eprintln!("Save Connection Failed: {}", e);
+                                                        let fail_message = app.global::<I18n>().t(
+                                                        "connection-error.failed-save-connection",
+                                                    );
+                                                        app.global_mut::<AppNotificationState>()
+                                                            .push(Notification::error(fail_message))
+                                                    })?;
+                                                }
+                                            };
+
+                                            cx.update(|app| Loading::hide(&loading, app))?;
+                                            Ok::<_, anyhow::Error>(())
+                                        })
+                                        .detach();
+                                    },
+                                );
+                            }),
+                    ]
+                })
+        })
+    }
+
+    /// Open the platform file picker and fill the path input with the chosen file
+    fn browse_file(&mut self, window: &mut Window, cx: &mut gpui::Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+        let path_state = self.path.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            if let Ok(Ok(Some(paths))) = paths.await {
+                if let Some(path) = paths.into_iter().next() {
+                    path_state.update_in(cx, |input, window, cx| {
+                        input.set_value(path.display().to_string(), window, cx);
+                    })?;
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+}
+
+impl Render for CreateSQLiteConnectionDialog {
+    fn render(&mut self, _: &mut Window, cx: &mut gpui::Context<Self>) -> impl gpui::IntoElement {
+        let i18n = cx.global::<I18n>();
+        v_flex()
+            .gap_3()
+            .child(
+                v_flex()
+                    .mt_3()
+                    .gap_2()
+                    .child(Label::new(i18n.t("connection.name")))
+                    .child(Input::new(&self.name)),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(Label::new(i18n.t("connection.file-path")))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Input::new(&self.path).flex_1())
+                            .child(
+                                Button::new("browse-button")
+                                    .small()
+                                    .px_2()
+                                    .label(i18n.t("connection.browse"))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.browse_file(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_4()
+                    .child(
+                        Checkbox::new("production")
+                            .checked(self.is_production)
+                            .on_click(cx.listener(|this, checked, _, cx| {
+                                this.is_production = *checked;
+                                cx.notify();
+                            }))
+                            .label(i18n.t("connection.production")),
+                    )
+                    .child(
+                        Checkbox::new("read-only")
+                            .checked(self.is_read_only)
+                            .on_click(cx.listener(|this, checked, _, cx| {
+                                this.is_read_only = *checked;
+                                cx.notify();
+                            }))
+                            .label(i18n.t("connection.read-only")),
+                    ),
+            )
+    }
+}
@@ -1,3 +1,8 @@
 pub mod create_connection_dialog;
+pub mod create_mongo_connection_dialog;
+pub mod create_mssql_connection_dialog;
 pub mod create_mysql_connection_dialog;
+pub mod create_postgres_connection_dialog;
+pub mod create_redis_connection_dialog;
+pub mod create_sqlite_connection_dialog;
 pub mod import_url_dialog;
@@ -0,0 +1,210 @@
+use db_sight_core::{events::SelectedKeyChanged, DBManager, KeyNode};
+use gpui::{
+    div, px, App, InteractiveElement, ParentElement, SharedString, StatefulInteractiveElement,
+    Styled, TextOverflow,
+};
+use gpui_component::{
+    list::{ListDelegate, ListItem, ListState},
+    tooltip::Tooltip,
+    IndexPath,
+};
+use uuid::Uuid;
+
+use crate::ui::state::AppTableState;
+
+/// Indentation of one tree level (pixels)
+const TREE_INDENT: f32 = 12.0;
+
+#[derive(Debug, Clone)]
+struct KeyTreeItem {
+    schema: String,
+    node: KeyNode,
+    depth: usize,
+    expanded: bool,
+}
+
+/// Key-space tree of a key-value connection, logical databases at the root
+pub struct ListKeys {
+    connection_id: Option<Uuid>,
+    items: Vec<KeyTreeItem>,
+    selected_index: Option<IndexPath>,
+}
+
+impl ListKeys {
+    pub fn new(connection_id: Option<Uuid>, schemas: Vec<String>) -> Self {
+        let mut list = Self {
+            connection_id: None,
+            items: Vec::new(),
+            selected_index: None,
+        };
+        list.set_schemas(connection_id, schemas);
+        list
+    }
+
+    pub fn set_schemas(&mut self, connection_id: Option<Uuid>, schemas: Vec<String>) {
+        self.connection_id = connection_id;
+        self.selected_index = None;
+        self.items = schemas
+            .into_iter()
+            .map(|schema| KeyTreeItem {
+                node: KeyNode {
+                    label: schema.clone(),
+                    key: String::new(),
+                    is_prefix: true,
+                    key_count: 0,
+                },
+                schema,
+                depth: 0,
+                expanded: false,
+            })
+            .collect();
+    }
+
+    fn position_of(&self, schema: &str, key: &str, depth: usize) -> Option<usize> {
+        self.items
+            .iter()
+            .position(|item| item.schema == schema && item.node.key == key && item.depth == depth)
+    }
+
+    fn collapse(&mut self, row: usize) {
+        let depth = self.items[row].depth;
+        let end = self.items[row + 1..]
+            .iter()
+            .position(|item| item.depth <= depth)
+            .map(|offset| row + 1 + offset)
+            .unwrap_or(self.items.len());
+        self.items.drain(row + 1..end);
+        self.items[row].expanded = false;
+    }
+
+    fn insert_children(&mut self, row: usize, nodes: Vec<KeyNode>) {
+        let parent = &self.items[row];
+        let children: Vec<KeyTreeItem> = nodes
+            .into_iter()
+            .map(|node| KeyTreeItem {
+                schema: parent.schema.clone(),
+                node,
+                depth: parent.depth + 1,
+                expanded: false,
+            })
+            .collect();
+        self.items.splice(row + 1..row + 1, children);
+        self.items[row].expanded = true;
+    }
+
+    fn expand(&mut self, row: usize, cx: &mut gpui::Context<ListState<Self>>) {
+        let Some(connection_id) = self.connection_id else {
+            return;
+        };
+        let item = self.items[row].clone();
+        let db_manager = cx.global::<DBManager>().clone();
+
+        cx.spawn(async move |this, cx| {
+            let Some(driver) = db_manager.get_connection(&connection_id.to_string()).await else {
+                return Ok(());
+            };
+            let Some(key_value) = driver.as_key_value() else {
+                return Ok(());
+            };
+
+            match key_value.list_key_nodes(&item.schema, &item.node.key).await {
+                Ok(nodes) => {
+                    this.update(cx, |state, cx| {
+                        let delegate = state.delegate_mut();
+                        // Rows may have moved while loading, look the parent up again
+                        if let Some(row) =
+                            delegate.position_of(&item.schema, &item.node.key, item.depth)
+                        {
+                            if !delegate.items[row].expanded {
+                                delegate.insert_children(row, nodes);
+                            }
+                        }
+                        cx.notify();
+                    })?;
+                }
+                Err(e) => {
+                    eprintln!("Failed to list keys under {}: {}", item.node.key, e);
+                }
+            }
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+}
+
+impl ListDelegate for ListKeys {
+    type Item = ListItem;
+
+    fn items_count(&self, _section: usize, _cx: &App) -> usize {
+        self.items.len()
+    }
+
+    fn render_item(
+        &self,
+        ix: IndexPath,
+        _window: &mut gpui::Window,
+        _cx: &mut App,
+    ) -> Option<Self::Item> {
+        self.items.get(ix.row).map(|item| {
+            let fullname = if item.node.key.is_empty() {
+                item.schema.clone()
+            } else {
+                item.node.key.clone()
+            };
+            let id = format!("key-{}-{}-{}", item.schema, item.depth, fullname);
+            let showname = if item.node.is_prefix {
+                let marker = if item.expanded { "▾" } else { "▸" };
+                if item.depth == 0 {
+                    format!("{} {}", marker, item.node.label)
+                } else {
+                    format!("{} {} ({})", marker, item.node.label, item.node.key_count)
+                }
+            } else {
+                item.node.label.clone()
+            };
+            ListItem::new(ix)
+                .rounded_md()
+                .child(
+                    div()
+                        .id(SharedString::from(id))
+                        .pl(px(item.depth as f32 * TREE_INDENT))
+                        .child(showname)
+                        .text_overflow(TextOverflow::Truncate("...".into()))
+                        .tooltip(move |window, cx| {
+                            Tooltip::new(fullname.clone()).build(window, cx)
+                        }),
+                )
+                .selected(Some(ix) == self.selected_index)
+        })
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: Option<IndexPath>,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<ListState<Self>>,
+    ) {
+        self.selected_index = ix;
+        if let Some(index) = ix {
+            if let Some(item) = self.items.get(index.row).cloned() {
+                if item.node.is_prefix {
+                    if item.expanded {
+                        self.collapse(index.row);
+                    } else {
+                        self.expand(index.row, cx);
+                    }
+                } else {
+                    let global_state = cx.global::<AppTableState>().state.clone();
+                    global_state.update(cx, |_, cx| {
+                        cx.emit(SelectedKeyChanged {
+                            schema: item.schema,
+                            key: item.node.key,
+                        });
+                    });
+                }
+            }
+        }
+        cx.notify();
+    }
+}
@@ -1,31 +1,311 @@
-use db_sight_core::events::SelectedTableChanged;
+use std::collections::HashMap;
+
+use db_sight_core::{
+    events::{ObjectDdlRequested, SelectedTableChanged},
+    DBError, DBManager, ObjectKind, ObjectRef, SchemaBrowser, TableInfo,
+};
 use gpui::{
-    div, App, InteractiveElement, ParentElement, SharedString, StatefulInteractiveElement, Styled,
-    TextOverflow,
+    div, prelude::FluentBuilder, px, App, InteractiveElement, ParentElement, SharedString,
+    StatefulInteractiveElement, Styled, TextOverflow,
 };
 use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex,
     list::{ListDelegate, ListItem, ListState},
     tooltip::Tooltip,
-    IndexPath,
+    ActiveTheme, IndexPath, Sizable,
 };
+use uuid::Uuid;
+
+use crate::{core::I18n, ui::state::AppTableState};
+
+/// Indentation of one tree level (pixels)
+const TREE_INDENT: f32 = 12.0;
+
+/// Kinds listed in the full object tree, in order
+pub const ALL_OBJECT_KINDS: [ObjectKind; 7] = [
+    ObjectKind::Table,
+    ObjectKind::View,
+    ObjectKind::Sequence,
+    ObjectKind::Procedure,
+    ObjectKind::Function,
+    ObjectKind::Trigger,
+    ObjectKind::Event,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TableTreeNode {
+    Schema,
+    /// Objects of one kind in the schema
+    Group(ObjectKind),
+    Object {
+        kind: ObjectKind,
+        name: String,
+        system_versioned: bool,
+    },
+}
+
+/// Object listed under a kind
+#[derive(Debug, Clone)]
+struct ListedObject {
+    name: String,
+    /// MariaDB table keeping the history of its rows, marked in the tree
+    system_versioned: bool,
+}
+
+impl ListedObject {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            system_versioned: false,
+        }
+    }
+}
 
-use crate::ui::state::AppTableState;
+#[derive(Debug, Clone)]
+struct TableTreeItem {
+    schema: String,
+    node: TableTreeNode,
+    depth: usize,
+    expanded: bool,
+}
 
+/// Object tree of a SQL connection: schemas, the kinds of objects in each and the
+/// objects. A tree of a single kind leaves out the kind level.
 pub struct ListTables {
-    items: Vec<String>,
+    connection_id: Option<Uuid>,
+    kinds: Vec<ObjectKind>,
+    items: Vec<TableTreeItem>,
+    /// Objects of each kind, per schema, loaded when first expanded
+    objects: HashMap<String, Vec<(ObjectKind, Vec<ListedObject>)>>,
     selected_index: Option<IndexPath>,
 }
 
 impl ListTables {
-    pub fn new(items: Vec<String>) -> Self {
+    pub fn new(kinds: &[ObjectKind]) -> Self {
         Self {
-            items,
-            selected_index: Some(IndexPath::new(0)),
+            connection_id: None,
+            kinds: kinds.to_vec(),
+            items: Vec::new(),
+            objects: HashMap::new(),
+            selected_index: None,
+        }
+    }
+
+    /// Show the schemas of a connection, expanding the `last` browsed one, or the only
+    /// one if there is a single
+    pub fn set_schemas(
+        &mut self,
+        connection_id: Option<Uuid>,
+        schemas: Vec<String>,
+        last: Option<&str>,
+        cx: &mut gpui::Context<ListState<Self>>,
+    ) {
+        self.connection_id = connection_id;
+        self.selected_index = None;
+        self.objects.clear();
+        self.items = schemas
+            .into_iter()
+            .map(|schema| TableTreeItem {
+                schema,
+                node: TableTreeNode::Schema,
+                depth: 0,
+                expanded: false,
+            })
+            .collect();
+        let row = match last.and_then(|last| self.position_of(last, &TableTreeNode::Schema)) {
+            Some(row) => Some(row),
+            None if self.items.len() == 1 => Some(0),
+            None => None,
+        };
+        if let Some(row) = row {
+            self.expand(row, cx);
         }
     }
 
-    pub fn set_items(&mut self, items: Vec<String>) {
-        self.items = items;
+    /// Remember the schema browsed, to expand it when the connection is opened again
+    fn remember_schema(&self, schema: String, cx: &mut gpui::Context<ListState<Self>>) {
+        let Some(connection_id) = self.connection_id else {
+            return;
+        };
+        let db_manager = cx.global::<DBManager>().clone();
+        cx.spawn(async move |_, _| {
+            if let Err(e) = db_manager.set_last_schema(&connection_id, &schema).await {
+                eprintln!("Failed to save the last schema: {}", e);
+            }
+        })
+        .detach();
+    }
+
+    /// Object an item stands for, `None` for schemas and groups
+    fn object_ref(&self, item: &TableTreeItem) -> Option<ObjectRef> {
+        match (&item.node, self.connection_id) {
+            (TableTreeNode::Object { kind, name, .. }, Some(connection_id)) => Some(
+                ObjectRef::new(connection_id, item.schema.clone(), name.clone(), *kind),
+            ),
+            _ => None,
+        }
+    }
+
+    fn position_of(&self, schema: &str, node: &TableTreeNode) -> Option<usize> {
+        self.items
+            .iter()
+            .position(|item| item.schema == schema && &item.node == node)
+    }
+
+    fn collapse(&mut self, row: usize) {
+        let depth = self.items[row].depth;
+        let end = self.items[row + 1..]
+            .iter()
+            .position(|item| item.depth <= depth)
+            .map(|offset| row + 1 + offset)
+            .unwrap_or(self.items.len());
+        self.items.drain(row + 1..end);
+        self.items[row].expanded = false;
+    }
+
+    /// Insert the children of an expanded row from the loaded objects of its schema
+    fn insert_children(&mut self, row: usize) {
+        let parent = self.items[row].clone();
+        let Some(objects) = self.objects.get(&parent.schema) else {
+            return;
+        };
+        let object_items = |kind: ObjectKind, listed: &[ListedObject]| {
+            listed
+                .iter()
+                .map(|object| TableTreeItem {
+                    schema: parent.schema.clone(),
+                    node: TableTreeNode::Object {
+                        kind,
+                        name: object.name.clone(),
+                        system_versioned: object.system_versioned,
+                    },
+                    depth: parent.depth + 1,
+                    expanded: false,
+                })
+                .collect::<Vec<_>>()
+        };
+        let children: Vec<TableTreeItem> = match &parent.node {
+            TableTreeNode::Schema if self.kinds.len() == 1 => objects
+                .iter()
+                .flat_map(|(kind, names)| object_items(*kind, names))
+                .collect(),
+            TableTreeNode::Schema => objects
+                .iter()
+                .filter(|(_, names)| !names.is_empty())
+                .map(|(kind, _)| TableTreeItem {
+                    schema: parent.schema.clone(),
+                    node: TableTreeNode::Group(*kind),
+                    depth: parent.depth + 1,
+                    expanded: false,
+                })
+                .collect(),
+            TableTreeNode::Group(kind) => objects
+                .iter()
+                .filter(|(k, _)| k == kind)
+                .flat_map(|(kind, names)| object_items(*kind, names))
+                .collect(),
+            TableTreeNode::Object { .. } => Vec::new(),
+        };
+        self.items.splice(row + 1..row + 1, children);
+        self.items[row].expanded = true;
+    }
+
+    fn expand(&mut self, row: usize, cx: &mut gpui::Context<ListState<Self>>) {
+        let item = self.items[row].clone();
+        if self.objects.contains_key(&item.schema) {
+            self.insert_children(row);
+            return;
+        }
+        let Some(connection_id) = self.connection_id else {
+            return;
+        };
+        let kinds = self.kinds.clone();
+        let db_manager = cx.global::<DBManager>().clone();
+
+        cx.spawn(async move |this, cx| {
+            let Some(driver) = db_manager.get_connection(&connection_id.to_string()).await else {
+                return Ok(());
+            };
+            let Some(browser) = driver.as_schema_browser() else {
+                return Ok(());
+            };
+
+            let mut objects = Vec::new();
+            let mut tables = None;
+            for kind in kinds {
+                match list_objects(browser, &item.schema, kind, &mut tables).await {
+                    Ok(names) => objects.push((kind, names)),
+                    Err(e) => {
+                        eprintln!("Failed to list {} of schema {}: {}", kind, item.schema, e);
+                        objects.push((kind, Vec::new()));
+                    }
+                }
+            }
+
+            this.update(cx, |state, cx| {
+                let delegate = state.delegate_mut();
+                delegate.objects.insert(item.schema.clone(), objects);
+                // Rows may have moved while loading, look the schema up again
+                if let Some(row) = delegate.position_of(&item.schema, &item.node) {
+                    if !delegate.items[row].expanded {
+                        delegate.insert_children(row);
+                    }
+                }
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+}
+
+/// Objects of one kind in a schema. Tables and sequences come from the same listing,
+/// kept in `tables` once loaded.
+async fn list_objects(
+    browser: &dyn SchemaBrowser,
+    schema: &str,
+    kind: ObjectKind,
+    tables: &mut Option<Vec<TableInfo>>,
+) -> Result<Vec<ListedObject>, DBError> {
+    let names = match kind {
+        ObjectKind::Table | ObjectKind::Sequence => {
+            let tables = match tables {
+                Some(tables) => tables,
+                None => tables.insert(browser.list_tables(schema).await?),
+            };
+            return Ok(tables
+                .iter()
+                .filter(|table| match kind {
+                    ObjectKind::Sequence => table.is_sequence(),
+                    _ => !table.is_view() && !table.is_sequence(),
+                })
+                .map(|table| ListedObject {
+                    name: table.name.clone(),
+                    system_versioned: table.is_system_versioned(),
+                })
+                .collect());
+        }
+        ObjectKind::View => browser.list_views(schema).await?,
+        ObjectKind::Procedure => browser.list_procedures(schema).await?,
+        ObjectKind::Function => browser.list_functions(schema).await?,
+        ObjectKind::Trigger => browser.list_triggers(schema).await?,
+        ObjectKind::Event => browser.list_events(schema).await?,
+    };
+    Ok(names.into_iter().map(ListedObject::new).collect())
+}
+
+/// Label of the group of objects of a kind
+fn group_label(kind: ObjectKind) -> &'static str {
+    match kind {
+        ObjectKind::Table => "database.tables",
+        ObjectKind::View => "database.views",
+        ObjectKind::Procedure => "database.procedures",
+        ObjectKind::Function => "database.functions",
+        ObjectKind::Trigger => "database.triggers",
+        ObjectKind::Event => "database.events",
+        ObjectKind::Sequence => "database.sequences",
     }
 }
 
@@ -40,21 +320,87 @@ impl ListDelegate for ListTables {
         &self,
         ix: IndexPath,
         _window: &mut gpui::Window,
-        _cx: &mut App,
+        cx: &mut App,
     ) -> Option<Self::Item> {
+        let i18n = cx.global::<I18n>();
+        let ddl_label = i18n.t("ddl.show");
+        let versioned_label = i18n.t("database.system-versioned");
+        let muted = cx.theme().muted_foreground;
         self.items.get(ix.row).map(|item| {
-            let fullname = item.clone();
-            let id = format!("table-name-{}", fullname.clone());
-            let showname = fullname.clone();
+            let marker = if item.expanded { "▾" } else { "▸" };
+            let (fullname, showname) = match &item.node {
+                TableTreeNode::Schema => {
+                    (item.schema.clone(), format!("{} {}", marker, item.schema))
+                }
+                TableTreeNode::Group(kind) => {
+                    let label = i18n.t(group_label(*kind));
+                    let count = self
+                        .objects
+                        .get(&item.schema)
+                        .and_then(|objects| objects.iter().find(|(k, _)| k == kind))
+                        .map(|(_, names)| names.len())
+                        .unwrap_or_default();
+                    (label.clone(), format!("{} {} ({})", marker, label, count))
+                }
+                TableTreeNode::Object { name, .. } => {
+                    (format!("{}.{}", item.schema, name), name.clone())
+                }
+            };
+            let system_versioned = matches!(
+                item.node,
+                TableTreeNode::Object {
+                    system_versioned: true,
+                    ..
+                }
+            );
+            let id = format!("table-tree-{}-{}-{}", item.schema, item.depth, showname);
+            // Tables and views also have rows, their definition is a click away
+            let ddl_object = self.object_ref(item).filter(|object| object.has_rows());
             ListItem::new(ix)
                 .rounded_md()
                 .child(
-                    div()
-                        .id(SharedString::from(id))
-                        .child(showname)
-                        .text_overflow(TextOverflow::Truncate("...".into()))
-                        .tooltip(move |window, cx| {
-                            Tooltip::new(fullname.clone()).build(window, cx)
+                    h_flex()
+                        .w_full()
+                        .gap_1()
+                        .justify_between()
+                        .pl(px(item.depth as f32 * TREE_INDENT))
+                        .child(
+                            div()
+                                .id(SharedString::from(id))
+                                .flex_1()
+                                .min_w_0()
+                                .child(showname)
+                                .text_overflow(TextOverflow::Truncate("...".into()))
+                                .tooltip(move |window, cx| {
+                                    Tooltip::new(fullname.clone()).build(window, cx)
+                                }),
+                        )
+                        .when(system_versioned, |this| {
+                            this.child(
+                                div()
+                                    .text_xs()
+                                    .text_color(muted)
+                                    .child(versioned_label.clone()),
+                            )
+                        })
+                        .when_some(ddl_object, |this, object| {
+                            this.child(
+                                Button::new(SharedString::from(format!("table-ddl-{}", object)))
+                                    .xsmall()
+                                    .ghost()
+                                    .label(ddl_label)
+                                    .on_click(move |_, _, cx| {
+                                        // Keep the click from selecting the item as well
+                                        cx.stop_propagation();
+                                        let global_state =
+                                            cx.global::<AppTableState>().state.clone();
+                                        global_state.update(cx, |_, cx| {
+                                            cx.emit(ObjectDdlRequested {
+                                                object: object.clone(),
+                                            });
+                                        });
+                                    }),
+                            )
                         }),
                 )
                 .selected(Some(ix) == self.selected_index)
@@ -69,13 +415,33 @@ impl ListDelegate for ListTables {
     ) {
         self.selected_index = ix;
         if let Some(index) = ix {
-            if let Some(name) = self.items.get(index.row) {
-                let name = name.clone();
-                let global_state = cx.global::<AppTableState>().state.clone();
-                global_state.update(cx, |state, cx| {
-                    state.selected_table = Some(name.clone());
-                    cx.emit(SelectedTableChanged { table_name: name });
-                });
+            if let Some(item) = self.items.get(index.row).cloned() {
+                match self.object_ref(&item) {
+                    None => {
+                        if item.expanded {
+                            self.collapse(index.row);
+                        } else {
+                            if item.node == TableTreeNode::Schema {
+                                self.remember_schema(item.schema.clone(), cx);
+                            }
+                            self.expand(index.row, cx);
+                        }
+                    }
+                    Some(object) if object.has_rows() => {
+                        let global_state = cx.global::<AppTableState>().state.clone();
+                        global_state.update(cx, |state, cx| {
+                            state.selected_table = Some(object.clone());
+                            cx.emit(SelectedTableChanged { object });
+                        });
+                    }
+                    // Routines, triggers and events have no rows, show their definition
+                    Some(object) => {
+                        let global_state = cx.global::<AppTableState>().state.clone();
+                        global_state.update(cx, |_, cx| {
+                            cx.emit(ObjectDdlRequested { object });
+                        });
+                    }
+                }
             }
         }
         cx.notify();
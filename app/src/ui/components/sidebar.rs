@@ -5,6 +5,7 @@ use gpui::{
     StatefulInteractiveElement, Styled, TextOverflow, Window,
 };
 use gpui_component::{
+    checkbox::Checkbox,
     h_flex,
     label::Label,
     list::{List, ListState},
@@ -14,21 +15,23 @@ use gpui_component::{
     v_flex, Collapsible, Icon, Side, StyledExt, ThemeMode,
 };
 
-use keyring::Entry;
-use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
     core::I18n,
     ui::{
-        components::list_tables::ListTables,
+        components::{
+            list_keys::ListKeys,
+            list_tables::{ListTables, ALL_OBJECT_KINDS},
+        },
+        pages::PageRoute,
         state::{AppConnectionTabsState, AppState},
         windows::SwitchThemeMode,
     },
 };
 use db_sight_core::{
     events::{ActiveConnectionsChanged, SelectedConnectionChanged},
-    ConnectionConfig, DBManager, DatabaseDriver, MySqlDriver, TableInfo,
+    ConnectionConfig, DBManager, DriverCapabilities, ObjectKind,
 };
 
 pub struct SideBar {
@@ -69,7 +72,8 @@ impl SideBar {
                             if let Some(conn) = &new_connection {
                                 content.load_tables(conn.id, cx);
                             } else {
-                                content.tables.clear();
+                                content.schemas.clear();
+                                content.key_schemas.clear();
                                 content.sync_list_state(cx);
                             }
                         });
@@ -197,10 +201,22 @@ impl Render for SideBar {
 pub struct SidebarContent {
     collapsed: bool,
     connection: Option<ConnectionConfig>,
-    tables: Vec<TableInfo>,
+    /// Schemas of a SQL connection, browsed as an object tree
+    schemas: Vec<String>,
+    /// Schema browsed last on the connection, expanded when the tree is shown
+    last_schema: Option<String>,
+    /// List the catalog schemas of the server too, see `DatabaseType::is_system_schema`
+    show_system_schemas: bool,
+    /// Logical databases of a key-value connection, browsed as a key tree
+    key_schemas: Vec<String>,
+    /// What the connected driver supports, decides which lists are shown
+    capabilities: DriverCapabilities,
     loading_tables: bool,
     selected_tab: usize,
     list_state: Option<Entity<ListState<ListTables>>>,
+    /// Tree of the views only, for the views tab
+    view_list_state: Option<Entity<ListState<ListTables>>>,
+    key_list_state: Option<Entity<ListState<ListKeys>>>,
 }
 
 impl SidebarContent {
@@ -208,10 +224,16 @@ impl SidebarContent {
         let mut content = Self {
             collapsed: false,
             connection: connection.clone(),
-            tables: Vec::new(),
+            schemas: Vec::new(),
+            last_schema: None,
+            show_system_schemas: false,
+            key_schemas: Vec::new(),
+            capabilities: DriverCapabilities::default(),
             loading_tables: false,
             selected_tab: 0,
             list_state: None,
+            view_list_state: None,
+            key_list_state: None,
         };
 
         // If a connection exists, load table list asynchronously
@@ -246,10 +268,13 @@ impl SidebarContent {
                     });
 
                     this.connection = new_connection.clone();
+                    // Switching connections shows its tables again
+                    this.selected_tab = 0;
                     if let Some(conn) = &new_connection {
                         this.load_tables(conn.id, cx);
                     } else {
-                        this.tables.clear();
+                        this.schemas.clear();
+                        this.key_schemas.clear();
                     }
                     this.sync_list_state(cx);
                     cx.notify();
@@ -262,103 +287,117 @@ impl SidebarContent {
     }
 
     fn sync_list_state(&mut self, cx: &mut Context<Self>) {
-        if let Some(list_state) = &self.list_state {
-            let items: Vec<String> = self.tables.iter().map(|t| t.name.clone()).collect();
+        let connection_id = self.connection.as_ref().map(|c| c.id);
+        for list_state in [&self.list_state, &self.view_list_state]
+            .into_iter()
+            .flatten()
+        {
+            let schemas = self.visible_schemas();
+            let last_schema = self.last_schema.clone();
             list_state.update(cx, |state, cx| {
-                state.delegate_mut().set_items(items);
+                state.delegate_mut().set_schemas(
+                    connection_id,
+                    schemas,
+                    last_schema.as_deref(),
+                    cx,
+                );
+                cx.notify();
+            });
+        }
+        if let Some(key_list_state) = &self.key_list_state {
+            let schemas = self.key_schemas.clone();
+            key_list_state.update(cx, |state, cx| {
+                state.delegate_mut().set_schemas(connection_id, schemas);
                 cx.notify();
             });
         }
     }
 
+    /// Schemas listed in the tree, without the system ones unless asked for
+    fn visible_schemas(&self) -> Vec<String> {
+        let Some(connection) = &self.connection else {
+            return Vec::new();
+        };
+        self.schemas
+            .iter()
+            .filter(|schema| {
+                self.show_system_schemas || !connection.db_type.is_system_schema(schema)
+            })
+            .cloned()
+            .collect()
+    }
+
     fn load_tables(&mut self, connection_id: Uuid, cx: &mut Context<Self>) {
         if self.loading_tables {
             return;
         }
 
         self.loading_tables = true;
-        self.tables.clear();
+        self.schemas.clear();
+        self.last_schema = None;
+        self.key_schemas.clear();
+        self.capabilities = DriverCapabilities::default();
         cx.notify();
 
         let db_manager = cx.global::<DBManager>().clone();
-        let connection_id_str = connection_id.to_string();
         let entity = cx.entity().clone();
 
         cx.spawn(async move |_, cx| {
-            // Try to reuse existing driver; otherwise build and connect one.
-            let mut driver = db_manager.get_connection(&connection_id_str).await;
-
-            if driver.is_none() {
-                // Fetch connection config to build URI
-                let config_opt = db_manager.get_config_by_id(&connection_id).await;
-                if let Some(config) = config_opt {
-                    // Only MySQL is supported right now
-                    if let db_sight_core::Endpoint::Tcp(host, port) = &config.endpoint {
-                        // Pull password from keyring if saved
-                        let password = if config.saved_password_len.is_some() {
-                            match Entry::new("db-sight", &config.id.to_string()) {
-                                Ok(entry) => entry.get_password().ok(),
-                                Err(_) => None,
-                            }
-                        } else {
-                            None
-                        };
-
-                        if let Some(pwd) = password {
-                            let uri =
-                                format!("mysql://{}:{}@{}:{}", config.username, pwd, host, port);
-                            let mut mysql_driver = MySqlDriver::new(uri);
-                            if mysql_driver.connect().await.is_ok() {
-                                let arc = Arc::new(mysql_driver);
-                                db_manager
-                                    .add_connection(connection_id_str.clone(), arc.clone())
-                                    .await;
-                                driver = Some(arc);
-                            } else {
-                                eprintln!("Connect failed, will not fetch tables");
-                            }
-                        } else {
-                            eprintln!("Password missing, please prompt user to input.");
-                        }
-                    } else {
-                        eprintln!("Unsupported endpoint type for table listing");
-                    }
-                } else {
-                    eprintln!("No config found for connection {}", connection_id_str);
+            // Reuse the live driver or connect the saved configuration
+            let driver = match db_manager.connect(&connection_id).await {
+                Ok(driver) => Some(driver),
+                Err(e) => {
+                    eprintln!("Connect failed, will not fetch tables: {}", e);
+                    None
                 }
-            }
+            };
 
             if let Some(driver) = driver {
-                // Fetch all schemas
-                match driver.list_schemas().await {
-                    Ok(schemas) => {
-                        let mut all_tables = Vec::new();
-
-                        // Traverse all schemas to fetch table list
-                        for schema in schemas {
-                            if matches!(
-                                schema.name.to_lowercase().as_str(),
-                                "information_schema" | "mysql" | "performance_schema" | "sys"
-                            ) {
-                                continue;
-                            }
+                let capabilities = driver.capabilities();
+                cx.update_entity(&entity, |content: &mut Self, cx| {
+                    content.capabilities = capabilities;
+                    cx.notify();
+                })?;
 
-                            match driver.list_tables(&schema.name).await {
-                                Ok(tables) => {
-                                    all_tables.extend(tables);
-                                }
-                                Err(e) => {
-                                    eprintln!(
-                                        "Failed to list tables for schema {}: {}",
-                                        schema.name, e
-                                    );
-                                }
-                            }
+                // Key-value stores are browsed lazily through the key tree
+                if let Some(key_value) = driver.as_key_value() {
+                    let key_schemas = match key_value.list_databases().await {
+                        Ok(schemas) => schemas.into_iter().map(|s| s.name).collect(),
+                        Err(e) => {
+                            eprintln!("Failed to list schemas: {}", e);
+                            Vec::new()
                         }
+                    };
+                    cx.update_entity(&entity, |content: &mut Self, cx| {
+                        content.key_schemas = key_schemas;
+                        content.loading_tables = false;
+                        content.sync_list_state(cx);
+                    })?;
+                    return Ok(());
+                }
 
-                        // Update UI with loaded tables
+                let Some(browser) = driver.as_schema_browser() else {
+                    cx.update_entity(&entity, |content: &mut Self, _| {
+                        content.loading_tables = false;
+                    })?;
+                    return Ok(());
+                };
+
+                // The saved configuration is current, the one of the tab may not be
+                let last_schema = db_manager
+                    .get_config_by_id(&connection_id)
+                    .await
+                    .and_then(|config| config.last_schema);
+
+                // Fetch all schemas, their objects are listed when expanded
+                match browser.list_schemas().await {
+                    Ok(schemas) => {
+                        let schemas = schemas.into_iter().map(|schema| schema.name).collect();
+
+                        // Update UI with loaded schemas
                         cx.update_entity(&entity, |content: &mut Self, cx| {
-                            content.tables = all_tables;
+                            content.schemas = schemas;
+                            content.last_schema = last_schema;
                             content.loading_tables = false;
                             content.sync_list_state(cx);
                         })?;
@@ -371,10 +410,10 @@ impl SidebarContent {
                     }
                 }
             } else {
-                // Connection missing, clear tables
+                // Connection missing, clear schemas
                 eprintln!("No connection found.");
                 cx.update_entity(&entity, |content: &mut Self, cx| {
-                    content.tables.clear();
+                    content.schemas.clear();
                     content.loading_tables = false;
                     content.sync_list_state(cx);
                 })?;
@@ -460,6 +499,10 @@ impl Render for SidebarContent {
                 let full_name: SharedString = name.clone().into();
                 let endpoint = connection.endpoint.clone();
                 let full_endpoint: SharedString = endpoint.clone().into();
+                let connection_id = connection.id;
+                let show_list = !collapsed && self.selected_tab == 0;
+                let show_views = !collapsed && self.selected_tab == 1;
+                let system_schemas_label = i18n.t("database.show-system-schemas");
 
                 base.child(
                     // The header shows basic information about the current database.
@@ -507,8 +550,13 @@ impl Render for SidebarContent {
                             .h_flex()
                             .selected_index(self.selected_tab)
                             .underline()
-                            .on_click(cx.listener(|this, ev, _, cx| {
+                            .on_click(cx.listener(|this, ev: &usize, _, cx| {
                                 this.selected_tab = *ev;
+                                cx.global_mut::<AppState>().current_page = if *ev == 2 {
+                                    PageRoute::DatabaseQueries
+                                } else {
+                                    PageRoute::DatabaseColumns
+                                };
                                 cx.notify();
                             }))
                             .child(
@@ -537,25 +585,66 @@ impl Render for SidebarContent {
                             ),
                     )
                 })
-                .when(!collapsed && self.selected_tab == 0, |this| {
-                    if self.list_state.is_none() {
-                        let list_tables = ListTables::new(
-                            self.tables.iter().map(|table| table.name.clone()).collect(),
+                .when(show_list && self.capabilities.key_value, |this| {
+                    if self.key_list_state.is_none() {
+                        let list_keys =
+                            ListKeys::new(Some(connection_id), self.key_schemas.clone());
+                        self.key_list_state = Some(
+                            cx.new(|cx| ListState::new(list_keys, window, cx).selectable(true)),
                         );
-                        self.list_state =
-                            Some(cx.new(|cx| {
-                                ListState::new(list_tables, window, cx).selectable(true)
-                            }));
                     }
-                    let table_state = self.list_state.clone().unwrap();
+                    let key_state = self.key_list_state.clone().unwrap();
 
                     this.child(
                         v_flex()
                             .flex_1()
                             .h_full()
-                            .child(List::new(&table_state).flex_1().h_full()),
+                            .child(List::new(&key_state).flex_1().h_full()),
                     )
                 })
+                .when(
+                    (show_list || show_views) && self.capabilities.schema_browser,
+                    |this| {
+                        let schemas = self.visible_schemas();
+                        let last_schema = self.last_schema.clone();
+                        let (list_state, kinds) = if show_views {
+                            (&mut self.view_list_state, &[ObjectKind::View][..])
+                        } else {
+                            (&mut self.list_state, &ALL_OBJECT_KINDS[..])
+                        };
+                        if list_state.is_none() {
+                            *list_state = Some(cx.new(|cx| {
+                                let mut state = ListState::new(ListTables::new(kinds), window, cx)
+                                    .selectable(true);
+                                state.delegate_mut().set_schemas(
+                                    Some(connection_id),
+                                    schemas,
+                                    last_schema.as_deref(),
+                                    cx,
+                                );
+                                state
+                            }));
+                        }
+                        let table_state = list_state.clone().unwrap();
+
+                        this.child(
+                            Checkbox::new("show-system-schemas")
+                                .checked(self.show_system_schemas)
+                                .on_click(cx.listener(|this, checked, _, cx| {
+                                    this.show_system_schemas = *checked;
+                                    this.sync_list_state(cx);
+                                    cx.notify();
+                                }))
+                                .label(system_schemas_label),
+                        )
+                        .child(
+                            v_flex()
+                                .flex_1()
+                                .h_full()
+                                .child(List::new(&table_state).flex_1().h_full()),
+                        )
+                    },
+                )
             }
             None => base,
         }
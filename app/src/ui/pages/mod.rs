@@ -1,3 +1,4 @@
+pub mod queries;
 pub mod tables;
 
 use std::fmt::Display;
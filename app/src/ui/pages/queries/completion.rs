@@ -0,0 +1,96 @@
+use anyhow::Result;
+use db_sight_core::{
+    completion::{Completion, CompletionKind},
+    DBManager,
+};
+use gpui::{Context, Task, Window};
+use gpui_component::input::{CompletionProvider, InputState, Rope};
+use lsp_types::{
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionResponse, CompletionTextEdit,
+    Position, Range, TextEdit,
+};
+
+/// Completes keywords, functions and the schemas, tables and columns of the selected
+/// connection in the SQL editor
+pub struct SqlCompletionProvider;
+
+impl SqlCompletionProvider {
+    /// LSP position of a byte offset, the character counts UTF-16 code units
+    fn position(text: &str, offset: usize) -> Position {
+        let before = &text[..offset];
+        let line_start = before.rfind('\n').map_or(0, |ix| ix + 1);
+        Position::new(
+            before.matches('\n').count() as u32,
+            before[line_start..].encode_utf16().count() as u32,
+        )
+    }
+
+    fn item_kind(kind: CompletionKind) -> CompletionItemKind {
+        match kind {
+            CompletionKind::Keyword => CompletionItemKind::KEYWORD,
+            CompletionKind::Function => CompletionItemKind::FUNCTION,
+            CompletionKind::Schema => CompletionItemKind::MODULE,
+            CompletionKind::Table => CompletionItemKind::STRUCT,
+            CompletionKind::View => CompletionItemKind::INTERFACE,
+            CompletionKind::Column => CompletionItemKind::FIELD,
+        }
+    }
+
+    fn item(completion: Completion, range: Range) -> CompletionItem {
+        CompletionItem {
+            kind: Some(Self::item_kind(completion.kind)),
+            detail: completion.detail,
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(
+                range,
+                completion.label.clone(),
+            ))),
+            label: completion.label,
+            ..Default::default()
+        }
+    }
+}
+
+impl CompletionProvider for SqlCompletionProvider {
+    fn completions(
+        &self,
+        text: &Rope,
+        offset: usize,
+        _trigger: CompletionContext,
+        _window: &mut Window,
+        cx: &mut Context<InputState>,
+    ) -> Task<Result<CompletionResponse>> {
+        let db_manager = cx.global::<DBManager>().clone();
+        let Some(connection_id) = db_manager.get_selected_connection() else {
+            return Task::ready(Ok(CompletionResponse::Array(Vec::new())));
+        };
+        let sql = text.to_string();
+
+        cx.spawn(async move |_, _| {
+            let completions = db_manager
+                .complete_sql(&connection_id, &sql, offset)
+                .await?;
+            let range = Range::new(
+                Self::position(&sql, completions.range.start),
+                Self::position(&sql, completions.range.end),
+            );
+            let items = completions
+                .items
+                .into_iter()
+                .map(|completion| Self::item(completion, range))
+                .collect();
+            Ok(CompletionResponse::Array(items))
+        })
+    }
+
+    fn is_completion_trigger(
+        &self,
+        _offset: usize,
+        new_text: &str,
+        _cx: &mut Context<InputState>,
+    ) -> bool {
+        new_text
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.')
+    }
+}
@@ -0,0 +1,2 @@
+mod completion;
+pub mod query;
@@ -0,0 +1,846 @@
+use std::{
+    collections::HashSet,
+    ops::Range,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use db_sight_core::{
+    sql::{changes_schema, split_statements, statement_at, statements_to_confirm},
+    CancelHandle, DBError, DBManager, DatabaseType, QueryResult, StatementResult, TableDataPage,
+};
+use gpui::{
+    div, prelude::FluentBuilder, px, App, AppContext, Context, Div, Entity, EntityInputHandler,
+    IntoElement, ParentElement, Render, Styled, Subscription, Task, Window,
+};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    tab::{Tab, TabBar},
+    table::{Table, TableState},
+    v_flex, ActiveTheme, Disableable, Sizable,
+};
+use uuid::Uuid;
+
+use crate::{core::I18n, ui::pages::tables::table_delegate::DatabaseTableDelegate};
+
+use super::completion::SqlCompletionProvider;
+
+/// Part of the editor buffer a run executes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RunMode {
+    All,
+    /// The statement under the cursor
+    Statement,
+    /// The selected text, or the current statement when nothing is selected
+    Selection,
+}
+
+/// Editor state a run executes, kept while its statements wait for confirmation
+#[derive(Clone)]
+struct RunRequest {
+    connection_id: Uuid,
+    text: String,
+    selection: Range<usize>,
+    cursor: usize,
+    mode: RunMode,
+}
+
+enum RunOutcome {
+    Finished(Vec<StatementResult>, Duration),
+    /// Nothing ran, the statements have to be confirmed first
+    NeedsConfirmation(Vec<String>),
+}
+
+/// A run held back until its risky statements are confirmed
+struct PendingRun {
+    request: RunRequest,
+    statements: Vec<String>,
+}
+
+struct QueryTab {
+    id: usize,
+    title: String,
+    editor: Entity<InputState>,
+    results: Vec<StatementResult>,
+    selected_result: usize,
+    table_state: Option<Entity<TableState<DatabaseTableDelegate>>>,
+    /// Wall-clock time of the last finished run
+    elapsed: Option<Duration>,
+    error: Option<String>,
+    cancelled: bool,
+    confirmation: Option<PendingRun>,
+    /// Dropping the task would leave the statements running on the server and the
+    /// connection mid-result, a query is only stopped through `cancel`
+    running: Option<Task<anyhow::Result<()>>>,
+    /// Set while the running query can be stopped on the server, with its connection
+    cancel: Option<(Uuid, CancelHandle)>,
+    _subscription: Subscription,
+}
+
+impl QueryTab {
+    fn clear_results(&mut self) {
+        self.results.clear();
+        self.selected_result = 0;
+        self.elapsed = None;
+        self.error = None;
+        self.cancelled = false;
+        self.confirmation = None;
+    }
+
+    fn selected_page(&self) -> Option<TableDataPage> {
+        match &self.results.get(self.selected_result)?.result {
+            QueryResult::Rows(set) => Some(set.to_page()),
+            QueryResult::Affected(_) => None,
+        }
+    }
+
+    /// Show the selected result set in the grid, the grid itself is created on render
+    fn sync_table(&self, cx: &mut App) {
+        if let (Some(table_state), Some(page)) = (&self.table_state, self.selected_page()) {
+            table_state.update(cx, |table_state, cx| {
+                table_state.delegate_mut().update_data(page);
+                cx.notify();
+            });
+        }
+    }
+}
+
+/// SQL editor with one tab per script
+pub struct PageQueries {
+    tabs: Vec<QueryTab>,
+    active_tab: usize,
+    next_tab_id: usize,
+    /// Off: the first run begins a transaction that stays open until commit or rollback
+    auto_commit: bool,
+    /// Connections with an explicit transaction still open
+    open_transactions: HashSet<Uuid>,
+}
+
+impl PageQueries {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut page = Self {
+            tabs: Vec::new(),
+            active_tab: 0,
+            next_tab_id: 1,
+            auto_commit: true,
+            open_transactions: HashSet::new(),
+        };
+        page.add_tab(window, cx);
+        page
+    }
+
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn add_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (title, placeholder) = {
+            let i18n = cx.global::<I18n>();
+            (
+                i18n.t_with("query.tab-title", &[("n", &self.next_tab_id.to_string())]),
+                i18n.t("query.placeholder"),
+            )
+        };
+        let editor = cx.new(|cx| {
+            let mut editor = InputState::new(window, cx)
+                .code_editor("sql")
+                .line_number(true)
+                .placeholder(placeholder);
+            editor.lsp.completion_provider = Some(Rc::new(SqlCompletionProvider));
+            editor
+        });
+        // Ctrl+Enter (Cmd+Enter on macOS) runs the statement under the cursor
+        let subscription = cx.subscribe_in(
+            &editor,
+            window,
+            |this, _, event: &InputEvent, window, cx| {
+                if let InputEvent::PressEnter {
+                    secondary: true, ..
+                } = event
+                {
+                    this.run(RunMode::Statement, window, cx);
+                }
+            },
+        );
+
+        self.tabs.push(QueryTab {
+            id: self.next_tab_id,
+            title,
+            editor,
+            results: Vec::new(),
+            selected_result: 0,
+            table_state: None,
+            elapsed: None,
+            error: None,
+            cancelled: false,
+            confirmation: None,
+            running: None,
+            cancel: None,
+            _subscription: subscription,
+        });
+        self.next_tab_id += 1;
+        self.active_tab = self.tabs.len() - 1;
+        cx.notify();
+    }
+
+    /// Close the active tab. Its running query is cancelled where the driver can stop it
+    /// on the server, and otherwise left to finish.
+    fn close_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.active_tab < self.tabs.len() {
+            let mut tab = self.tabs.remove(self.active_tab);
+            if let Some((connection_id, handle)) = tab.cancel.take() {
+                Self::cancel_on_server(connection_id, handle, cx);
+            }
+            if let Some(running) = tab.running.take() {
+                running.detach();
+            }
+        }
+        if self.tabs.is_empty() {
+            self.add_tab(window, cx);
+        }
+        self.active_tab = self.active_tab.min(self.tabs.len() - 1);
+        cx.notify();
+    }
+
+    fn run(&mut self, mode: RunMode, window: &mut Window, cx: &mut Context<Self>) {
+        let db_manager = cx.global::<DBManager>().clone();
+        let no_connection = cx.global::<I18n>().t("query.no-connection");
+        let Some(tab) = self.tabs.get_mut(self.active_tab) else {
+            return;
+        };
+        if tab.running.is_some() {
+            return;
+        }
+
+        let Some(connection_id) = db_manager.get_selected_connection() else {
+            tab.clear_results();
+            tab.error = Some(no_connection);
+            cx.notify();
+            return;
+        };
+
+        // The input reports its selection in UTF-16 offsets
+        let (text, selection, cursor) = tab.editor.update(cx, |input, cx| {
+            let text = input.value().to_string();
+            let (selection, cursor) = match input.selected_text_range(true, window, cx) {
+                Some(selected) => {
+                    let start = Self::byte_offset(&text, selected.range.start);
+                    let end = Self::byte_offset(&text, selected.range.end);
+                    (start..end, if selected.reversed { start } else { end })
+                }
+                None => (0..0, text.len()),
+            };
+            (text, selection, cursor)
+        });
+
+        let request = RunRequest {
+            connection_id,
+            text,
+            selection,
+            cursor,
+            mode,
+        };
+        self.start(request, false, cx);
+    }
+
+    /// Run the confirmed statements of the active tab
+    fn confirm_run(&mut self, cx: &mut Context<Self>) {
+        let pending = self
+            .tabs
+            .get_mut(self.active_tab)
+            .and_then(|tab| tab.confirmation.take());
+        if let Some(pending) = pending {
+            self.start(pending.request, true, cx);
+        }
+    }
+
+    fn dismiss_confirmation(&mut self, cx: &mut Context<Self>) {
+        if let Some(tab) = self.tabs.get_mut(self.active_tab) {
+            tab.confirmation = None;
+            cx.notify();
+        }
+    }
+
+    /// Run a request in the active tab. Unless `confirmed`, risky statements stop the
+    /// run for confirmation before anything executes.
+    fn start(&mut self, request: RunRequest, confirmed: bool, cx: &mut Context<Self>) {
+        let db_manager = cx.global::<DBManager>().clone();
+        let Some(tab) = self.tabs.get_mut(self.active_tab) else {
+            return;
+        };
+        tab.clear_results();
+
+        let tab_id = tab.id;
+        let auto_commit = self.auto_commit;
+        let connection_id = request.connection_id;
+        let handle = CancelHandle::new();
+        tab.running = Some(cx.spawn(async move |this, cx| {
+            let cancellable = || {
+                let _ = this.update(cx, |this, cx| {
+                    this.set_cancellable(tab_id, connection_id, handle.clone(), cx);
+                });
+            };
+            let result = Self::execute(
+                db_manager.clone(),
+                request.clone(),
+                auto_commit,
+                confirmed,
+                &handle,
+                cancellable,
+            )
+            .await;
+            let in_transaction = db_manager.in_transaction(&connection_id).await;
+            this.update(cx, |this, cx| {
+                this.set_in_transaction(connection_id, in_transaction);
+                this.finish(tab_id, request, result, cx);
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        }));
+        cx.notify();
+    }
+
+    /// Run a request, `cancellable` is called before the statements start when the
+    /// driver can stop them on the server through `handle`
+    async fn execute(
+        db_manager: DBManager,
+        request: RunRequest,
+        auto_commit: bool,
+        confirmed: bool,
+        handle: &CancelHandle,
+        cancellable: impl FnOnce(),
+    ) -> Result<RunOutcome, DBError> {
+        let connection_id = request.connection_id;
+        let config = db_manager
+            .get_config_by_id(&connection_id)
+            .await
+            .ok_or(DBError::ConnectionError("Connection not found".to_string()))?;
+        let db_type = config.db_type;
+
+        let sql = Self::sql_to_run(
+            &request.text,
+            request.selection,
+            request.cursor,
+            request.mode,
+            db_type,
+        );
+        if sql.trim().is_empty() {
+            return Ok(RunOutcome::Finished(Vec::new(), Duration::ZERO));
+        }
+        if !confirmed {
+            let statements = statements_to_confirm(sql, db_type, config.production);
+            if !statements.is_empty() {
+                let statements = statements.into_iter().map(str::to_string).collect();
+                return Ok(RunOutcome::NeedsConfirmation(statements));
+            }
+        }
+
+        let driver = db_manager.connect(&connection_id).await?;
+        let executor = driver.as_sql_executor().ok_or_else(|| {
+            DBError::QueryError(format!("{} does not run SQL statements", driver.name()))
+        })?;
+        if !auto_commit {
+            let transactional = driver.as_transactional().ok_or_else(|| {
+                DBError::TransactionError(format!(
+                    "{} does not support transactions",
+                    driver.name()
+                ))
+            })?;
+            if !transactional.in_transaction().await {
+                transactional.begin().await?;
+            }
+        }
+        let canceller = driver.as_query_canceller();
+        if canceller.is_some() {
+            cancellable();
+        }
+        let started = Instant::now();
+        let results = match canceller {
+            Some(canceller) => canceller.execute_cancellable(sql, handle).await,
+            None => executor.execute(sql, &[]).await,
+        };
+        let elapsed = started.elapsed();
+        // Even a failed batch may have changed the schema before the failing statement
+        if split_statements(sql, db_type)
+            .into_iter()
+            .any(changes_schema)
+        {
+            db_manager.refresh_metadata(&connection_id).await;
+        }
+        Ok(RunOutcome::Finished(results?, elapsed))
+    }
+
+    fn sql_to_run(
+        text: &str,
+        selection: Range<usize>,
+        cursor: usize,
+        mode: RunMode,
+        db_type: DatabaseType,
+    ) -> &str {
+        match mode {
+            RunMode::All => text,
+            RunMode::Selection if !selection.is_empty() => &text[selection],
+            _ => statement_at(text, cursor, db_type).map_or("", |range| &text[range]),
+        }
+    }
+
+    fn finish(
+        &mut self,
+        tab_id: usize,
+        request: RunRequest,
+        result: Result<RunOutcome, DBError>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tab) = self.tabs.iter_mut().find(|tab| tab.id == tab_id) else {
+            return;
+        };
+        tab.running = None;
+        tab.cancel = None;
+        match result {
+            Ok(RunOutcome::Finished(results, elapsed)) => {
+                // The cancel came too late, the statements all ran
+                tab.cancelled = false;
+                tab.results = results;
+                tab.elapsed = Some(elapsed);
+            }
+            Ok(RunOutcome::NeedsConfirmation(statements)) => {
+                tab.confirmation = Some(PendingRun {
+                    request,
+                    statements,
+                });
+            }
+            Err(_) if tab.cancelled => {}
+            Err(e) => {
+                eprintln!("Query failed: {}", e);
+                tab.error = Some(e.to_string());
+            }
+        }
+        tab.sync_table(cx);
+        cx.notify();
+    }
+
+    fn set_in_transaction(&mut self, connection_id: Uuid, in_transaction: bool) {
+        if in_transaction {
+            self.open_transactions.insert(connection_id);
+        } else {
+            self.open_transactions.remove(&connection_id);
+        }
+    }
+
+    fn selected_in_transaction(&self, cx: &App) -> bool {
+        cx.global::<DBManager>()
+            .get_selected_connection()
+            .is_some_and(|id| self.open_transactions.contains(&id))
+    }
+
+    /// Commit or roll back the open transaction of the selected connection
+    fn end_transaction(&mut self, commit: bool, cx: &mut Context<Self>) {
+        let db_manager = cx.global::<DBManager>().clone();
+        let Some(connection_id) = db_manager.get_selected_connection() else {
+            return;
+        };
+        cx.spawn(async move |this, cx| {
+            let result = async {
+                let driver = db_manager.connect(&connection_id).await?;
+                let transactional = driver.as_transactional().ok_or_else(|| {
+                    DBError::TransactionError("No transaction is open".to_string())
+                })?;
+                if commit {
+                    transactional.commit().await
+                } else {
+                    transactional.rollback().await
+                }
+            }
+            .await;
+            let in_transaction = db_manager.in_transaction(&connection_id).await;
+            this.update(cx, |this, cx| {
+                this.set_in_transaction(connection_id, in_transaction);
+                if let Err(e) = result {
+                    eprintln!("Failed to end transaction: {}", e);
+                    if let Some(tab) = this.tabs.get_mut(this.active_tab) {
+                        tab.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Forget the completion metadata of the selected connection, e.g. after the schema
+    /// was changed outside of this editor
+    fn refresh_metadata(&mut self, cx: &mut Context<Self>) {
+        let db_manager = cx.global::<DBManager>().clone();
+        let Some(connection_id) = db_manager.get_selected_connection() else {
+            return;
+        };
+        cx.spawn(async move |_, _| {
+            db_manager.refresh_metadata(&connection_id).await;
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Offer to cancel the running query of a tab, its driver can stop it on the server
+    fn set_cancellable(
+        &mut self,
+        tab_id: usize,
+        connection_id: Uuid,
+        handle: CancelHandle,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(tab) = self.tabs.iter_mut().find(|tab| tab.id == tab_id) {
+            if tab.running.is_some() {
+                tab.cancel = Some((connection_id, handle));
+                cx.notify();
+            }
+        }
+    }
+
+    /// Stop the running query of the active tab on the server, the run then finishes
+    /// as cancelled
+    fn cancel(&mut self, cx: &mut Context<Self>) {
+        let Some(tab) = self.tabs.get_mut(self.active_tab) else {
+            return;
+        };
+        if tab.cancelled {
+            return;
+        }
+        if let Some((connection_id, handle)) = tab.cancel.clone() {
+            tab.cancelled = true;
+            Self::cancel_on_server(connection_id, handle, cx);
+            cx.notify();
+        }
+    }
+
+    fn cancel_on_server(connection_id: Uuid, handle: CancelHandle, cx: &mut Context<Self>) {
+        let db_manager = cx.global::<DBManager>().clone();
+        cx.spawn(async move |_, _| {
+            let Some(driver) = db_manager.get_connection(&connection_id.to_string()).await else {
+                return;
+            };
+            if let Some(canceller) = driver.as_query_canceller() {
+                if let Err(e) = canceller.cancel(&handle).await {
+                    eprintln!("Failed to cancel query: {}", e);
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn select_result(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if let Some(tab) = self.tabs.get_mut(self.active_tab) {
+            tab.selected_result = ix;
+            tab.sync_table(cx);
+            cx.notify();
+        }
+    }
+
+    fn byte_offset(text: &str, utf16_offset: usize) -> usize {
+        let mut utf16 = 0;
+        for (ix, c) in text.char_indices() {
+            if utf16 >= utf16_offset {
+                return ix;
+            }
+            utf16 += c.len_utf16();
+        }
+        text.len()
+    }
+
+    fn render_tab_bar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let i18n = cx.global::<I18n>();
+        let tab_bar = self.tabs.iter().fold(
+            TabBar::new("query-tabs")
+                .flex_1()
+                .selected_index(self.active_tab)
+                .on_click(cx.listener(|this, ix: &usize, _, cx| {
+                    this.active_tab = *ix;
+                    cx.notify();
+                })),
+            |tab_bar, tab| tab_bar.child(Tab::new().label(tab.title.clone())),
+        );
+
+        h_flex()
+            .gap_1()
+            .pr_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(tab_bar)
+            .child(
+                Button::new("new-query-tab")
+                    .small()
+                    .ghost()
+                    .label(i18n.t("query.new-tab"))
+                    .on_click(cx.listener(|this, _, window, cx| this.add_tab(window, cx))),
+            )
+            .child(
+                Button::new("close-query-tab")
+                    .small()
+                    .ghost()
+                    .label(i18n.t("query.close-tab"))
+                    .on_click(cx.listener(|this, _, window, cx| this.close_tab(window, cx))),
+            )
+    }
+
+    fn render_toolbar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let i18n = cx.global::<I18n>();
+        let tab = self.tabs.get(self.active_tab);
+        let running = tab.is_some_and(|tab| tab.running.is_some());
+        let cancellable = tab.is_some_and(|tab| tab.cancel.is_some() && !tab.cancelled);
+        let status = match tab {
+            Some(tab) if tab.running.is_some() && tab.cancelled => i18n.t("query.cancelling"),
+            Some(tab) if tab.running.is_some() => i18n.t("query.running"),
+            Some(tab) if tab.cancelled => i18n.t("query.cancelled"),
+            Some(QueryTab {
+                elapsed: Some(elapsed),
+                ..
+            }) => i18n.t_with("query.elapsed", &[("ms", &elapsed.as_millis().to_string())]),
+            _ => String::new(),
+        };
+        let in_transaction = self.selected_in_transaction(cx);
+
+        h_flex()
+            .gap_2()
+            .px_3()
+            .py_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                Button::new("run-all")
+                    .small()
+                    .primary()
+                    .label(i18n.t("query.run"))
+                    .disabled(running)
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.run(RunMode::All, window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("run-statement")
+                    .small()
+                    .label(i18n.t("query.run-statement"))
+                    .disabled(running)
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.run(RunMode::Statement, window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("run-selection")
+                    .small()
+                    .label(i18n.t("query.run-selection"))
+                    .disabled(running)
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.run(RunMode::Selection, window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("cancel-query")
+                    .small()
+                    .label(i18n.t("query.cancel"))
+                    .disabled(!cancellable)
+                    .on_click(cx.listener(|this, _, _, cx| this.cancel(cx))),
+            )
+            .child(
+                Checkbox::new("auto-commit")
+                    .checked(self.auto_commit)
+                    .disabled(in_transaction)
+                    .on_click(cx.listener(|this, checked, _, cx| {
+                        this.auto_commit = *checked;
+                        cx.notify();
+                    }))
+                    .label(i18n.t("query.auto-commit")),
+            )
+            .when(in_transaction, |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().warning)
+                        .child(i18n.t("query.transaction-open")),
+                )
+                .child(
+                    Button::new("commit-transaction")
+                        .small()
+                        .primary()
+                        .label(i18n.t("query.commit"))
+                        .disabled(running)
+                        .on_click(cx.listener(|this, _, _, cx| this.end_transaction(true, cx))),
+                )
+                .child(
+                    Button::new("rollback-transaction")
+                        .small()
+                        .label(i18n.t("query.rollback"))
+                        .disabled(running)
+                        .on_click(cx.listener(|this, _, _, cx| this.end_transaction(false, cx))),
+                )
+            })
+            .child(div().flex_1())
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(status),
+            )
+            .child(
+                Button::new("refresh-metadata")
+                    .small()
+                    .ghost()
+                    .label(i18n.t("query.refresh-metadata"))
+                    .on_click(cx.listener(|this, _, _, cx| this.refresh_metadata(cx))),
+            )
+    }
+
+    fn render_results(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Div {
+        let no_results = cx.global::<I18n>().t("query.no-results");
+        let Some(tab) = self.tabs.get_mut(self.active_tab) else {
+            return div();
+        };
+        if let Some(pending) = &tab.confirmation {
+            let i18n = cx.global::<I18n>();
+            return v_flex()
+                .gap_2()
+                .p_3()
+                .child(
+                    div()
+                        .text_color(cx.theme().warning)
+                        .child(i18n.t("query.confirm-title")),
+                )
+                .children(
+                    pending
+                        .statements
+                        .iter()
+                        .map(|statement| div().text_sm().child(statement.clone())),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("confirm-run")
+                                .small()
+                                .danger()
+                                .label(i18n.t("query.confirm-run"))
+                                .on_click(cx.listener(|this, _, _, cx| this.confirm_run(cx))),
+                        )
+                        .child(
+                            Button::new("dismiss-run")
+                                .small()
+                                .label(i18n.t("query.cancel"))
+                                .on_click(
+                                    cx.listener(|this, _, _, cx| this.dismiss_confirmation(cx)),
+                                ),
+                        ),
+                );
+        }
+        if let Some(error) = &tab.error {
+            return div()
+                .p_3()
+                .text_color(cx.theme().danger)
+                .child(error.clone());
+        }
+        let Some(statement) = tab.results.get(tab.selected_result) else {
+            return div()
+                .flex_1()
+                .flex()
+                .items_center()
+                .justify_center()
+                .text_color(cx.theme().muted_foreground)
+                .when(tab.elapsed.is_some(), |this| this.child(no_results));
+        };
+
+        let (summary, result_labels) = {
+            let i18n = cx.global::<I18n>();
+            let result_labels: Vec<String> = (1..=tab.results.len())
+                .map(|n| i18n.t_with("query.result", &[("n", &n.to_string())]))
+                .collect();
+            let summary = match &statement.result {
+                QueryResult::Rows(set) => {
+                    vec![i18n.t_with("query.rows", &[("count", &set.rows.len().to_string())])]
+                }
+                QueryResult::Affected(summary) => {
+                    let mut lines = vec![i18n.t_with(
+                        "query.affected",
+                        &[("count", &summary.rows_affected.to_string())],
+                    )];
+                    if let Some(id) = summary.last_insert_id {
+                        lines.push(i18n.t_with("query.last-insert-id", &[("id", &id.to_string())]));
+                    }
+                    lines.extend(summary.warnings.iter().cloned());
+                    lines
+                }
+            };
+            (summary, result_labels)
+        };
+
+        let result_tabs = (tab.results.len() > 1).then(|| {
+            result_labels.into_iter().fold(
+                TabBar::new("query-results")
+                    .segmented()
+                    .selected_index(tab.selected_result)
+                    .on_click(cx.listener(|this, ix: &usize, _, cx| {
+                        this.select_result(*ix, cx);
+                    })),
+                |tab_bar, label| tab_bar.child(Tab::new().label(label)),
+            )
+        });
+
+        let grid = match tab.selected_page() {
+            Some(page) => {
+                let table_state = tab
+                    .table_state
+                    .get_or_insert_with(|| {
+                        cx.new(|cx| TableState::new(DatabaseTableDelegate::new(page), window, cx))
+                    })
+                    .clone();
+                Some(Table::new(&table_state).scrollbar_visible(true, true))
+            }
+            None => None,
+        };
+
+        v_flex()
+            .flex_1()
+            .min_h_0()
+            .child(
+                h_flex()
+                    .gap_4()
+                    .px_3()
+                    .py_2()
+                    .when_some(result_tabs, |this, result_tabs| this.child(result_tabs))
+                    .child(
+                        v_flex()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .children(summary.into_iter().map(|line| div().child(line))),
+                    ),
+            )
+            .when_some(grid, |this, grid| {
+                this.child(div().flex_1().size_full().child(grid))
+            })
+    }
+}
+
+impl Render for PageQueries {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let editor = self.tabs.get(self.active_tab).map(|tab| tab.editor.clone());
+
+        v_flex()
+            .size_full()
+            .bg(cx.theme().background)
+            .text_color(cx.theme().foreground)
+            .child(self.render_tab_bar(cx))
+            .child(self.render_toolbar(cx))
+            .when_some(editor, |this, editor| {
+                this.child(
+                    div()
+                        .h(px(240.))
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(Input::new(&editor).h_full()),
+                )
+            })
+            .child(self.render_results(window, cx))
+    }
+}
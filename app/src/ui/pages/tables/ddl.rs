@@ -0,0 +1,51 @@
+use gpui::{div, App, ClipboardItem, Div, Entity, ParentElement, Styled};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    notification::Notification,
+    v_flex, ActiveTheme, Sizable, StyledExt, WindowExt,
+};
+
+use crate::core::I18n;
+
+/// Read-only, highlighted definition of an object in `editor`, with a button copying it
+pub fn render_ddl(title: String, ddl: String, editor: &Entity<InputState>, cx: &App) -> Div {
+    let i18n = cx.global::<I18n>();
+    let copied = i18n.t("ddl.copied");
+    v_flex()
+        .size_full()
+        .bg(cx.theme().background)
+        .child(
+            h_flex()
+                .px_3()
+                .py_2()
+                .gap_2()
+                .justify_between()
+                .border_b_1()
+                .border_color(cx.theme().border)
+                .child(
+                    div()
+                        .text_sm()
+                        .font_semibold()
+                        .text_color(cx.theme().foreground)
+                        .child(title),
+                )
+                .child(
+                    Button::new("ddl-copy")
+                        .small()
+                        .ghost()
+                        .label(i18n.t("ddl.copy"))
+                        .on_click(move |_, window, cx| {
+                            cx.write_to_clipboard(ClipboardItem::new_string(ddl.clone()));
+                            window.push_notification(Notification::success(copied.clone()), cx);
+                        }),
+                ),
+        )
+        .child(
+            div()
+                .flex_1()
+                .p_2()
+                .child(Input::new(editor).h_full().disabled(true)),
+        )
+}
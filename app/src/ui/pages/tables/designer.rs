@@ -0,0 +1,591 @@
+use db_sight_core::{
+    AlterStatement, ColumnDesign, DBError, DBManager, ForeignKey, IndexColumn, ObjectRef,
+    TableColumn, TableDesign, TableIndex,
+};
+use gpui::{
+    div, prelude::FluentBuilder, px, App, AppContext, Context, Div, Entity, EventEmitter,
+    InteractiveElement, IntoElement, ParentElement, Render, StatefulInteractiveElement, Styled,
+    Window,
+};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputState},
+    v_flex, ActiveTheme, Disableable, Sizable, StyledExt,
+};
+
+use crate::core::I18n;
+
+/// Emitted once the table was altered, its columns have to be read again
+pub struct DesignApplied;
+
+struct ColumnRow {
+    /// Name of the column in the table, `None` for a column to add
+    original: Option<String>,
+    name: Entity<InputState>,
+    data_type: Entity<InputState>,
+    nullable: bool,
+    default: Entity<InputState>,
+}
+
+struct IndexRow {
+    name: Entity<InputState>,
+    /// Comma separated
+    columns: Entity<InputState>,
+    unique: bool,
+}
+
+struct ForeignKeyRow {
+    name: Entity<InputState>,
+    columns: Entity<InputState>,
+    /// `table` or `schema.table`
+    referenced_table: Entity<InputState>,
+    referenced_columns: Entity<InputState>,
+    on_delete: Entity<InputState>,
+}
+
+/// Editable columns of a table plus indexes and foreign keys to add. The changes are
+/// turned into `ALTER TABLE` statements, shown for review before they run.
+pub struct TableDesigner {
+    table: ObjectRef,
+    current: Vec<TableColumn>,
+    columns: Vec<ColumnRow>,
+    indexes: Vec<IndexRow>,
+    foreign_keys: Vec<ForeignKeyRow>,
+    /// Statements of the design, shown for review before running them
+    preview: Option<Vec<AlterStatement>>,
+    applying: bool,
+    error: Option<String>,
+}
+
+impl EventEmitter<DesignApplied> for TableDesigner {}
+
+impl TableDesigner {
+    pub fn new(
+        table: ObjectRef,
+        current: Vec<TableColumn>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut designer = Self {
+            table,
+            current,
+            columns: Vec::new(),
+            indexes: Vec::new(),
+            foreign_keys: Vec::new(),
+            preview: None,
+            applying: false,
+            error: None,
+        };
+        designer.reset(window, cx);
+        designer
+    }
+
+    pub fn view(
+        table: ObjectRef,
+        current: Vec<TableColumn>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        cx.new(|cx| Self::new(table, current, window, cx))
+    }
+
+    fn input(
+        value: &str,
+        placeholder: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<InputState> {
+        let (value, placeholder) = (value.to_string(), placeholder.to_string());
+        cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(placeholder)
+                .default_value(value)
+        })
+    }
+
+    /// Drop every change and start over from the columns of the table
+    fn reset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.current.clone();
+        self.columns = current
+            .iter()
+            .map(|column| ColumnRow {
+                original: Some(column.name.clone()),
+                name: Self::input(&column.name, "", window, cx),
+                data_type: Self::input(&column.data_type, "", window, cx),
+                nullable: column.nullable,
+                default: Self::input(column.default.as_deref().unwrap_or(""), "", window, cx),
+            })
+            .collect();
+        self.indexes.clear();
+        self.foreign_keys.clear();
+        self.preview = None;
+        self.error = None;
+        cx.notify();
+    }
+
+    fn add_column(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let i18n = cx.global::<I18n>();
+        let (name, data_type, default) = (
+            i18n.t("designer.name-placeholder"),
+            i18n.t("designer.type-placeholder"),
+            i18n.t("designer.default-placeholder"),
+        );
+        self.columns.push(ColumnRow {
+            original: None,
+            name: Self::input("", &name, window, cx),
+            data_type: Self::input("", &data_type, window, cx),
+            nullable: true,
+            default: Self::input("", &default, window, cx),
+        });
+        cx.notify();
+    }
+
+    fn add_index(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let i18n = cx.global::<I18n>();
+        let (name, columns) = (
+            i18n.t("designer.name-placeholder"),
+            i18n.t("designer.columns-placeholder"),
+        );
+        self.indexes.push(IndexRow {
+            name: Self::input("", &name, window, cx),
+            columns: Self::input("", &columns, window, cx),
+            unique: false,
+        });
+        cx.notify();
+    }
+
+    fn add_foreign_key(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let i18n = cx.global::<I18n>();
+        let (name, columns, table, on_delete) = (
+            i18n.t("designer.name-placeholder"),
+            i18n.t("designer.columns-placeholder"),
+            i18n.t("designer.table-placeholder"),
+            i18n.t("designer.on-delete-placeholder"),
+        );
+        self.foreign_keys.push(ForeignKeyRow {
+            name: Self::input("", &name, window, cx),
+            columns: Self::input("", &columns, window, cx),
+            referenced_table: Self::input("", &table, window, cx),
+            referenced_columns: Self::input("", &columns, window, cx),
+            on_delete: Self::input("", &on_delete, window, cx),
+        });
+        cx.notify();
+    }
+
+    /// The table as the rows describe it
+    fn design(&self, cx: &App) -> TableDesign {
+        let value = |input: &Entity<InputState>| input.read(cx).value().trim().to_string();
+        let list = |input: &Entity<InputState>| {
+            value(input)
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        TableDesign {
+            columns: self
+                .columns
+                .iter()
+                .map(|row| ColumnDesign {
+                    original: row.original.clone(),
+                    name: value(&row.name),
+                    data_type: value(&row.data_type),
+                    nullable: row.nullable,
+                    default: Some(value(&row.default)).filter(|default| !default.is_empty()),
+                })
+                .collect(),
+            indexes: self
+                .indexes
+                .iter()
+                .map(|row| TableIndex {
+                    name: value(&row.name),
+                    columns: list(&row.columns)
+                        .into_iter()
+                        .map(IndexColumn::new)
+                        .collect(),
+                    unique: row.unique,
+                    primary: false,
+                    method: None,
+                })
+                .collect(),
+            foreign_keys: self
+                .foreign_keys
+                .iter()
+                .map(|row| {
+                    let referenced = value(&row.referenced_table);
+                    let (referenced_schema, referenced_table) = match referenced.split_once('.') {
+                        Some((schema, table)) => (schema.to_string(), table.to_string()),
+                        None => (String::new(), referenced),
+                    };
+                    ForeignKey {
+                        name: value(&row.name),
+                        columns: list(&row.columns),
+                        referenced_schema,
+                        referenced_table,
+                        referenced_columns: list(&row.referenced_columns),
+                        on_update: String::new(),
+                        on_delete: value(&row.on_delete),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    fn review(&mut self, cx: &mut Context<Self>) {
+        let design = self.design(cx);
+        let table = self.table.clone();
+        let db_manager = cx.global::<DBManager>().clone();
+        self.error = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let driver = db_manager
+                .get_connection(&table.connection_id.to_string())
+                .await;
+            let result = match driver.as_ref().and_then(|d| d.as_table_designer()) {
+                Some(designer) => {
+                    designer
+                        .preview_design(&table.schema, &table.name, &design)
+                        .await
+                }
+                None => Err(DBError::ConnectionError("Not connected".to_string())),
+            };
+
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(statements) if statements.is_empty() => {
+                        this.error = Some(cx.global::<I18n>().t("designer.no-changes"));
+                    }
+                    Ok(statements) => this.preview = Some(statements),
+                    Err(e) => {
+                        eprintln!("Failed to preview table changes: {}", e);
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn apply(&mut self, cx: &mut Context<Self>) {
+        let design = self.design(cx);
+        let table = self.table.clone();
+        let db_manager = cx.global::<DBManager>().clone();
+        self.applying = true;
+        self.error = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let driver = db_manager
+                .get_connection(&table.connection_id.to_string())
+                .await;
+            let result = match driver.as_ref().and_then(|d| d.as_table_designer()) {
+                Some(designer) => {
+                    designer
+                        .apply_design(&table.schema, &table.name, &design)
+                        .await
+                }
+                None => Err(DBError::ConnectionError("Not connected".to_string())),
+            };
+
+            if result.is_ok() {
+                db_manager.refresh_metadata(&table.connection_id).await;
+            }
+            this.update(cx, |this, cx| {
+                this.applying = false;
+                match result {
+                    Ok(()) => {
+                        this.preview = None;
+                        cx.emit(DesignApplied);
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to alter table: {}", e);
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn render_toolbar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let i18n = cx.global::<I18n>();
+        let reviewing = self.preview.is_some();
+        h_flex()
+            .gap_2()
+            .px_3()
+            .py_2()
+            .items_center()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .when(!reviewing, |this| {
+                this.child(
+                    Button::new("designer-add-column")
+                        .small()
+                        .ghost()
+                        .label(i18n.t("designer.add-column"))
+                        .on_click(cx.listener(|this, _, window, cx| this.add_column(window, cx))),
+                )
+                .child(
+                    Button::new("designer-add-index")
+                        .small()
+                        .ghost()
+                        .label(i18n.t("designer.add-index"))
+                        .on_click(cx.listener(|this, _, window, cx| this.add_index(window, cx))),
+                )
+                .child(
+                    Button::new("designer-add-foreign-key")
+                        .small()
+                        .ghost()
+                        .label(i18n.t("designer.add-foreign-key"))
+                        .on_click(
+                            cx.listener(|this, _, window, cx| this.add_foreign_key(window, cx)),
+                        ),
+                )
+                .child(div().flex_1())
+                .child(
+                    Button::new("designer-reset")
+                        .small()
+                        .ghost()
+                        .label(i18n.t("designer.reset"))
+                        .on_click(cx.listener(|this, _, window, cx| this.reset(window, cx))),
+                )
+                .child(
+                    Button::new("designer-review")
+                        .small()
+                        .primary()
+                        .label(i18n.t("table.edit-review"))
+                        .on_click(cx.listener(|this, _, _, cx| this.review(cx))),
+                )
+            })
+            .when(reviewing, |this| {
+                this.child(div().flex_1())
+                    .child(
+                        Button::new("designer-back")
+                            .small()
+                            .ghost()
+                            .label(i18n.t("table.edit-back"))
+                            .disabled(self.applying)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.preview = None;
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Button::new("designer-apply")
+                            .small()
+                            .primary()
+                            .label(i18n.t("table.edit-apply"))
+                            .disabled(self.applying)
+                            .on_click(cx.listener(|this, _, _, cx| this.apply(cx))),
+                    )
+            })
+    }
+
+    fn render_preview(statements: &[AlterStatement], cx: &App) -> Div {
+        let i18n = cx.global::<I18n>();
+        v_flex()
+            .font_family("monospace")
+            .text_sm()
+            .children(statements.iter().map(|statement| {
+                let algorithm = statement.instant.map(|instant| {
+                    if instant {
+                        div()
+                            .text_color(cx.theme().success)
+                            .child(i18n.t("designer.instant"))
+                    } else {
+                        div()
+                            .text_color(cx.theme().warning)
+                            .child(i18n.t("designer.not-instant"))
+                    }
+                });
+                h_flex()
+                    .gap_3()
+                    .px_3()
+                    .py_2()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .child(div().flex_1().child(format!("{};", statement.sql)))
+                    .children(algorithm)
+            }))
+    }
+
+    fn render_columns(&self, cx: &Context<Self>) -> Div {
+        let i18n = cx.global::<I18n>();
+        let header = |key: &str| div().flex_1().child(i18n.t(key));
+        v_flex()
+            .gap_1()
+            .child(div().font_semibold().child(i18n.t("structure.columns")))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(header("structure.name"))
+                    .child(header("structure.type"))
+                    .child(div().w(px(80.)).child(i18n.t("structure.nullable")))
+                    .child(header("structure.default"))
+                    .child(div().w(px(80.))),
+            )
+            .children(self.columns.iter().enumerate().map(|(ix, row)| {
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(div().flex_1().child(Input::new(&row.name).small()))
+                    .child(div().flex_1().child(Input::new(&row.data_type).small()))
+                    .child(
+                        div().w(px(80.)).child(
+                            Checkbox::new(("designer-nullable", ix))
+                                .checked(row.nullable)
+                                .on_click(cx.listener(move |this, checked, _, cx| {
+                                    if let Some(row) = this.columns.get_mut(ix) {
+                                        row.nullable = *checked;
+                                    }
+                                    cx.notify();
+                                })),
+                        ),
+                    )
+                    .child(div().flex_1().child(Input::new(&row.default).small()))
+                    .child(
+                        div().w(px(80.)).child(
+                            Button::new(("designer-drop-column", ix))
+                                .small()
+                                .ghost()
+                                .label(i18n.t("designer.remove"))
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    if ix < this.columns.len() {
+                                        this.columns.remove(ix);
+                                    }
+                                    cx.notify();
+                                })),
+                        ),
+                    )
+            }))
+    }
+
+    fn render_indexes(&self, cx: &Context<Self>) -> Div {
+        let i18n = cx.global::<I18n>();
+        v_flex()
+            .gap_1()
+            .child(div().font_semibold().child(i18n.t("designer.new-indexes")))
+            .children(self.indexes.iter().enumerate().map(|(ix, row)| {
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(div().flex_1().child(Input::new(&row.name).small()))
+                    .child(div().flex_1().child(Input::new(&row.columns).small()))
+                    .child(
+                        Checkbox::new(("designer-unique", ix))
+                            .checked(row.unique)
+                            .label(i18n.t("structure.unique"))
+                            .on_click(cx.listener(move |this, checked, _, cx| {
+                                if let Some(row) = this.indexes.get_mut(ix) {
+                                    row.unique = *checked;
+                                }
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Button::new(("designer-drop-index", ix))
+                            .small()
+                            .ghost()
+                            .label(i18n.t("designer.remove"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                if ix < this.indexes.len() {
+                                    this.indexes.remove(ix);
+                                }
+                                cx.notify();
+                            })),
+                    )
+            }))
+    }
+
+    fn render_foreign_keys(&self, cx: &Context<Self>) -> Div {
+        let i18n = cx.global::<I18n>();
+        v_flex()
+            .gap_1()
+            .child(
+                div()
+                    .font_semibold()
+                    .child(i18n.t("designer.new-foreign-keys")),
+            )
+            .children(self.foreign_keys.iter().enumerate().map(|(ix, row)| {
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(div().flex_1().child(Input::new(&row.name).small()))
+                    .child(div().flex_1().child(Input::new(&row.columns).small()))
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(Input::new(&row.referenced_table).small()),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(Input::new(&row.referenced_columns).small()),
+                    )
+                    .child(div().flex_1().child(Input::new(&row.on_delete).small()))
+                    .child(
+                        Button::new(("designer-drop-foreign-key", ix))
+                            .small()
+                            .ghost()
+                            .label(i18n.t("designer.remove"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                if ix < this.foreign_keys.len() {
+                                    this.foreign_keys.remove(ix);
+                                }
+                                cx.notify();
+                            })),
+                    )
+            }))
+    }
+}
+
+impl Render for TableDesigner {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .bg(cx.theme().background)
+            .text_color(cx.theme().foreground)
+            .child(self.render_toolbar(cx))
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .px_3()
+                        .py_1()
+                        .text_sm()
+                        .text_color(cx.theme().danger)
+                        .child(error),
+                )
+            })
+            .child(
+                v_flex()
+                    .id("table-designer")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .gap_4()
+                    .p_3()
+                    .text_sm()
+                    .map(|this| match &self.preview {
+                        Some(statements) => this.child(Self::render_preview(statements, cx)),
+                        None => this
+                            .child(self.render_columns(cx))
+                            .when(!self.indexes.is_empty(), |this| {
+                                this.child(self.render_indexes(cx))
+                            })
+                            .when(!self.foreign_keys.is_empty(), |this| {
+                                this.child(self.render_foreign_keys(cx))
+                            }),
+                    }),
+            )
+    }
+}
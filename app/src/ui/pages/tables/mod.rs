@@ -1,2 +1,5 @@
+pub mod ddl;
+pub mod designer;
+pub mod structure;
 pub mod table;
 pub mod table_delegate;
@@ -0,0 +1,275 @@
+use db_sight_core::{TableIndex, TableStructure};
+use gpui::{
+    div, prelude::FluentBuilder, px, App, Div, InteractiveElement, ParentElement,
+    StatefulInteractiveElement, Styled,
+};
+use gpui_component::{h_flex, v_flex, ActiveTheme, StyledExt};
+
+use crate::core::I18n;
+
+/// Read-only view of a table definition: columns, indexes, constraints, triggers and
+/// storage options, one section each
+pub fn render_structure(structure: &TableStructure, cx: &App) -> Div {
+    let i18n = cx.global::<I18n>();
+    let yes = |flag: bool| {
+        if flag {
+            i18n.t("structure.yes")
+        } else {
+            String::new()
+        }
+    };
+
+    let columns = structure
+        .columns
+        .iter()
+        .map(|detail| {
+            let column = &detail.column;
+            vec![
+                column.name.clone(),
+                column.data_type.clone(),
+                yes(column.nullable),
+                column.default.clone().unwrap_or_default(),
+                column
+                    .primary_key
+                    .map(|position| position.to_string())
+                    .unwrap_or_default(),
+                yes(detail.auto_increment),
+                detail.charset.clone().unwrap_or_default(),
+                detail.collation.clone().unwrap_or_default(),
+                detail.comment.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    let indexes = structure
+        .indexes
+        .iter()
+        .map(|index| {
+            vec![
+                index.name.clone(),
+                index_kind(index, i18n),
+                index_columns(index),
+                index.method.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    let foreign_keys = structure
+        .foreign_keys
+        .iter()
+        .map(|key| {
+            vec![
+                key.name.clone(),
+                key.columns.join(", "),
+                format!(
+                    "{}.{} ({})",
+                    key.referenced_schema,
+                    key.referenced_table,
+                    key.referenced_columns.join(", ")
+                ),
+                key.on_update.clone(),
+                key.on_delete.clone(),
+            ]
+        })
+        .collect();
+    let checks = structure
+        .checks
+        .iter()
+        .map(|check| vec![check.name.clone(), check.expression.clone()])
+        .collect();
+    let triggers = structure
+        .triggers
+        .iter()
+        .map(|trigger| {
+            vec![
+                trigger.name.clone(),
+                trigger.timing.clone(),
+                trigger.event.clone(),
+                trigger.statement.clone(),
+            ]
+        })
+        .collect();
+
+    let options = &structure.options;
+    let options: Vec<(String, String)> = [
+        ("structure.engine", options.engine.clone()),
+        ("structure.row-format", options.row_format.clone()),
+        ("structure.collation", options.collation.clone()),
+        ("structure.size", options.size_bytes.map(format_size)),
+        ("structure.comment", options.comment.clone()),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some((i18n.t(key), value?)))
+    .collect();
+
+    let headers = |keys: &[&str]| keys.iter().map(|key| i18n.t(key)).collect::<Vec<_>>();
+    div().size_full().bg(cx.theme().background).child(
+        v_flex()
+            .id("table-structure")
+            .size_full()
+            .overflow_y_scroll()
+            .gap_4()
+            .p_3()
+            .text_sm()
+            .text_color(cx.theme().foreground)
+            .when(!options.is_empty(), |this| {
+                this.child(
+                    v_flex()
+                        .gap_1()
+                        .child(section_title(i18n.t("structure.options")))
+                        .children(options.into_iter().map(|(label, value)| {
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .w(px(120.))
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(label),
+                                )
+                                .child(value)
+                        })),
+                )
+            })
+            .child(section(
+                i18n.t("structure.columns"),
+                headers(&[
+                    "structure.name",
+                    "structure.type",
+                    "structure.nullable",
+                    "structure.default",
+                    "structure.primary-key",
+                    "structure.auto-increment",
+                    "structure.charset",
+                    "structure.collation",
+                    "structure.comment",
+                ]),
+                columns,
+                cx,
+            ))
+            .child(section(
+                i18n.t("structure.indexes"),
+                headers(&[
+                    "structure.name",
+                    "structure.index-kind",
+                    "structure.index-columns",
+                    "structure.index-method",
+                ]),
+                indexes,
+                cx,
+            ))
+            .child(section(
+                i18n.t("structure.foreign-keys"),
+                headers(&[
+                    "structure.name",
+                    "structure.index-columns",
+                    "structure.references",
+                    "structure.on-update",
+                    "structure.on-delete",
+                ]),
+                foreign_keys,
+                cx,
+            ))
+            .child(section(
+                i18n.t("structure.checks"),
+                headers(&["structure.name", "structure.expression"]),
+                checks,
+                cx,
+            ))
+            .child(section(
+                i18n.t("structure.triggers"),
+                headers(&[
+                    "structure.name",
+                    "structure.timing",
+                    "structure.event",
+                    "structure.statement",
+                ]),
+                triggers,
+                cx,
+            )),
+    )
+}
+
+fn section_title(title: String) -> Div {
+    div().font_semibold().child(title)
+}
+
+/// A titled grid, or just the title and a note when there are no rows
+fn section(title: String, headers: Vec<String>, rows: Vec<Vec<String>>, cx: &App) -> Div {
+    let row = |cells: Vec<String>| {
+        h_flex()
+            .gap_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .children(
+                cells
+                    .into_iter()
+                    .map(|cell| div().flex_1().min_w(px(80.)).child(cell)),
+            )
+    };
+    let empty = rows.is_empty();
+    v_flex()
+        .gap_1()
+        .child(section_title(title))
+        .when(empty, |this| {
+            this.child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(cx.global::<I18n>().t("structure.none")),
+            )
+        })
+        .when(!empty, |this| {
+            this.child(
+                row(headers)
+                    .text_color(cx.theme().muted_foreground)
+                    .font_semibold(),
+            )
+            .children(rows.into_iter().map(row))
+        })
+}
+
+fn index_kind(index: &TableIndex, i18n: &I18n) -> String {
+    let kind = if index.primary {
+        i18n.t("structure.primary")
+    } else if index.unique {
+        i18n.t("structure.unique")
+    } else {
+        i18n.t("structure.index")
+    };
+    if index.is_composite() {
+        format!("{} ({})", kind, i18n.t("structure.composite"))
+    } else {
+        kind
+    }
+}
+
+/// Key columns as in an index definition, e.g. `name(10), created_at DESC`
+fn index_columns(index: &TableIndex) -> String {
+    index
+        .columns
+        .iter()
+        .map(|column| {
+            let mut text = column.name.clone();
+            if let Some(length) = column.prefix_length {
+                text.push_str(&format!("({})", length));
+            }
+            if column.descending {
+                text.push_str(" DESC");
+            }
+            text
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
@@ -1,136 +1,1392 @@
-use db_sight_core::{events::SelectedTableChanged, DBManager, TableDataPage};
-use gpui::{div, App, AppContext, Context, Entity, ParentElement, Render, Styled, Window};
+use std::rc::Rc;
+
+use db_sight_core::{
+    events::{ObjectDdlRequested, SelectedKeyChanged, SelectedTableChanged},
+    sql::parse_filter,
+    BoundStatement, CellValue, DBError, DBManager, DocumentPage, KeyEntry, ObjectKind, ObjectRef,
+    OrderBy, QueryParam, RowChange, RowCount, SortDirection, TableColumn, TableDataPage,
+    TableQuery, TableStructure,
+};
+use gpui::{
+    div, prelude::FluentBuilder, App, AppContext, Context, Div, Entity, InteractiveElement,
+    ParentElement, Render, StatefulInteractiveElement, Styled, Window,
+};
 use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    tab::{Tab, TabBar},
     table::{Table, TableState},
-    v_flex, ActiveTheme, StyledExt,
+    v_flex, ActiveTheme, Disableable, Sizable, StyledExt,
 };
 
 use crate::{
     core::I18n,
-    ui::{pages::tables::table_delegate::DatabaseTableDelegate, state::AppTableState},
+    ui::{
+        pages::tables::{
+            ddl::render_ddl,
+            designer::{DesignApplied, TableDesigner},
+            structure::render_structure,
+            table_delegate::{CellEdit, DatabaseTableDelegate, StagedChanges},
+        },
+        state::AppTableState,
+    },
 };
 
+/// Rows fetched per request, until another page size is picked
+const PAGE_SIZE: u64 = 100;
+const PAGE_SIZES: [u64; 4] = [50, 100, 200, 500];
+
+/// What is shown of the current table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TableView {
+    Data,
+    Structure,
+    Ddl,
+    Design,
+}
+
 pub struct PageTables {
     data: Option<TableDataPage>,
     loading: bool,
-    current_table: Option<String>,
+    /// Object shown, only tables and views have rows
+    current: Option<ObjectRef>,
+    /// Sort, filter and page of the current table
+    query: TableQuery,
+    filter_input: Entity<InputState>,
+    /// Filter that didn't parse, or the error of the last fetch
+    query_error: Option<String>,
+    /// Load the next rows when scrolling to the end, instead of showing pages
+    infinite_scroll: bool,
+    /// The last fetch returned a full page, so there may be more rows
+    has_more: bool,
+    /// Rows are being appended, the loaded ones stay visible meanwhile
+    loading_more: bool,
+    /// The connection can write changed rows back
+    table_editor: bool,
+    /// The connection can alter tables
+    table_designer: bool,
+    /// Pending changes to the columns, indexes and foreign keys of the current table
+    designer: Option<Entity<TableDesigner>>,
+    /// Columns of the current table, for the defaults of new rows
+    table_columns: Vec<TableColumn>,
+    /// Rows changed, added or deleted in the grid, not yet written to the table
+    staged: StagedChanges,
+    /// Row and column of the cell being edited in `edit_input`
+    editing: Option<(usize, usize)>,
+    edit_input: Entity<InputState>,
+    /// Statements of the staged changes, shown for review before applying them
+    preview: Option<Vec<BoundStatement>>,
+    applying: bool,
+    /// Why the changes couldn't be reviewed or applied
+    edit_error: Option<String>,
+    /// Key shown when browsing a key-value connection
+    key_entry: Option<KeyEntry>,
+    /// Raw documents of the current collection, for document stores
+    documents: Option<DocumentPage>,
+    show_documents: bool,
+    view: TableView,
+    /// Definition of the current table, loaded when first shown
+    structure: Option<TableStructure>,
+    structure_error: Option<String>,
+    /// DDL of the current table, loaded when first shown
+    ddl: Option<String>,
+    ddl_error: Option<String>,
+    /// Read-only editor highlighting `ddl`
+    ddl_editor: Entity<InputState>,
     table_state: Option<Entity<TableState<DatabaseTableDelegate>>>,
 }
 
 impl PageTables {
-    fn new(cx: &mut Context<Self>) -> Self {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
         let table_state = cx.global::<AppTableState>().state.clone();
+        cx.subscribe_in(
+            &table_state,
+            window,
+            |this: &mut Self, _, event: &SelectedTableChanged, window, cx| {
+                this.load_table_data(event.object.clone(), window, cx);
+            },
+        )
+        .detach();
         cx.subscribe(
             &table_state,
-            |this: &mut Self, _, event: &SelectedTableChanged, cx| {
-                this.load_table_data(event.table_name.clone(), cx);
+            |this: &mut Self, _, event: &SelectedKeyChanged, cx| {
+                this.load_key(event.schema.clone(), event.key.clone(), cx);
+            },
+        )
+        .detach();
+        cx.subscribe_in(
+            &table_state,
+            window,
+            |this: &mut Self, _, event: &ObjectDdlRequested, window, cx| {
+                if !event.object.has_rows() {
+                    this.load_object(event.object.clone(), window, cx);
+                } else if this.current.as_ref() == Some(&event.object) {
+                    this.set_view(TableView::Ddl, window, cx);
+                } else {
+                    // Shown once the columns of the table are loaded
+                    this.view = TableView::Ddl;
+                    this.load_table_data(event.object.clone(), window, cx);
+                }
+            },
+        )
+        .detach();
+
+        let placeholder = cx.global::<I18n>().t("table.filter-placeholder");
+        let filter_input = cx.new(|cx| InputState::new(window, cx).placeholder(placeholder));
+        cx.subscribe(
+            &filter_input,
+            |this: &mut Self, _, event: &InputEvent, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.apply_filter(cx);
+                }
+            },
+        )
+        .detach();
+
+        // Enter stages the typed value, Ctrl+Enter (Cmd+Enter on macOS) stages NULL
+        let edit_input = cx.new(|cx| InputState::new(window, cx));
+        cx.subscribe(
+            &edit_input,
+            |this: &mut Self, _, event: &InputEvent, cx| match event {
+                InputEvent::PressEnter { secondary, .. } => this.finish_edit(*secondary, cx),
+                InputEvent::Blur => this.cancel_edit(cx),
+                _ => {}
             },
         )
         .detach();
+
+        let ddl_editor = cx.new(|cx| {
+            InputState::new(window, cx)
+                .code_editor("sql")
+                .line_number(true)
+        });
+
         Self {
             data: None,
             loading: false,
-            current_table: None,
+            current: None,
+            query: TableQuery::new(0, PAGE_SIZE),
+            filter_input,
+            query_error: None,
+            infinite_scroll: false,
+            has_more: false,
+            loading_more: false,
+            table_editor: false,
+            table_designer: false,
+            designer: None,
+            table_columns: Vec::new(),
+            staged: StagedChanges::default(),
+            editing: None,
+            edit_input,
+            preview: None,
+            applying: false,
+            edit_error: None,
+            key_entry: None,
+            documents: None,
+            show_documents: false,
+            view: TableView::Data,
+            structure: None,
+            structure_error: None,
+            ddl: None,
+            ddl_error: None,
+            ddl_editor,
             table_state: None,
         }
     }
 
-    fn load_table_data(&mut self, table_name: String, cx: &mut Context<Self>) {
-        self.loading = true;
-        self.current_table = Some(table_name.clone());
+    /// Forget everything loaded of the current object before showing `object`
+    fn reset_object(&mut self, object: ObjectRef, window: &mut Window, cx: &mut Context<Self>) {
+        self.current = Some(object);
+        self.query = TableQuery::new(0, self.query.limit);
+        self.query_error = None;
+        self.key_entry = None;
+        self.documents = None;
         self.data = None;
+        self.structure = None;
+        self.structure_error = None;
+        self.ddl = None;
+        self.ddl_error = None;
+        self.designer = None;
+        self.table_columns.clear();
+        self.discard_changes(cx);
+        self.filter_input
+            .update(cx, |input, cx| input.set_value("", window, cx));
+    }
+
+    /// Show the definition of a routine, trigger or event, which have no rows
+    fn load_object(&mut self, object: ObjectRef, window: &mut Window, cx: &mut Context<Self>) {
+        self.reset_object(object, window, cx);
+        self.loading = false;
+        self.view = TableView::Ddl;
+        self.load_ddl(window, cx);
+        cx.notify();
+    }
+
+    /// Show the rows of a table or view, and whatever else the current view needs
+    fn load_table_data(&mut self, object: ObjectRef, window: &mut Window, cx: &mut Context<Self>) {
+        self.reset_object(object.clone(), window, cx);
+        self.loading = true;
         cx.notify();
 
         let db_manager = cx.global::<DBManager>().clone();
-        let connection_id = db_manager.get_selected_connection();
-
-        if let Some(conn_id) = connection_id {
-            let conn_id_str = conn_id.to_string();
-            let entity = cx.entity().clone();
-            cx.spawn(async move |_, cx| {
-                // Fetch driver asynchronously
-                let driver = db_manager.get_connection(&conn_id_str).await;
-
-                if let Some(driver) = driver {
-                    // Find schema
-                    let mut target_schema = None;
-                    if let Ok(schemas) = driver.list_schemas().await {
-                        for schema in schemas {
-                            if let Ok(tables) = driver.list_tables(&schema.name).await {
-                                if tables.iter().any(|t| t.name == table_name) {
-                                    target_schema = Some(schema.name);
-                                    break;
-                                }
-                            }
-                        }
-                    }
+        cx.spawn_in(window, async move |this, cx| {
+            let driver = db_manager
+                .get_connection(&object.connection_id.to_string())
+                .await;
 
-                    if let Some(schema) = target_schema {
-                        match driver.fetch_table_data(&schema, &table_name, 0, 100).await {
-                            Ok(page) => {
-                                match cx.update_entity(&entity, |this, cx| {
-                                    this.data = Some(page.clone());
-                                    this.loading = false;
-
-                                    // Update table state if it exists
-                                    if let Some(table_state) = &this.table_state {
-                                        table_state.update(cx, |table_state, cx| {
-                                            table_state.delegate_mut().update_data(page.clone());
-                                            table_state.delegate_mut().set_loading(false);
-                                            cx.notify();
-                                        });
-                                    }
+            // Defaults and nullability of the columns, for new rows
+            let table_columns = match driver.as_ref().and_then(|d| d.as_schema_browser()) {
+                Some(browser) => browser
+                    .get_table_columns(&object.schema, &object.name)
+                    .await
+                    .map_err(|e| eprintln!("Failed to get table columns: {}", e))
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            };
+            let table_designer = driver
+                .as_ref()
+                .is_some_and(|d| d.as_table_designer().is_some());
 
-                                    cx.notify();
-                                }) {
-                                    Ok(_) => {}
-                                    Err(e) => {
-                                        eprintln!("Failed to update entity: {:?}", e);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Failed to fetch table data: {}", e);
-                                if let Err(update_err) = cx.update_entity(&entity, |this, cx| {
-                                    this.loading = false;
-                                    cx.notify();
-                                }) {
-                                    eprintln!(
-                                        "Failed to update entity after error: {:?}",
-                                        update_err
-                                    );
-                                }
+            this.update_in(cx, |this, window, cx| {
+                // Another table may have been selected meanwhile
+                if this.current.as_ref() != Some(&object) {
+                    return;
+                }
+                this.table_columns = table_columns;
+                this.table_designer = table_designer;
+                if !this.available_views().contains(&this.view) {
+                    this.view = TableView::Data;
+                }
+                this.loading = false;
+                this.fetch(false, cx);
+                match this.view {
+                    TableView::Data => {}
+                    TableView::Structure => this.load_structure(cx),
+                    TableView::Ddl => this.load_ddl(window, cx),
+                    TableView::Design => this.open_designer(window, cx),
+                }
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Fetch the first rows of the current table again with the current sort and filter
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.query.offset = 0;
+        self.query.after = None;
+        self.query_error = None;
+        self.fetch(false, cx);
+    }
+
+    /// Fetch the rows `query` points at, replacing the loaded rows or appending to them
+    fn fetch(&mut self, append: bool, cx: &mut Context<Self>) {
+        let Some(object) = self.current.clone() else {
+            return;
+        };
+        let db_manager = cx.global::<DBManager>().clone();
+        let conn_id_str = object.connection_id.to_string();
+        let query = self.query.clone();
+        let entity = cx.entity().clone();
+        if append {
+            self.loading_more = true;
+        } else {
+            self.loading = true;
+        }
+        cx.notify();
+
+        cx.spawn(async move |_, cx| {
+            let driver = db_manager.get_connection(&conn_id_str).await;
+            let result = match driver.as_ref().and_then(|d| d.as_schema_browser()) {
+                Some(browser) => {
+                    browser
+                        .fetch_table_data(&object.schema, &object.name, &query)
+                        .await
+                }
+                None => Err(DBError::ConnectionError("Not connected".to_string())),
+            };
+            let table_editor = driver
+                .as_ref()
+                .is_some_and(|d| d.as_table_editor().is_some());
+            // Nested fields are only readable in the raw document view
+            let document_browser = driver.as_ref().and_then(|d| d.as_document());
+            let documents = match document_browser {
+                Some(document_browser) if result.is_ok() => document_browser
+                    .fetch_documents(&object.schema, &object.name, &query)
+                    .await
+                    .map_err(|e| eprintln!("Failed to fetch documents: {}", e))
+                    .ok(),
+                _ => None,
+            };
+
+            cx.update_entity(&entity, |this, cx| {
+                // A newer table or query may have been requested meanwhile
+                if this.current.as_ref() != Some(&object) || this.query != query {
+                    return;
+                }
+                match result {
+                    Ok(mut page) => {
+                        this.has_more = page.rows.len() as u64 >= query.limit;
+                        this.table_editor = table_editor;
+                        if !append {
+                            // The edited cell may be on another row now
+                            this.editing = None;
+                        }
+                        if append {
+                            if let Some(loaded) = this.data.take() {
+                                page.rows.splice(0..0, loaded.rows);
                             }
                         }
-                    } else {
-                        eprintln!("Table {} not found in any schema", table_name);
-                        if let Err(e) = cx.update_entity(&entity, |this, cx| {
-                            this.loading = false;
-                            cx.notify();
-                        }) {
-                            eprintln!("Failed to update entity: {:?}", e);
+                        this.data = Some(page.clone());
+                        this.documents = match (append, this.documents.take(), documents) {
+                            (true, Some(mut loaded), Some(documents)) => {
+                                loaded.documents.extend(documents.documents);
+                                loaded.total = documents.total;
+                                Some(loaded)
+                            }
+                            (_, _, documents) => documents,
+                        };
+
+                        // Update table state if it exists
+                        if let Some(table_state) = &this.table_state {
+                            table_state.update(cx, |table_state, cx| {
+                                table_state.delegate_mut().update_data(page);
+                                table_state.delegate_mut().set_loading(false);
+                                cx.notify();
+                            });
                         }
                     }
-                } else {
-                    if let Err(e) = cx.update_entity(&entity, |this, cx| {
-                        this.loading = false;
-                        cx.notify();
-                    }) {
-                        eprintln!("Failed to update entity: {:?}", e);
+                    Err(e) => {
+                        eprintln!("Failed to fetch table data: {}", e);
+                        this.query_error = Some(e.to_string());
                     }
                 }
+                this.loading = false;
+                this.loading_more = false;
+                cx.notify();
+            })?;
 
-                Ok::<_, anyhow::Error>(())
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Views offered for the current object: views can't be designed, and routines,
+    /// triggers and events only have a definition
+    fn available_views(&self) -> Vec<TableView> {
+        match self.current.as_ref().map(|object| object.kind) {
+            Some(ObjectKind::Table) if self.table_designer => vec![
+                TableView::Data,
+                TableView::Structure,
+                TableView::Ddl,
+                TableView::Design,
+            ],
+            Some(ObjectKind::Table | ObjectKind::View) => {
+                vec![TableView::Data, TableView::Structure, TableView::Ddl]
+            }
+            _ => vec![TableView::Ddl],
+        }
+    }
+
+    fn set_view(&mut self, view: TableView, window: &mut Window, cx: &mut Context<Self>) {
+        self.view = view;
+        match view {
+            TableView::Structure if self.structure.is_none() => self.load_structure(cx),
+            TableView::Ddl if self.ddl.is_none() => self.load_ddl(window, cx),
+            TableView::Design if self.designer.is_none() => self.open_designer(window, cx),
+            _ => {}
+        }
+        cx.notify();
+    }
+
+    fn load_structure(&mut self, cx: &mut Context<Self>) {
+        let Some(object) = self.current.clone() else {
+            return;
+        };
+        let db_manager = cx.global::<DBManager>().clone();
+        let entity = cx.entity().clone();
+        self.structure_error = None;
+
+        cx.spawn(async move |_, cx| {
+            let driver = db_manager
+                .get_connection(&object.connection_id.to_string())
+                .await;
+            let result = match driver.as_ref().and_then(|d| d.as_schema_browser()) {
+                Some(browser) => {
+                    browser
+                        .get_table_structure(&object.schema, &object.name)
+                        .await
+                }
+                None => Err(DBError::ConnectionError("Not connected".to_string())),
+            };
+
+            cx.update_entity(&entity, |this, cx| {
+                if this.current.as_ref() != Some(&object) {
+                    return;
+                }
+                match result {
+                    Ok(structure) => this.structure = Some(structure),
+                    Err(e) => {
+                        eprintln!("Failed to load table structure: {}", e);
+                        this.structure_error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Start designing the current table from its columns. Once the design is applied
+    /// the table is loaded again, with its new columns.
+    fn open_designer(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(object) = self.current.clone() else {
+            return;
+        };
+        let designer = TableDesigner::view(object.clone(), self.table_columns.clone(), window, cx);
+        cx.subscribe_in(
+            &designer,
+            window,
+            move |this: &mut Self, _, _: &DesignApplied, window, cx| {
+                this.load_table_data(object.clone(), window, cx);
+            },
+        )
+        .detach();
+        self.designer = Some(designer);
+    }
+
+    fn load_ddl(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(object) = self.current.clone() else {
+            return;
+        };
+        let db_manager = cx.global::<DBManager>().clone();
+        self.ddl_error = None;
+
+        cx.spawn_in(window, async move |this, cx| {
+            let driver = db_manager
+                .get_connection(&object.connection_id.to_string())
+                .await;
+            let result = match driver.as_ref().and_then(|d| d.as_schema_browser()) {
+                Some(browser) => {
+                    browser
+                        .get_object_ddl(&object.schema, &object.name, object.kind)
+                        .await
+                }
+                None => Err(DBError::ConnectionError("Not connected".to_string())),
+            };
+
+            this.update_in(cx, |this, window, cx| {
+                if this.current.as_ref() != Some(&object) {
+                    return;
+                }
+                match result {
+                    Ok(ddl) => {
+                        this.ddl_editor
+                            .update(cx, |editor, cx| editor.set_value(ddl.clone(), window, cx));
+                        this.ddl = Some(ddl);
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load DDL: {}", e);
+                        this.ddl_error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Cycle the sort of a column through ascending, descending and unsorted
+    fn toggle_sort(&mut self, column: &str, cx: &mut Context<Self>) {
+        let direction = match self.query.order_by.first() {
+            Some(order) if order.column == column => match order.direction {
+                SortDirection::Asc => Some(SortDirection::Desc),
+                SortDirection::Desc => None,
+            },
+            _ => Some(SortDirection::Asc),
+        };
+        self.query.order_by = direction
+            .map(|direction| OrderBy {
+                column: column.to_string(),
+                direction,
             })
-            .detach();
+            .into_iter()
+            .collect();
+        self.reload(cx);
+    }
+
+    fn apply_filter(&mut self, cx: &mut Context<Self>) {
+        let text = self.filter_input.read(cx).value().trim().to_string();
+        let filter = if text.is_empty() {
+            None
         } else {
-            self.loading = false;
+            match parse_filter(&text) {
+                Ok(filter) => Some(filter),
+                Err(e) => {
+                    self.query_error = Some(e.to_string());
+                    cx.notify();
+                    return;
+                }
+            }
+        };
+        self.query.filter = filter;
+        self.reload(cx);
+    }
+
+    fn clear_filter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.filter_input
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        self.apply_filter(cx);
+    }
+
+    /// Primary key of the last loaded row, to seek past it. Only rows read in key
+    /// order can be paged this way.
+    fn last_key(&self) -> Option<Vec<QueryParam>> {
+        if !self.query.order_by.is_empty() {
+            return None;
+        }
+        let data = self.data.as_ref()?;
+        if data.primary_key.is_empty() {
+            return None;
+        }
+        let key = data.row_key(data.rows.len().checked_sub(1)?)?;
+        Some(key.iter().map(|(_, value)| value.to_param()).collect())
+    }
+
+    fn go_to_page(&mut self, page: u64, cx: &mut Context<Self>) {
+        self.query.offset = page * self.query.limit;
+        self.query.after = None;
+        self.fetch(false, cx);
+    }
+
+    fn next_page(&mut self, cx: &mut Context<Self>) {
+        self.query.after = self.last_key();
+        self.query.offset += self.query.limit;
+        self.fetch(false, cx);
+    }
+
+    /// Append the rows after the loaded ones, for infinite scroll
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if self.loading || self.loading_more || !self.has_more {
+            return;
+        }
+        self.query.after = self.last_key();
+        self.query.offset = self.data.as_ref().map_or(0, |data| data.rows.len() as u64);
+        self.fetch(true, cx);
+    }
+
+    fn set_page_size(&mut self, page_size: u64, cx: &mut Context<Self>) {
+        self.query.limit = page_size;
+        self.reload(cx);
+    }
+
+    fn set_infinite_scroll(&mut self, infinite_scroll: bool, cx: &mut Context<Self>) {
+        self.infinite_scroll = infinite_scroll;
+        self.reload(cx);
+    }
+
+    /// Replace the estimated total with a `COUNT(*)`
+    fn count_exactly(&mut self, cx: &mut Context<Self>) {
+        self.query.count = RowCount::Exact;
+        self.fetch(false, cx);
+    }
+
+    /// Open the editor on a cell. Loaded rows are refused unless they can be found
+    /// again by a key, new rows always take values.
+    fn start_edit(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(data) = &self.data else {
+            return;
+        };
+        if self.current.is_none() || self.preview.is_some() || self.applying {
+            return;
+        }
+        let i18n = cx.global::<I18n>();
+        if !self.table_editor {
+            self.edit_error = Some(i18n.t("table.edit-unsupported"));
+            cx.notify();
+            return;
+        }
+        let value = match row_ix.checked_sub(data.rows.len()) {
+            Some(new_ix) => self
+                .staged
+                .inserts
+                .get(new_ix)
+                .and_then(|row| row.get(col_ix)?.as_ref()),
+            None => {
+                let (Some(key), Some(column)) = (data.row_key(row_ix), data.columns.get(col_ix))
+                else {
+                    self.edit_error = Some(i18n.t("table.edit-no-key"));
+                    cx.notify();
+                    return;
+                };
+                if self.staged.is_deleted(&key) {
+                    return;
+                }
+                self.staged
+                    .edited_value(&key, &column.name)
+                    .or_else(|| data.rows.get(row_ix)?.get(col_ix))
+            }
+        };
+        let text = match value {
+            Some(CellValue::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        };
+
+        self.edit_error = None;
+        self.editing = Some((row_ix, col_ix));
+        self.edit_input.update(cx, |input, cx| {
+            input.set_value(text, window, cx);
+            input.focus(window, cx);
+        });
+        cx.notify();
+    }
+
+    /// Stage the value typed into the editor, or NULL. Typing the loaded value back
+    /// drops the edit.
+    fn finish_edit(&mut self, null: bool, cx: &mut Context<Self>) {
+        let (Some((row_ix, col_ix)), Some(data)) = (self.editing.take(), &self.data) else {
+            return;
+        };
+        let Some(column) = data.columns.get(col_ix) else {
+            return;
+        };
+        let value = if null {
+            CellValue::Null
+        } else {
+            CellValue::parse(&self.edit_input.read(cx).value(), column.kind)
+        };
+
+        if let Some(new_ix) = row_ix.checked_sub(data.rows.len()) {
+            if let Some(cell) = self
+                .staged
+                .inserts
+                .get_mut(new_ix)
+                .and_then(|row| row.get_mut(col_ix))
+            {
+                *cell = Some(value);
+            }
+        } else if let (Some(key), Some(loaded)) = (
+            data.row_key(row_ix),
+            data.rows.get(row_ix).and_then(|row| row.get(col_ix)),
+        ) {
+            self.staged
+                .edits
+                .retain(|edit| !(edit.key == key && edit.column == column.name));
+            if &value != loaded {
+                self.staged.edits.push(CellEdit {
+                    key,
+                    column: column.name.clone(),
+                    value,
+                });
+            }
+        }
+        cx.notify();
+    }
+
+    fn cancel_edit(&mut self, cx: &mut Context<Self>) {
+        if self.editing.take().is_some() {
             cx.notify();
         }
     }
 
-    pub fn view(cx: &mut App) -> Entity<Self> {
-        cx.new(|cx| Self::new(cx))
+    /// Value of a column in a new row: the column default when it has one or is
+    /// part of the primary key, which is usually generated, otherwise NULL where
+    /// allowed. Columns that need a value are left to the default as well, and
+    /// caught before the changes are reviewed.
+    fn new_row_value(&self, column: &str) -> Option<CellValue> {
+        let column = self.table_columns.iter().find(|c| c.name == column)?;
+        if column.default.is_some() || column.primary_key.is_some() || !column.nullable {
+            None
+        } else {
+            Some(CellValue::Null)
+        }
+    }
+
+    /// Stage a new row at the end of the grid
+    fn add_row(&mut self, cx: &mut Context<Self>) {
+        let Some(data) = &self.data else {
+            return;
+        };
+        let row = data
+            .columns
+            .iter()
+            .map(|column| self.new_row_value(&column.name))
+            .collect();
+        self.staged.inserts.push(row);
+        self.edit_error = None;
+        cx.notify();
+    }
+
+    fn selected_row(&self, cx: &App) -> Option<usize> {
+        self.table_state.as_ref()?.read(cx).selected_row()
+    }
+
+    /// Stage a copy of the selected row. Primary key columns take their default, so
+    /// that generated keys don't clash.
+    fn duplicate_row(&mut self, cx: &mut Context<Self>) {
+        let (Some(row_ix), Some(data)) = (self.selected_row(cx), &self.data) else {
+            return;
+        };
+        let row = match row_ix.checked_sub(data.rows.len()) {
+            Some(new_ix) => self.staged.inserts.get(new_ix).cloned(),
+            None => data.row_key(row_ix).and_then(|key| {
+                let values = data.rows.get(row_ix)?;
+                Some(
+                    data.columns
+                        .iter()
+                        .zip(values)
+                        .map(|(column, value)| {
+                            if data.key_columns().contains(&column.name) {
+                                self.new_row_value(&column.name)
+                            } else {
+                                self.staged
+                                    .edited_value(&key, &column.name)
+                                    .or(Some(value))
+                                    .cloned()
+                            }
+                        })
+                        .collect(),
+                )
+            }),
+        };
+        if let Some(row) = row {
+            self.staged.inserts.push(row);
+            self.edit_error = None;
+            cx.notify();
+        }
+    }
+
+    /// Stage the deletion of the selected row, or take it back. A new row is
+    /// dropped right away.
+    fn delete_row(&mut self, cx: &mut Context<Self>) {
+        let (Some(row_ix), Some(data)) = (self.selected_row(cx), &self.data) else {
+            return;
+        };
+        match row_ix.checked_sub(data.rows.len()) {
+            Some(new_ix) if new_ix < self.staged.inserts.len() => {
+                self.staged.inserts.remove(new_ix);
+            }
+            Some(_) => return,
+            None => match data.row_key(row_ix) {
+                Some(key) => self.staged.toggle_delete(key),
+                None => {
+                    self.edit_error = Some(cx.global::<I18n>().t("table.edit-no-key"));
+                }
+            },
+        }
+        self.editing = None;
+        cx.notify();
+    }
+
+    /// Column of a new row left without a value although it allows neither NULL nor
+    /// the default
+    fn missing_value(&self) -> Option<String> {
+        let data = self.data.as_ref()?;
+        self.staged.inserts.iter().find_map(|row| {
+            data.columns.iter().zip(row).find_map(|(column, value)| {
+                let table_column = self.table_columns.iter().find(|c| c.name == column.name)?;
+                let required = table_column.default.is_none()
+                    && table_column.primary_key.is_none()
+                    && !table_column.nullable;
+                let missing = match value {
+                    None => required,
+                    Some(CellValue::Null) => !table_column.nullable,
+                    Some(_) => false,
+                };
+                missing.then(|| column.name.clone())
+            })
+        })
+    }
+
+    fn row_changes(&self) -> Vec<RowChange> {
+        match &self.data {
+            Some(data) => self.staged.row_changes(&data.columns),
+            None => Vec::new(),
+        }
+    }
+
+    /// Generate the statements of the staged changes and show them for review
+    fn review_changes(&mut self, cx: &mut Context<Self>) {
+        let Some(object) = self.current.clone() else {
+            return;
+        };
+        let db_manager = cx.global::<DBManager>().clone();
+        self.editing = None;
+        if let Some(column) = self.missing_value() {
+            self.edit_error = Some(
+                cx.global::<I18n>()
+                    .t_with("table.edit-missing-value", &[("column", &column)]),
+            );
+            cx.notify();
+            return;
+        }
+        let conn_id_str = object.connection_id.to_string();
+        let changes = self.row_changes();
+        let entity = cx.entity().clone();
+        self.edit_error = None;
+
+        cx.spawn(async move |_, cx| {
+            let driver = db_manager.get_connection(&conn_id_str).await;
+            let result = match driver.as_ref().and_then(|d| d.as_table_editor()) {
+                Some(editor) => {
+                    editor
+                        .preview_changes(&object.schema, &object.name, &changes)
+                        .await
+                }
+                None => Err(DBError::ConnectionError("Not connected".to_string())),
+            };
+
+            cx.update_entity(&entity, |this, cx| {
+                if this.current.as_ref() != Some(&object) {
+                    return;
+                }
+                match result {
+                    Ok(statements) => this.preview = Some(statements),
+                    Err(e) => this.edit_error = Some(e.to_string()),
+                }
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Write the staged changes in one transaction, then read the rows back
+    fn apply_changes(&mut self, cx: &mut Context<Self>) {
+        let Some(object) = self.current.clone() else {
+            return;
+        };
+        let db_manager = cx.global::<DBManager>().clone();
+        let conn_id_str = object.connection_id.to_string();
+        let changes = self.row_changes();
+        let entity = cx.entity().clone();
+        self.applying = true;
+        self.edit_error = None;
+        cx.notify();
+
+        cx.spawn(async move |_, cx| {
+            let driver = db_manager.get_connection(&conn_id_str).await;
+            let result = match driver.as_ref().and_then(|d| d.as_table_editor()) {
+                Some(editor) => {
+                    editor
+                        .apply_changes(&object.schema, &object.name, &changes)
+                        .await
+                }
+                None => Err(DBError::ConnectionError("Not connected".to_string())),
+            };
+
+            cx.update_entity(&entity, |this, cx| {
+                this.applying = false;
+                if this.current.as_ref() != Some(&object) {
+                    return;
+                }
+                match result {
+                    Ok(_) => {
+                        this.staged = StagedChanges::default();
+                        this.preview = None;
+                        this.fetch(false, cx);
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to apply changes: {}", e);
+                        this.edit_error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn discard_changes(&mut self, cx: &mut Context<Self>) {
+        self.staged = StagedChanges::default();
+        self.editing = None;
+        self.preview = None;
+        self.edit_error = None;
+        cx.notify();
+    }
+
+    fn load_key(&mut self, schema: String, key: String, cx: &mut Context<Self>) {
+        self.loading = true;
+        self.current = None;
+        self.query_error = None;
+        self.key_entry = None;
+        self.documents = None;
+        self.data = None;
+        self.discard_changes(cx);
+
+        let db_manager = cx.global::<DBManager>().clone();
+        let Some(conn_id) = db_manager.get_selected_connection() else {
+            self.loading = false;
+            cx.notify();
+            return;
+        };
+        let conn_id_str = conn_id.to_string();
+        let entity = cx.entity().clone();
+
+        cx.spawn(async move |_, cx| {
+            let driver = db_manager.get_connection(&conn_id_str).await;
+            let result = match driver.as_ref().and_then(|driver| driver.as_key_value()) {
+                Some(key_value) => Some(key_value.get_key(&schema, &key).await),
+                None => None,
+            };
+
+            cx.update_entity(&entity, |this, cx| {
+                match result {
+                    Some(Ok(entry)) => {
+                        let page = entry.to_page();
+                        this.data = Some(page.clone());
+                        this.key_entry = Some(entry);
+
+                        if let Some(table_state) = &this.table_state {
+                            table_state.update(cx, |table_state, cx| {
+                                table_state.delegate_mut().update_data(page);
+                                table_state.delegate_mut().set_loading(false);
+                                cx.notify();
+                            });
+                        }
+                    }
+                    Some(Err(e)) => eprintln!("Failed to load key {}: {}", key, e),
+                    None => eprintln!("No key-value connection found."),
+                }
+                this.loading = false;
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn render_key_header(entry: &KeyEntry, cx: &App) -> impl gpui::IntoElement {
+        let i18n = cx.global::<I18n>();
+        let key_type = format!("{}: {}", i18n.t("key.type"), entry.key_type);
+        let ttl = match entry.ttl {
+            Some(seconds) => format!("{}: {}s", i18n.t("key.ttl"), seconds),
+            None => format!("{}: {}", i18n.t("key.ttl"), i18n.t("key.no-expiry")),
+        };
+        h_flex()
+            .gap_4()
+            .px_3()
+            .py_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().background)
+            .text_color(cx.theme().foreground)
+            .child(div().font_semibold().child(entry.key.clone()))
+            .child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(key_type),
+            )
+            .child(div().text_color(cx.theme().muted_foreground).child(ttl))
+    }
+
+    fn render_view_switch(&self, cx: &Context<Self>) -> impl gpui::IntoElement {
+        let i18n = cx.global::<I18n>();
+        h_flex()
+            .px_3()
+            .py_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().background)
+            .child(
+                TabBar::new("document-view-tab")
+                    .segmented()
+                    .selected_index(if self.show_documents { 1 } else { 0 })
+                    .on_click(cx.listener(|this, ix: &usize, _, cx| {
+                        this.show_documents = *ix == 1;
+                        cx.notify();
+                    }))
+                    .child(Tab::new().label(i18n.t("document.grid")))
+                    .child(Tab::new().label(i18n.t("document.json"))),
+            )
+    }
+
+    fn render_table_tabs(&self, cx: &Context<Self>) -> impl gpui::IntoElement {
+        let i18n = cx.global::<I18n>();
+        let views = self.available_views();
+        h_flex()
+            .px_3()
+            .py_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().background)
+            .child(
+                TabBar::new("table-view-tab")
+                    .segmented()
+                    .selected_index(
+                        views
+                            .iter()
+                            .position(|view| *view == self.view)
+                            .unwrap_or_default(),
+                    )
+                    .on_click(cx.listener(|this, ix: &usize, window, cx| {
+                        if let Some(view) = this.available_views().get(*ix).copied() {
+                            this.set_view(view, window, cx);
+                        }
+                    }))
+                    .children(views.iter().map(|view| {
+                        Tab::new().label(i18n.t(match view {
+                            TableView::Data => "table.view-data",
+                            TableView::Structure => "table.view-structure",
+                            TableView::Ddl => "table.view-ddl",
+                            TableView::Design => "table.view-design",
+                        }))
+                    })),
+            )
+    }
+
+    fn render_filter_bar(&self, cx: &Context<Self>) -> impl gpui::IntoElement {
+        let i18n = cx.global::<I18n>();
+        v_flex()
+            .gap_1()
+            .px_3()
+            .py_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().background)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(div().flex_1().child(Input::new(&self.filter_input).small()))
+                    .child(
+                        Button::new("apply-filter")
+                            .small()
+                            .primary()
+                            .label(i18n.t("table.filter-apply"))
+                            .on_click(cx.listener(|this, _, _, cx| this.apply_filter(cx))),
+                    )
+                    .child(
+                        Button::new("clear-filter")
+                            .small()
+                            .ghost()
+                            .label(i18n.t("table.filter-clear"))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.clear_filter(window, cx)),
+                            ),
+                    ),
+            )
+            .when_some(self.query_error.clone(), |this, error| {
+                this.child(div().text_sm().text_color(cx.theme().danger).child(error))
+            })
+    }
+
+    fn render_pagination(
+        &self,
+        data: &TableDataPage,
+        cx: &Context<Self>,
+    ) -> impl gpui::IntoElement {
+        let i18n = cx.global::<I18n>();
+        let limit = self.query.limit.max(1);
+        let total = data.total.to_string();
+        let rows_label = if self.infinite_scroll {
+            i18n.t_with(
+                "table.rows-loaded",
+                &[("loaded", &data.rows.len().to_string()), ("total", &total)],
+            )
+        } else if data.total_estimated {
+            i18n.t_with("table.rows-estimated", &[("total", &total)])
+        } else {
+            i18n.t_with("table.rows-total", &[("total", &total)])
+        };
+        let page = self.query.offset / limit;
+        let pages = data.total.div_ceil(limit).max(page + 1);
+        let at_start = self.query.offset == 0 || self.loading;
+
+        h_flex()
+            .gap_2()
+            .px_3()
+            .py_2()
+            .border_t_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().background)
+            .text_sm()
+            .child(
+                TabBar::new("paging-mode-tab")
+                    .segmented()
+                    .selected_index(if self.infinite_scroll { 1 } else { 0 })
+                    .on_click(cx.listener(|this, ix: &usize, _, cx| {
+                        this.set_infinite_scroll(*ix == 1, cx);
+                    }))
+                    .child(Tab::new().label(i18n.t("table.paging-pages")))
+                    .child(Tab::new().label(i18n.t("table.paging-scroll"))),
+            )
+            .when(!self.infinite_scroll, |this| {
+                this.child(
+                    Button::new("first-page")
+                        .small()
+                        .ghost()
+                        .label(i18n.t("table.first-page"))
+                        .disabled(at_start)
+                        .on_click(cx.listener(|this, _, _, cx| this.go_to_page(0, cx))),
+                )
+                .child(
+                    Button::new("prev-page")
+                        .small()
+                        .ghost()
+                        .label(i18n.t("table.prev-page"))
+                        .disabled(at_start)
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.go_to_page(page.saturating_sub(1), cx)
+                        })),
+                )
+                .child(div().child(i18n.t_with(
+                    "table.page-info",
+                    &[
+                        ("page", &(page + 1).to_string()),
+                        ("pages", &pages.to_string()),
+                    ],
+                )))
+                .child(
+                    Button::new("next-page")
+                        .small()
+                        .ghost()
+                        .label(i18n.t("table.next-page"))
+                        .disabled(!self.has_more || self.loading)
+                        .on_click(cx.listener(|this, _, _, cx| this.next_page(cx))),
+                )
+                .child(
+                    Button::new("last-page")
+                        .small()
+                        .ghost()
+                        .label(i18n.t("table.last-page"))
+                        // An estimate could point past the last row
+                        .disabled(data.total_estimated || page + 1 >= pages || self.loading)
+                        .on_click(
+                            cx.listener(move |this, _, _, cx| this.go_to_page(pages - 1, cx)),
+                        ),
+                )
+            })
+            .child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(rows_label),
+            )
+            .when(data.total_estimated, |this| {
+                this.child(
+                    Button::new("count-rows")
+                        .small()
+                        .ghost()
+                        .label(i18n.t("table.count-exact"))
+                        .disabled(self.loading)
+                        .on_click(cx.listener(|this, _, _, cx| this.count_exactly(cx))),
+                )
+            })
+            .child(div().flex_1())
+            .child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(i18n.t("table.page-size")),
+            )
+            .child(
+                TabBar::new("page-size-tab")
+                    .segmented()
+                    .selected_index(
+                        PAGE_SIZES
+                            .iter()
+                            .position(|size| *size == self.query.limit)
+                            .unwrap_or_default(),
+                    )
+                    .on_click(cx.listener(|this, ix: &usize, _, cx| {
+                        this.set_page_size(PAGE_SIZES[*ix], cx);
+                    }))
+                    .children(
+                        PAGE_SIZES
+                            .iter()
+                            .map(|size| Tab::new().label(size.to_string())),
+                    ),
+            )
+    }
+
+    /// Staged changes with the buttons to review, apply or discard them
+    fn render_edit_bar(&self, cx: &Context<Self>) -> impl gpui::IntoElement {
+        let i18n = cx.global::<I18n>();
+        let status = if self.editing.is_some() {
+            i18n.t("table.edit-hint")
+        } else if self.staged.is_empty() {
+            i18n.t("table.edit-start-hint")
+        } else {
+            i18n.t_with(
+                "table.edit-pending",
+                &[("count", &self.staged.len().to_string())],
+            )
+        };
+        let reviewing = self.preview.is_some();
+
+        v_flex()
+            .gap_1()
+            .px_3()
+            .py_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().background)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(status),
+                    )
+                    .child(div().flex_1())
+                    .when(!reviewing && self.table_editor, |this| {
+                        this.child(
+                            Button::new("add-row")
+                                .small()
+                                .ghost()
+                                .label(i18n.t("table.add-row"))
+                                .on_click(cx.listener(|this, _, _, cx| this.add_row(cx))),
+                        )
+                        .child(
+                            Button::new("duplicate-row")
+                                .small()
+                                .ghost()
+                                .label(i18n.t("table.duplicate-row"))
+                                .on_click(cx.listener(|this, _, _, cx| this.duplicate_row(cx))),
+                        )
+                        .child(
+                            Button::new("delete-row")
+                                .small()
+                                .ghost()
+                                .label(i18n.t("table.delete-row"))
+                                .on_click(cx.listener(|this, _, _, cx| this.delete_row(cx))),
+                        )
+                        .child(
+                            Button::new("review-changes")
+                                .small()
+                                .primary()
+                                .label(i18n.t("table.edit-review"))
+                                .disabled(self.staged.is_empty())
+                                .on_click(cx.listener(|this, _, _, cx| this.review_changes(cx))),
+                        )
+                    })
+                    .when(reviewing, |this| {
+                        this.child(
+                            Button::new("apply-changes")
+                                .small()
+                                .primary()
+                                .label(i18n.t("table.edit-apply"))
+                                .disabled(self.applying)
+                                .on_click(cx.listener(|this, _, _, cx| this.apply_changes(cx))),
+                        )
+                        .child(
+                            Button::new("back-to-grid")
+                                .small()
+                                .ghost()
+                                .label(i18n.t("table.edit-back"))
+                                .disabled(self.applying)
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.preview = None;
+                                    cx.notify();
+                                })),
+                        )
+                    })
+                    .child(
+                        Button::new("discard-changes")
+                            .small()
+                            .ghost()
+                            .label(i18n.t("table.edit-discard"))
+                            .disabled(self.staged.is_empty() || self.applying)
+                            .on_click(cx.listener(|this, _, _, cx| this.discard_changes(cx))),
+                    ),
+            )
+            .when_some(self.edit_error.clone(), |this, error| {
+                this.child(div().text_sm().text_color(cx.theme().danger).child(error))
+            })
+    }
+
+    /// Statements of the staged changes, each followed by the values of its placeholders
+    fn render_preview(statements: &[BoundStatement], cx: &App) -> Div {
+        div().size_full().bg(cx.theme().background).child(
+            div()
+                .id("change-preview")
+                .size_full()
+                .overflow_y_scroll()
+                .text_color(cx.theme().foreground)
+                .font_family("monospace")
+                .text_sm()
+                .children(statements.iter().map(|statement| {
+                    let params = statement
+                        .params
+                        .iter()
+                        .map(QueryParam::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    div()
+                        .px_3()
+                        .py_2()
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .child(div().child(format!("{};", statement.sql)))
+                        .child(
+                            div()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("-- {}", params)),
+                        )
+                })),
+        )
+    }
+
+    fn render_documents(page: &DocumentPage, cx: &App) -> Div {
+        div().size_full().bg(cx.theme().background).child(
+            div()
+                .id("document-list")
+                .size_full()
+                .overflow_y_scroll()
+                .text_color(cx.theme().foreground)
+                .font_family("monospace")
+                .text_sm()
+                .children(page.documents.iter().map(|document| {
+                    div()
+                        .px_3()
+                        .py_2()
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .children(document.lines().map(|line| div().child(line.to_string())))
+                })),
+        )
+    }
+
+    fn render_error(error: String, cx: &App) -> Div {
+        div()
+            .size_full()
+            .p_3()
+            .bg(cx.theme().background)
+            .text_color(cx.theme().danger)
+            .child(error)
+    }
+
+    fn render_loading(cx: &App) -> Div {
+        div().size_full().bg(cx.theme().background).child(
+            v_flex()
+                .flex_1()
+                .items_center()
+                .justify_center()
+                .text_color(cx.theme().muted_foreground)
+                .child(cx.global::<I18n>().t("table.loading")),
+        )
+    }
+
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
     }
 }
 
@@ -141,15 +1397,90 @@ impl Render for PageTables {
         cx: &mut gpui::Context<Self>,
     ) -> impl gpui::IntoElement {
         let i18n = cx.global::<I18n>();
+        let documents = self.documents.clone().filter(|_| self.show_documents);
+        let view = if self.current.is_some() {
+            Some(self.view)
+        } else {
+            None
+        };
+        let show_rows = view == Some(TableView::Data);
         v_flex()
             .size_full()
             .bg(gpui::white())
             .text_color(gpui::black())
-            .child(if self.data.is_some() {
+            .when_some(self.key_entry.clone(), |this, entry| {
+                this.child(Self::render_key_header(&entry, cx))
+            })
+            .when(self.current.is_some(), |this| {
+                this.child(self.render_table_tabs(cx))
+            })
+            .when(show_rows, |this| this.child(self.render_filter_bar(cx)))
+            .when(
+                show_rows
+                    && self.data.is_some()
+                    && (self.table_editor || self.edit_error.is_some()),
+                |this| this.child(self.render_edit_bar(cx)),
+            )
+            .when(show_rows && self.documents.is_some(), |this| {
+                this.child(self.render_view_switch(cx))
+            })
+            .child(if view == Some(TableView::Structure) {
+                match (&self.structure, &self.structure_error) {
+                    (_, Some(error)) => Self::render_error(error.clone(), cx),
+                    (Some(structure), _) => render_structure(structure, cx),
+                    (None, None) => Self::render_loading(cx),
+                }
+            } else if view == Some(TableView::Ddl) {
+                match (&self.ddl, &self.ddl_error, &self.current) {
+                    (_, Some(error), _) => Self::render_error(error.clone(), cx),
+                    (Some(ddl), _, Some(object)) => render_ddl(
+                        format!("{} {}", object.kind, object.name),
+                        ddl.clone(),
+                        &self.ddl_editor,
+                        cx,
+                    ),
+                    _ => Self::render_loading(cx),
+                }
+            } else if view == Some(TableView::Design) {
+                match &self.designer {
+                    Some(designer) => div().size_full().child(designer.clone()),
+                    None => Self::render_loading(cx),
+                }
+            } else if let Some(statements) = &self.preview {
+                Self::render_preview(statements, cx)
+            } else if let Some(documents) = &documents {
+                Self::render_documents(documents, cx)
+            } else if self.data.is_some() {
                 let data = self.data.clone().unwrap();
                 // Initialize table state if needed (only when we have data)
                 if self.table_state.is_none() {
-                    let delegate = DatabaseTableDelegate::new(data.clone());
+                    let page = cx.entity().downgrade();
+                    let sort_page = page.clone();
+                    let edit_page = page.clone();
+                    let delegate = DatabaseTableDelegate::new(data.clone())
+                        .on_sort(Rc::new(move |column, _, cx| {
+                            if let Err(e) =
+                                sort_page.update(cx, |this, cx| this.toggle_sort(column, cx))
+                            {
+                                eprintln!("Failed to sort table: {:?}", e);
+                            }
+                        }))
+                        .on_edit(Rc::new(move |row_ix, col_ix, window, cx| {
+                            if let Err(e) = edit_page
+                                .update(cx, |this, cx| this.start_edit(row_ix, col_ix, window, cx))
+                            {
+                                eprintln!("Failed to edit cell: {:?}", e);
+                            }
+                        }))
+                        .on_load_more(Rc::new(move |_, cx| {
+                            // The table is mid-layout when asking, load once it is done
+                            let page = page.clone();
+                            cx.defer(move |cx| {
+                                if let Err(e) = page.update(cx, |this, cx| this.load_more(cx)) {
+                                    eprintln!("Failed to load more rows: {:?}", e);
+                                }
+                            });
+                        }));
                     let table_state = cx.new(|cx| TableState::new(delegate, window, cx));
                     self.table_state = Some(table_state);
                 } else if let Some(table_state) = &self.table_state {
@@ -157,6 +1488,13 @@ impl Render for PageTables {
                     table_state.update(cx, |table_state, cx| {
                         let delegate = table_state.delegate_mut();
                         delegate.set_loading(self.loading);
+                        delegate.set_sort(self.query.order_by.first().cloned());
+                        delegate.set_has_more(self.infinite_scroll && self.has_more);
+                        delegate.set_staged(self.staged.clone());
+                        delegate.set_editing(
+                            self.editing
+                                .map(|(row, col)| (row, col, self.edit_input.clone())),
+                        );
                         delegate.update_data(data.clone());
                         cx.notify();
                     });
@@ -215,5 +1553,8 @@ impl Render for PageTables {
                             ),
                     )
             })
+            .when_some(self.data.clone().filter(|_| show_rows), |this, data| {
+                this.child(self.render_pagination(&data, cx))
+            })
     }
 }
@@ -1,47 +1,149 @@
-use db_sight_core::TableDataPage;
+use db_sight_core::{CellValue, OrderBy, ResultColumn, RowChange, SortDirection, TableDataPage};
 use gpui::{
-    div, prelude::FluentBuilder, App, Context, InteractiveElement, IntoElement, ParentElement,
-    SharedString, Styled, TextAlign, Window,
+    div, prelude::FluentBuilder, App, ClickEvent, Context, Entity, InteractiveElement, IntoElement,
+    ParentElement, SharedString, StatefulInteractiveElement, Styled, TextAlign, Window,
 };
 use gpui_component::{
+    h_flex,
+    input::{Input, InputState},
     label::Label,
     table::{Column, TableDelegate, TableState},
-    StyledExt,
+    ActiveTheme, Sizable, StyledExt,
 };
-use std::ops::Range;
+use std::{ops::Range, rc::Rc};
+
+/// Called with the column name when a sortable header is clicked
+pub type SortHandler = Rc<dyn Fn(&str, &mut Window, &mut App)>;
+
+/// Called when the view scrolls near the last loaded row
+pub type LoadMoreHandler = Rc<dyn Fn(&mut Window, &mut App)>;
+
+/// Called with the row and column index of a double-clicked cell
+pub type EditHandler = Rc<dyn Fn(usize, usize, &mut Window, &mut App)>;
+
+/// New value of a cell, staged until the changes are applied. The row is found by
+/// its primary key, so the edit survives paging and sorting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellEdit {
+    pub key: Vec<(String, CellValue)>,
+    pub column: String,
+    pub value: CellValue,
+}
+
+/// Row changes made in the grid, kept until they are applied or discarded
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StagedChanges {
+    pub edits: Vec<CellEdit>,
+    /// Primary keys of the loaded rows to delete
+    pub deletes: Vec<Vec<(String, CellValue)>>,
+    /// New rows with a value per grid column, `None` takes the column default
+    pub inserts: Vec<Vec<Option<CellValue>>>,
+}
+
+impl StagedChanges {
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty() && self.deletes.is_empty() && self.inserts.is_empty()
+    }
+
+    /// Number of changed cells and rows
+    pub fn len(&self) -> usize {
+        self.edits.len() + self.deletes.len() + self.inserts.len()
+    }
+
+    pub fn is_deleted(&self, key: &[(String, CellValue)]) -> bool {
+        self.deletes.iter().any(|deleted| deleted == key)
+    }
+
+    pub fn edited_value(&self, key: &[(String, CellValue)], column: &str) -> Option<&CellValue> {
+        self.edits
+            .iter()
+            .find(|edit| edit.key == key && edit.column == column)
+            .map(|edit| &edit.value)
+    }
+
+    /// Delete a loaded row, or keep it when it is already deleted. Its edits are
+    /// dropped either way.
+    pub fn toggle_delete(&mut self, key: Vec<(String, CellValue)>) {
+        self.edits.retain(|edit| edit.key != key);
+        if self.is_deleted(&key) {
+            self.deletes.retain(|deleted| *deleted != key);
+        } else {
+            self.deletes.push(key);
+        }
+    }
+
+    /// The changes in the order they are applied: deletes first, so that the unique
+    /// values they free can be reused, then one update per row and the inserts
+    pub fn row_changes(&self, columns: &[ResultColumn]) -> Vec<RowChange> {
+        let mut changes: Vec<RowChange> = self
+            .deletes
+            .iter()
+            .map(|key| RowChange::Delete { key: key.clone() })
+            .collect();
+        for edit in &self.edits {
+            let row = changes.iter_mut().find_map(|change| match change {
+                RowChange::Update { key, values } if *key == edit.key => Some(values),
+                _ => None,
+            });
+            let value = (edit.column.clone(), edit.value.clone());
+            match row {
+                Some(values) => values.push(value),
+                None => changes.push(RowChange::Update {
+                    key: edit.key.clone(),
+                    values: vec![value],
+                }),
+            }
+        }
+        changes.extend(self.inserts.iter().map(|row| {
+            RowChange::Insert {
+                values: columns
+                    .iter()
+                    .zip(row)
+                    .filter_map(|(column, value)| Some((column.name.clone(), value.clone()?)))
+                    .collect(),
+            }
+        }));
+        changes
+    }
+}
+
+/// Rows left below the view when the next chunk is requested
+const LOAD_MORE_THRESHOLD: usize = 20;
 
 pub struct DatabaseTableDelegate {
     data: TableDataPage,
     columns: Vec<Column>,
     loading: bool,
+    /// Sort key shown in the header, the data is already sorted by the server
+    sort: Option<OrderBy>,
+    on_sort: Option<SortHandler>,
+    /// Whether the server has rows past the loaded ones, for infinite scroll
+    has_more: bool,
+    on_load_more: Option<LoadMoreHandler>,
+    on_edit: Option<EditHandler>,
+    /// Changes shown over the loaded rows, new rows follow the loaded ones
+    staged: StagedChanges,
+    /// Cell being edited, rendered as the input
+    editing: Option<(usize, usize, Entity<InputState>)>,
     visible_rows: Range<usize>,
     visible_cols: Range<usize>,
 }
 
 impl DatabaseTableDelegate {
     pub fn new(data: TableDataPage) -> Self {
-        let columns: Vec<Column> = data
-            .columns
-            .iter()
-            .enumerate()
-            .map(|(idx, col_name)| {
-                // First few columns can be fixed to left
-                if idx < 3 {
-                    Column::new(col_name.clone(), col_name.clone())
-                        .width(120.)
-                        .resizable(true)
-                } else {
-                    Column::new(col_name.clone(), col_name.clone())
-                        .width(120.)
-                        .resizable(true)
-                }
-            })
-            .collect();
+        let columns = Self::build_columns(&data);
 
         Self {
             data,
             columns,
             loading: false,
+            sort: None,
+            on_sort: None,
+            has_more: false,
+            on_load_more: None,
+            on_edit: None,
+            staged: StagedChanges::default(),
+            editing: None,
             visible_rows: Range::default(),
             visible_cols: Range::default(),
         }
@@ -51,31 +153,67 @@ impl DatabaseTableDelegate {
         let columns_length = data.columns.len();
         // Update columns if needed
         if self.columns.len() != columns_length {
-            self.data = data.clone();
-            self.columns = data
-                .columns
-                .iter()
-                .enumerate()
-                .map(|(idx, col_name)| {
-                    if idx < 3 {
-                        Column::new(col_name.clone(), col_name.clone())
-                            .width(120.)
-                            .resizable(true)
-                    } else {
-                        Column::new(col_name.clone(), col_name.clone())
-                            .width(120.)
-                            .resizable(true)
-                    }
-                })
-                .collect();
+            self.columns = Self::build_columns(&data);
+            self.data = data;
         } else {
             self.data = data;
         }
     }
 
+    /// Numbers are right-aligned so that their digits line up
+    fn build_columns(data: &TableDataPage) -> Vec<Column> {
+        data.columns
+            .iter()
+            .map(|col| {
+                let column = Column::new(col.name.clone(), col.name.clone())
+                    .width(120.)
+                    .resizable(true);
+                if col.kind.is_numeric() {
+                    column.text_right()
+                } else {
+                    column
+                }
+            })
+            .collect()
+    }
+
     pub fn set_loading(&mut self, loading: bool) {
         self.loading = loading;
     }
+
+    pub fn set_sort(&mut self, sort: Option<OrderBy>) {
+        self.sort = sort;
+    }
+
+    pub fn set_has_more(&mut self, has_more: bool) {
+        self.has_more = has_more;
+    }
+
+    pub fn set_staged(&mut self, staged: StagedChanges) {
+        self.staged = staged;
+    }
+
+    pub fn set_editing(&mut self, editing: Option<(usize, usize, Entity<InputState>)>) {
+        self.editing = editing;
+    }
+
+    /// Make cells editable on double click
+    pub fn on_edit(mut self, handler: EditHandler) -> Self {
+        self.on_edit = Some(handler);
+        self
+    }
+
+    /// Load more rows when scrolling near the end, while `has_more` is set
+    pub fn on_load_more(mut self, handler: LoadMoreHandler) -> Self {
+        self.on_load_more = Some(handler);
+        self
+    }
+
+    /// Make the headers clickable, without a handler they are plain labels
+    pub fn on_sort(mut self, handler: SortHandler) -> Self {
+        self.on_sort = Some(handler);
+        self
+    }
 }
 
 impl TableDelegate for DatabaseTableDelegate {
@@ -85,7 +223,7 @@ impl TableDelegate for DatabaseTableDelegate {
     }
 
     fn rows_count(&self, _: &App) -> usize {
-        let count = self.data.rows.len();
+        let count = self.data.rows.len() + self.staged.inserts.len();
         count
     }
 
@@ -93,22 +231,45 @@ impl TableDelegate for DatabaseTableDelegate {
         &self.columns[col_ix]
     }
 
-    fn render_th(&self, col_ix: usize, _: &mut Window, _cx: &mut App) -> impl IntoElement {
+    fn render_th(&self, col_ix: usize, _: &mut Window, cx: &mut App) -> impl IntoElement {
         let col = self.columns.get(col_ix).unwrap();
         let col_name = self
             .data
             .columns
             .get(col_ix)
-            .map(|s| s.clone())
+            .map(|c| c.name.clone())
             .unwrap_or_else(|| col.name.to_string());
 
-        div()
+        let indicator = self
+            .sort
+            .as_ref()
+            .filter(|sort| sort.column == col_name)
+            .map(|sort| match sort.direction {
+                SortDirection::Asc => "▲",
+                SortDirection::Desc => "▼",
+            });
+        let on_sort = self.on_sort.clone();
+
+        h_flex()
+            .id(SharedString::from(format!("th-{}", col_ix)))
+            .w_full()
+            .gap_1()
             .px_2()
             .py_1()
-            .when(col.align == TextAlign::Right, |this| {
-                this.h_flex().w_full().justify_end()
+            .when(col.align == TextAlign::Right, |this| this.justify_end())
+            .child(Label::new(SharedString::from(col_name.clone())))
+            .when_some(indicator, |this, indicator| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(indicator),
+                )
+            })
+            .when_some(on_sort, |this, on_sort| {
+                this.cursor_pointer()
+                    .on_click(move |_, window, cx| on_sort(&col_name, window, cx))
             })
-            .child(Label::new(SharedString::from(col_name)))
     }
 
     fn render_td(
@@ -116,28 +277,78 @@ impl TableDelegate for DatabaseTableDelegate {
         row_ix: usize,
         col_ix: usize,
         _: &mut Window,
-        _cx: &mut App,
+        cx: &mut App,
     ) -> impl IntoElement {
         let col = self.columns.get(col_ix).unwrap();
-        let value = self
-            .data
-            .rows
-            .get(row_ix)
-            .and_then(|row| row.get(col_ix))
-            .cloned()
-            .unwrap_or_else(|| {
+        let cell_id = SharedString::from(format!("td-{}-{}", row_ix, col_ix));
+        if let Some((_, _, input)) = self
+            .editing
+            .as_ref()
+            .filter(|(row, col, _)| (*row, *col) == (row_ix, col_ix))
+        {
+            return div().id(cell_id).h_full().child(Input::new(input).small());
+        }
+
+        let new_row = row_ix
+            .checked_sub(self.data.rows.len())
+            .and_then(|ix| self.staged.inserts.get(ix));
+        let key = if self.staged.is_empty() {
+            None
+        } else {
+            self.data.row_key(row_ix)
+        };
+        let deleted = key.as_ref().is_some_and(|key| self.staged.is_deleted(key));
+        let edited = match (&key, self.data.columns.get(col_ix)) {
+            (Some(key), Some(column)) => self.staged.edited_value(key, &column.name),
+            _ => None,
+        };
+        let value = match new_row {
+            Some(values) => values.get(col_ix).cloned().flatten(),
+            None => edited
+                .or_else(|| self.data.rows.get(row_ix).and_then(|row| row.get(col_ix)))
+                .cloned(),
+        };
+        let text = match (&value, new_row) {
+            (Some(value), _) => value.to_string(),
+            (None, Some(_)) => "DEFAULT".to_string(),
+            (None, None) => {
                 eprintln!("Missing data for row {}, col {}", row_ix, col_ix);
                 "<missing>".to_string()
-            });
+            }
+        };
+        let background = if new_row.is_some() {
+            Some(cx.theme().success.opacity(0.2))
+        } else if deleted {
+            Some(cx.theme().danger.opacity(0.2))
+        } else if edited.is_some() {
+            Some(cx.theme().warning.opacity(0.2))
+        } else {
+            None
+        };
+        let on_edit = self.on_edit.clone();
 
         div()
+            .id(cell_id)
             .px_2()
             .py_1()
             .h_full()
             .when(col.align == TextAlign::Right, |this| {
                 this.h_flex().justify_end()
             })
-            .child(Label::new(SharedString::from(value)))
+            // A real NULL or DEFAULT must not read like the text "NULL" or "DEFAULT"
+            .when(value.as_ref().is_none_or(CellValue::is_null), |this| {
+                this.italic().text_color(cx.theme().muted_foreground)
+            })
+            .when_some(background, |this, background| this.bg(background))
+            .when(deleted, |this| this.line_through())
+            .when_some(on_edit, |this, on_edit| {
+                this.on_click(move |event: &ClickEvent, window, cx| {
+                    if event.click_count() == 2 {
+                        on_edit(row_ix, col_ix, window, cx);
+                    }
+                })
+            })
+            .child(Label::new(SharedString::from(text)))
     }
 
     fn render_tr(
@@ -156,10 +367,16 @@ impl TableDelegate for DatabaseTableDelegate {
     fn visible_rows_changed(
         &mut self,
         visible_range: Range<usize>,
-        _: &mut Window,
-        _: &mut Context<TableState<Self>>,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
     ) {
+        let near_end = visible_range.end + LOAD_MORE_THRESHOLD >= self.data.rows.len();
         self.visible_rows = visible_range;
+        if near_end && self.has_more && !self.loading {
+            if let Some(on_load_more) = self.on_load_more.clone() {
+                on_load_more(window, cx);
+            }
+        }
     }
 
     fn visible_columns_changed(
@@ -1,4 +1,7 @@
-use db_sight_core::{events::SelectedTableChanged, ConnectionConfig};
+use db_sight_core::{
+    events::{ObjectDdlRequested, SelectedKeyChanged, SelectedTableChanged},
+    ConnectionConfig, ObjectRef,
+};
 use gpui::{App, AppContext, Entity, EventEmitter, Global, SharedString};
 use gpui_component::notification::Notification;
 use serde::{Deserialize, Serialize};
@@ -93,9 +96,11 @@ impl AppConnectionTabsState {
 impl Global for AppConnectionTabsState {}
 
 pub struct TableSelectionState {
-    pub selected_table: Option<String>,
+    pub selected_table: Option<ObjectRef>,
 }
 impl EventEmitter<SelectedTableChanged> for TableSelectionState {}
+impl EventEmitter<SelectedKeyChanged> for TableSelectionState {}
+impl EventEmitter<ObjectDdlRequested> for TableSelectionState {}
 pub struct AppTableState {
     pub state: Entity<TableSelectionState>,
 }
@@ -5,7 +5,7 @@ use gpui_component::{Root, StyledExt, WindowExt};
 
 use crate::ui::{
     components::{SideBar, TopBar},
-    pages::tables::table::PageTables,
+    pages::{queries::query::PageQueries, tables::table::PageTables},
     state::{AppLoadingState, AppNotificationState, AppState},
 };
 
@@ -13,17 +13,20 @@ pub struct RootApp {
     sidebar: Entity<SideBar>,
     topbar: Entity<TopBar>,
     page_tables: Entity<PageTables>,
+    page_queries: Entity<PageQueries>,
 }
 
 impl RootApp {
     fn new(window: &mut Window, cx: &mut App) -> Self {
         let sidebar = SideBar::view(window, cx);
         let topbar = TopBar::view(sidebar.clone(), window, cx);
-        let page_tables = PageTables::view(cx);
+        let page_tables = PageTables::view(window, cx);
+        let page_queries = PageQueries::view(window, cx);
         Self {
             sidebar,
             topbar,
             page_tables,
+            page_queries,
         }
     }
 
@@ -59,6 +62,9 @@ impl Render for RootApp {
                         crate::ui::pages::PageRoute::DatabaseColumns => {
                             self.page_tables.clone().into_any_element()
                         }
+                        crate::ui::pages::PageRoute::DatabaseQueries => {
+                            self.page_queries.clone().into_any_element()
+                        }
                         _ => current_page.to_element(cx).into_any_element(),
                     }),
             )
@@ -0,0 +1,797 @@
+use std::{collections::HashSet, ops::Range};
+
+use uuid::Uuid;
+
+use crate::{
+    driver::{DBError, SchemaBrowser},
+    metadata::MetadataCache,
+    model::table::{TableColumn, TableInfo},
+    sql::{skip_line, skip_quoted, statement_at},
+    DatabaseType,
+};
+
+/// Keywords offered while typing, also the words that can't be a table alias
+const KEYWORDS: &[&str] = &[
+    "SELECT",
+    "FROM",
+    "WHERE",
+    "AND",
+    "OR",
+    "NOT",
+    "NULL",
+    "IS",
+    "IN",
+    "EXISTS",
+    "BETWEEN",
+    "LIKE",
+    "AS",
+    "ON",
+    "USING",
+    "JOIN",
+    "INNER",
+    "LEFT",
+    "RIGHT",
+    "FULL",
+    "OUTER",
+    "CROSS",
+    "NATURAL",
+    "LATERAL",
+    "GROUP",
+    "BY",
+    "ORDER",
+    "ASC",
+    "DESC",
+    "HAVING",
+    "LIMIT",
+    "OFFSET",
+    "FETCH",
+    "UNION",
+    "ALL",
+    "DISTINCT",
+    "EXCEPT",
+    "INTERSECT",
+    "INSERT",
+    "INTO",
+    "VALUES",
+    "UPDATE",
+    "SET",
+    "DELETE",
+    "RETURNING",
+    "CREATE",
+    "ALTER",
+    "DROP",
+    "TABLE",
+    "VIEW",
+    "INDEX",
+    "TRUNCATE",
+    "WITH",
+    "CASE",
+    "WHEN",
+    "THEN",
+    "ELSE",
+    "END",
+    "BEGIN",
+    "COMMIT",
+    "ROLLBACK",
+    "PRIMARY",
+    "KEY",
+    "FOREIGN",
+    "REFERENCES",
+    "DEFAULT",
+    "UNIQUE",
+    "CHECK",
+    "CONSTRAINT",
+    "COLUMN",
+    "ADD",
+    "RENAME",
+    "TO",
+    "IF",
+    "WINDOW",
+    "OVER",
+    "PARTITION",
+    "FOR",
+    "TRUE",
+    "FALSE",
+    "EXPLAIN",
+    "DESCRIBE",
+    "SHOW",
+];
+
+/// Keywords followed by a table name
+const TABLE_KEYWORDS: &[&str] = &[
+    "FROM", "JOIN", "UPDATE", "INTO", "TABLE", "DESCRIBE", "TRUNCATE",
+];
+
+/// Keywords starting a clause, which decide what a `,` separates
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "SELECT",
+    "FROM",
+    "WHERE",
+    "GROUP",
+    "ORDER",
+    "HAVING",
+    "LIMIT",
+    "OFFSET",
+    "SET",
+    "VALUES",
+    "ON",
+    "USING",
+    "JOIN",
+    "UNION",
+    "INTO",
+    "UPDATE",
+    "RETURNING",
+    "WINDOW",
+];
+
+/// Clauses of the table list, where a `,` is followed by a table
+const FROM_CLAUSES: &[&str] = &["FROM", "JOIN", "ON", "USING"];
+
+const FUNCTIONS: &[&str] = &[
+    "COUNT",
+    "SUM",
+    "AVG",
+    "MIN",
+    "MAX",
+    "COALESCE",
+    "NULLIF",
+    "CAST",
+    "LOWER",
+    "UPPER",
+    "TRIM",
+    "LENGTH",
+    "SUBSTRING",
+    "REPLACE",
+    "ROUND",
+    "ABS",
+    "CONCAT",
+    "CURRENT_DATE",
+    "CURRENT_TIMESTAMP",
+    "ROW_NUMBER",
+    "RANK",
+];
+
+/// Functions of one engine on top of `FUNCTIONS`
+fn engine_functions(db_type: DatabaseType) -> &'static [&'static str] {
+    match db_type {
+        DatabaseType::MySql | DatabaseType::MariaDB => &[
+            "IFNULL",
+            "NOW",
+            "DATE_FORMAT",
+            "DATE_ADD",
+            "DATEDIFF",
+            "GROUP_CONCAT",
+            "JSON_EXTRACT",
+            "CHAR_LENGTH",
+        ],
+        DatabaseType::Postgre => &[
+            "NOW",
+            "TO_CHAR",
+            "DATE_TRUNC",
+            "STRING_AGG",
+            "ARRAY_AGG",
+            "JSONB_BUILD_OBJECT",
+            "GENERATE_SERIES",
+        ],
+        DatabaseType::Sqlite => &[
+            "IFNULL",
+            "DATETIME",
+            "STRFTIME",
+            "GROUP_CONCAT",
+            "JSON_EXTRACT",
+            "TYPEOF",
+        ],
+        DatabaseType::MicrosoftSQLServer => &[
+            "ISNULL",
+            "GETDATE",
+            "DATEADD",
+            "DATEDIFF",
+            "STRING_AGG",
+            "LEN",
+            "FORMAT",
+        ],
+        _ => &[],
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompletionKind {
+    Keyword,
+    Function,
+    Schema,
+    Table,
+    View,
+    Column,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    /// The table of a column with its type, or the table a alias stands for
+    pub detail: Option<String>,
+}
+
+impl Completion {
+    fn new(label: impl Into<String>, kind: CompletionKind, detail: Option<String>) -> Self {
+        Self {
+            label: label.into(),
+            kind,
+            detail,
+        }
+    }
+}
+
+/// Completions at a cursor
+#[derive(Debug, Clone, Default)]
+pub struct Completions {
+    /// Byte range of the word being typed, which a completion replaces
+    pub range: Range<usize>,
+    pub items: Vec<Completion>,
+}
+
+/// What may be typed at the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionScope {
+    /// A table, after `FROM`, `JOIN`, `UPDATE`, `INTO` and the like
+    Table,
+    /// A member of the word before the `.`: column of a table or alias, or table of a
+    /// schema
+    Member(String),
+    /// Anything else: columns, functions and keywords
+    Expression,
+}
+
+/// Table named in a statement, e.g. `sales.orders o`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableReference {
+    pub schema: Option<String>,
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+/// The statement around a cursor, as far as completion cares
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionContext {
+    pub scope: CompletionScope,
+    /// Part of the word before the cursor
+    pub prefix: String,
+    /// Byte offset of the start of `prefix`
+    pub prefix_start: usize,
+    /// Tables of the statement, with their aliases
+    pub tables: Vec<TableReference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Identifier or keyword, quoted identifiers without their quotes
+    Word {
+        text: String,
+        quoted: bool,
+    },
+    Dot,
+    Comma,
+    LParen,
+    RParen,
+    /// Literal or operator, nothing completion looks at
+    Other,
+}
+
+impl Token {
+    /// Upper-cased keyword, quoted identifiers are never keywords
+    fn keyword(&self) -> Option<String> {
+        match self {
+            Token::Word {
+                text,
+                quoted: false,
+            } => Some(text.to_ascii_uppercase()),
+            _ => None,
+        }
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn is_keyword(word: &str) -> bool {
+    KEYWORDS
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(word))
+}
+
+/// Tokens of the statement `sql` ends in, the ones before the last `;` are dropped
+fn tokenize(sql: &str, db_type: DatabaseType) -> Vec<Token> {
+    let backslash_escapes = matches!(db_type, DatabaseType::MySql | DatabaseType::MariaDB);
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while let Some(c) = sql[i..].chars().next() {
+        match c {
+            '-' if bytes.get(i + 1) == Some(&b'-') => i = skip_line(bytes, i + 2),
+            '#' if backslash_escapes => i = skip_line(bytes, i + 1),
+            '/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = sql[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + 2 + end + 2);
+            }
+            ';' => {
+                tokens.clear();
+                i += 1;
+            }
+            '\'' => {
+                i = skip_quoted(bytes, i + 1, b'\'', backslash_escapes);
+                tokens.push(Token::Other);
+            }
+            '"' | '`' => i = push_quoted(sql, i, c, &mut tokens),
+            '[' if db_type == DatabaseType::MicrosoftSQLServer => {
+                i = push_quoted(sql, i, ']', &mut tokens)
+            }
+            '.' => {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            c if is_ident_char(c) => {
+                let end = sql[i..]
+                    .find(|c: char| !is_ident_char(c))
+                    .map_or(sql.len(), |len| i + len);
+                tokens.push(if c.is_ascii_digit() {
+                    Token::Other
+                } else {
+                    Token::Word {
+                        text: sql[i..end].to_string(),
+                        quoted: false,
+                    }
+                });
+                i = end;
+            }
+            c if c.is_whitespace() => i += c.len_utf8(),
+            c => {
+                tokens.push(Token::Other);
+                i += c.len_utf8();
+            }
+        }
+    }
+    tokens
+}
+
+/// Push the quoted identifier opening at `start`, returning the index past it
+fn push_quoted(sql: &str, start: usize, close: char, tokens: &mut Vec<Token>) -> usize {
+    let end = skip_quoted(sql.as_bytes(), start + 1, close as u8, false);
+    let text_end = if end > start + 1 && sql[..end].ends_with(close) {
+        end - 1
+    } else {
+        end
+    };
+    let doubled = format!("{}{}", close, close);
+    tokens.push(Token::Word {
+        text: sql[start + 1..text_end].replace(&doubled, &close.to_string()),
+        quoted: true,
+    });
+    end
+}
+
+/// Clause each token is in, by the last clause keyword at its parenthesis depth
+fn clauses(tokens: &[Token]) -> Vec<Option<String>> {
+    let mut stack: Vec<Option<String>> = vec![None];
+    tokens
+        .iter()
+        .map(|token| {
+            match token {
+                Token::LParen => stack.push(None),
+                Token::RParen if stack.len() > 1 => {
+                    stack.pop();
+                }
+                _ => {
+                    if let Some(keyword) = token.keyword() {
+                        if CLAUSE_KEYWORDS.contains(&keyword.as_str()) {
+                            *stack.last_mut().unwrap() = Some(keyword);
+                        }
+                    }
+                }
+            }
+            stack.last().cloned().flatten()
+        })
+        .collect()
+}
+
+/// Table named at the start of `tokens`: `name`, `schema.name`, then an optional alias
+fn parse_reference(tokens: &[Token]) -> Option<TableReference> {
+    let Some(Token::Word {
+        text: first,
+        quoted,
+    }) = tokens.first()
+    else {
+        return None;
+    };
+    if !quoted && is_keyword(first) {
+        return None;
+    }
+    let (schema, name, mut rest) = match tokens.get(1..3) {
+        Some([Token::Dot, Token::Word { text, .. }]) => {
+            (Some(first.clone()), text.clone(), &tokens[3..])
+        }
+        _ => (None, first.clone(), &tokens[1..]),
+    };
+    if let Some((token, tail)) = rest.split_first() {
+        if token.keyword().as_deref() == Some("AS") {
+            rest = tail;
+        }
+    }
+    let alias = match rest.first() {
+        Some(Token::Word { text, quoted }) if *quoted || !is_keyword(text) => Some(text.clone()),
+        _ => None,
+    };
+    Some(TableReference {
+        schema,
+        name,
+        alias,
+    })
+}
+
+fn is_from_clause(clause: Option<&str>) -> bool {
+    clause.is_some_and(|clause| FROM_CLAUSES.contains(&clause))
+}
+
+/// Tables named in `FROM`, `JOIN`, `UPDATE` and `INTO` of a statement
+fn table_references(tokens: &[Token]) -> Vec<TableReference> {
+    let clauses = clauses(tokens);
+    tokens
+        .iter()
+        .enumerate()
+        .filter(|(ix, token)| match token {
+            Token::Comma => is_from_clause(clauses[*ix].as_deref()),
+            _ => token
+                .keyword()
+                .is_some_and(|keyword| TABLE_KEYWORDS.contains(&keyword.as_str())),
+        })
+        .filter_map(|(ix, _)| parse_reference(&tokens[ix + 1..]))
+        .collect()
+}
+
+/// What can be typed at the byte `offset` of `sql`
+pub fn completion_context(sql: &str, offset: usize, db_type: DatabaseType) -> CompletionContext {
+    let mut offset = offset.min(sql.len());
+    while !sql.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let prefix_start = sql[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_ident_char(*c))
+        .last()
+        .map_or(offset, |(ix, _)| ix);
+
+    let before = tokenize(&sql[..prefix_start], db_type);
+    let scope = match before.as_slice() {
+        [.., Token::Word { text, .. }, Token::Dot] => CompletionScope::Member(text.clone()),
+        [.., Token::Comma]
+            if is_from_clause(clauses(&before).last().cloned().flatten().as_deref()) =>
+        {
+            CompletionScope::Table
+        }
+        [.., last]
+            if last
+                .keyword()
+                .is_some_and(|keyword| TABLE_KEYWORDS.contains(&keyword.as_str())) =>
+        {
+            CompletionScope::Table
+        }
+        _ => CompletionScope::Expression,
+    };
+
+    // The whole statement, tables may be named after the cursor
+    let end = match statement_at(sql, offset, db_type) {
+        Some(range) if range.start <= offset => range.end.max(offset),
+        _ => offset,
+    };
+    let tables = table_references(&tokenize(&sql[..end], db_type));
+
+    CompletionContext {
+        scope,
+        prefix: sql[prefix_start..offset].to_string(),
+        prefix_start,
+        tables,
+    }
+}
+
+fn table_items(
+    tables: Vec<TableInfo>,
+    schema: Option<&str>,
+) -> impl Iterator<Item = Completion> + '_ {
+    tables.into_iter().map(move |table| {
+        let kind = if table.is_view() {
+            CompletionKind::View
+        } else {
+            CompletionKind::Table
+        };
+        Completion::new(table.name, kind, schema.map(str::to_string))
+    })
+}
+
+fn column_items(columns: Vec<TableColumn>, table: &str) -> impl Iterator<Item = Completion> + '_ {
+    columns.into_iter().map(move |column| {
+        let detail = format!("{} {}", table, column.data_type);
+        Completion::new(column.name, CompletionKind::Column, Some(detail))
+    })
+}
+
+/// Completions at the byte `offset` of `sql`. Unqualified tables are looked up in
+/// `default_schema`, the metadata comes from `cache` and is listed on first use.
+pub async fn complete(
+    cache: &MetadataCache,
+    connection_id: Uuid,
+    browser: &dyn SchemaBrowser,
+    sql: &str,
+    offset: usize,
+    db_type: DatabaseType,
+    default_schema: Option<&str>,
+) -> Result<Completions, DBError> {
+    let context = completion_context(sql, offset, db_type);
+    // Names of a statement may be CTEs or typos, which have no columns
+    let columns = |reference: &TableReference| {
+        let schema = reference
+            .schema
+            .clone()
+            .or(default_schema.map(str::to_string));
+        let name = reference.name.clone();
+        async move {
+            match schema {
+                Some(schema) => cache
+                    .columns(connection_id, browser, &schema, &name)
+                    .await
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            }
+        }
+    };
+
+    let mut items = Vec::new();
+    match &context.scope {
+        CompletionScope::Table => {
+            if let Some(schema) = default_schema {
+                let tables = cache.tables(connection_id, browser, schema).await?;
+                items.extend(table_items(tables, None));
+            }
+            let schemas = cache.schemas(connection_id, browser).await?;
+            items.extend(
+                schemas
+                    .into_iter()
+                    .map(|schema| Completion::new(schema, CompletionKind::Schema, None)),
+            );
+        }
+        CompletionScope::Member(qualifier) => {
+            let named = |name: &str| name.eq_ignore_ascii_case(qualifier);
+            let reference = context
+                .tables
+                .iter()
+                .find(|table| table.alias.as_deref().is_some_and(named))
+                .or_else(|| context.tables.iter().find(|table| named(&table.name)));
+            let schemas = cache.schemas(connection_id, browser).await?;
+            if let Some(reference) = reference {
+                let table_columns = columns(reference).await;
+                items.extend(column_items(table_columns, &reference.name));
+            } else if let Some(schema) = schemas.iter().find(|schema| named(schema)) {
+                let tables = cache.tables(connection_id, browser, schema).await?;
+                items.extend(table_items(tables, Some(schema)));
+            } else if let Some(schema) = default_schema {
+                // A table not named in the statement yet
+                let tables = cache.tables(connection_id, browser, schema).await?;
+                if let Some(table) = tables.iter().find(|table| named(&table.name)) {
+                    let table_columns = cache
+                        .columns(connection_id, browser, schema, &table.name)
+                        .await?;
+                    items.extend(column_items(table_columns, &table.name));
+                }
+            }
+        }
+        CompletionScope::Expression => {
+            for reference in &context.tables {
+                let table_columns = columns(reference).await;
+                items.extend(column_items(table_columns, &reference.name));
+                if let Some(alias) = &reference.alias {
+                    items.push(Completion::new(
+                        alias.clone(),
+                        CompletionKind::Table,
+                        Some(reference.name.clone()),
+                    ));
+                }
+            }
+            let functions = FUNCTIONS.iter().chain(engine_functions(db_type));
+            items.extend(
+                functions
+                    .map(|function| Completion::new(*function, CompletionKind::Function, None)),
+            );
+            items.extend(
+                KEYWORDS
+                    .iter()
+                    .map(|keyword| Completion::new(*keyword, CompletionKind::Keyword, None)),
+            );
+        }
+    }
+
+    let prefix = context.prefix.to_lowercase();
+    let mut seen = HashSet::new();
+    items.retain(|item| {
+        item.label.to_lowercase().starts_with(&prefix)
+            && seen.insert((item.label.clone(), item.kind))
+    });
+    Ok(Completions {
+        range: context.prefix_start..context.prefix_start + context.prefix.len(),
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str) -> Token {
+        Token::Word {
+            text: text.to_string(),
+            quoted: false,
+        }
+    }
+
+    fn quoted(text: &str) -> Token {
+        Token::Word {
+            text: text.to_string(),
+            quoted: true,
+        }
+    }
+
+    fn context(sql: &str, db_type: DatabaseType) -> CompletionContext {
+        completion_context(sql, sql.len(), db_type)
+    }
+
+    fn reference(schema: Option<&str>, name: &str, alias: Option<&str>) -> TableReference {
+        TableReference {
+            schema: schema.map(str::to_string),
+            name: name.to_string(),
+            alias: alias.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn tokenize_words_and_punctuation() {
+        assert_eq!(
+            tokenize("SELECT a.b, f(1) FROM t", DatabaseType::Postgre),
+            vec![
+                word("SELECT"),
+                word("a"),
+                Token::Dot,
+                word("b"),
+                Token::Comma,
+                word("f"),
+                Token::LParen,
+                Token::Other,
+                Token::RParen,
+                word("FROM"),
+                word("t"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_skips_strings_and_comments() {
+        assert_eq!(
+            tokenize(
+                "SELECT 'a;b' -- FROM x\n/* JOIN y */ FROM t",
+                DatabaseType::Postgre
+            ),
+            vec![word("SELECT"), Token::Other, word("FROM"), word("t")]
+        );
+        assert_eq!(
+            tokenize("SELECT 'it\\'s' # FROM x\nFROM t", DatabaseType::MySql),
+            vec![word("SELECT"), Token::Other, word("FROM"), word("t")]
+        );
+    }
+
+    #[test]
+    fn tokenize_quoted_identifiers() {
+        assert_eq!(
+            tokenize(r#"SELECT "a""b", `c`"#, DatabaseType::MySql),
+            vec![word("SELECT"), quoted("a\"b"), Token::Comma, quoted("c")]
+        );
+        assert_eq!(
+            tokenize("FROM [my table]", DatabaseType::MicrosoftSQLServer),
+            vec![word("FROM"), quoted("my table")]
+        );
+        // Unterminated while being typed
+        assert_eq!(
+            tokenize(r#"FROM "ord"#, DatabaseType::Postgre),
+            vec![word("FROM"), quoted("ord")]
+        );
+    }
+
+    #[test]
+    fn tokenize_keeps_the_last_statement() {
+        assert_eq!(
+            tokenize("SELECT 1; UPDATE t", DatabaseType::Sqlite),
+            vec![word("UPDATE"), word("t")]
+        );
+    }
+
+    #[test]
+    fn table_scope_after_table_keywords() {
+        for sql in [
+            "SELECT * FROM ",
+            "SELECT * FROM a JOIN ",
+            "UPDATE ",
+            "INSERT INTO ",
+            "SELECT * FROM a, ",
+            "SELECT * FROM a JOIN b ON a.id = b.id, ",
+            "select * from ord",
+        ] {
+            assert_eq!(
+                context(sql, DatabaseType::Postgre).scope,
+                CompletionScope::Table,
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn member_scope_after_dot() {
+        let context = context("SELECT o.na", DatabaseType::Postgre);
+        assert_eq!(context.scope, CompletionScope::Member("o".to_string()));
+        assert_eq!(context.prefix, "na");
+        assert_eq!(context.prefix_start, 9);
+
+        assert_eq!(
+            completion_context("SELECT * FROM \"sales\".", 22, DatabaseType::Postgre).scope,
+            CompletionScope::Member("sales".to_string())
+        );
+    }
+
+    #[test]
+    fn expression_scope_elsewhere() {
+        for sql in [
+            "SELECT ",
+            "SELECT a, ",
+            "SELECT * FROM t WHERE ",
+            "SELECT * FROM t WHERE x IN (SELECT y, ",
+            "SELECT 'FROM ",
+        ] {
+            assert_eq!(
+                context(sql, DatabaseType::Postgre).scope,
+                CompletionScope::Expression,
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn tables_of_the_statement_with_aliases() {
+        let sql = "SELECT  FROM sales.orders o JOIN customers AS c ON o.id = c.id, items";
+        let context = completion_context(sql, 7, DatabaseType::Postgre);
+        assert_eq!(context.scope, CompletionScope::Expression);
+        assert_eq!(
+            context.tables,
+            vec![
+                reference(Some("sales"), "orders", Some("o")),
+                reference(None, "customers", Some("c")),
+                reference(None, "items", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_of_other_statements_are_ignored() {
+        let sql = "SELECT * FROM a; SELECT  FROM b; SELECT * FROM c";
+        let context = completion_context(sql, 24, DatabaseType::Sqlite);
+        assert_eq!(context.tables, vec![reference(None, "b", None)]);
+    }
+}
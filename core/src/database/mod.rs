@@ -44,6 +44,22 @@ impl DatabaseType {
     pub fn all() -> &'static [DatabaseType] {
         &Self::ALL
     }
+
+    /// Whether a schema holds the catalog or internals of the server rather than user data
+    pub fn is_system_schema(&self, schema: &str) -> bool {
+        let schema = schema.to_lowercase();
+        match self {
+            DatabaseType::MySql | DatabaseType::MariaDB => matches!(
+                schema.as_str(),
+                "information_schema" | "mysql" | "performance_schema" | "sys"
+            ),
+            DatabaseType::Postgre => matches!(schema.as_str(), "information_schema" | "pg_catalog"),
+            DatabaseType::MicrosoftSQLServer => {
+                matches!(schema.as_str(), "information_schema" | "sys" | "guest")
+            }
+            _ => false,
+        }
+    }
 }
 
 impl Display for DatabaseType {
@@ -107,6 +123,18 @@ pub struct ConnectionConfig {
     pub username: String,
     pub saved_password_len: Option<u8>,
     // Using keyring crate to store password, Credentials are stored in the system keychain
+    // Initial database to connect to (required by PostgreSQL)
+    #[serde(default)]
+    pub database: Option<String>,
+    /// Schema browsed last, expanded again when the connection is opened
+    #[serde(default)]
+    pub last_schema: Option<String>,
+    /// Production connections ask before every write, not only before risky statements
+    #[serde(default)]
+    pub production: bool,
+    /// Read-only connections refuse every statement that writes
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 impl ConnectionConfig {
@@ -126,6 +154,36 @@ impl ConnectionConfig {
             remember_password,
             username: username.to_string(),
             saved_password_len,
+            database: None,
+            last_schema: None,
+            production: false,
+            read_only: false,
+        }
+    }
+
+    pub fn with_database(mut self, database: Option<String>) -> Self {
+        self.database = database.filter(|d| !d.is_empty());
+        self
+    }
+
+    pub fn with_production(mut self, production: bool) -> Self {
+        self.production = production;
+        self
+    }
+
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Schema unqualified names resolve to, as far as the configuration tells
+    pub fn default_schema(&self) -> Option<String> {
+        match self.db_type {
+            DatabaseType::MySql | DatabaseType::MariaDB => self.database.clone(),
+            DatabaseType::Postgre => Some("public".to_string()),
+            DatabaseType::Sqlite => Some("main".to_string()),
+            DatabaseType::MicrosoftSQLServer => Some("dbo".to_string()),
+            _ => None,
         }
     }
 }
@@ -5,11 +5,15 @@ use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::{
-    driver::DatabaseDriver,
+    completion::{self, Completions},
+    driver::{DBError, DatabaseDriver, DriverFactory},
     events::{ActiveConnectionsChanged, SelectedConnectionChanged},
-    ConnectionConfig, DBConfig,
+    ConnectionConfig, DBConfig, MetadataCache,
 };
 
+/// Keychain service the connection passwords are stored under
+const KEYRING_SERVICE: &str = "db-sight";
+
 #[derive(Clone)]
 pub struct DBManager {
     /// Active database-driven instances (connected)
@@ -20,6 +24,8 @@ pub struct DBManager {
     config_dir: Arc<PathBuf>,
     /// Currently selected connection ID (InMemory)
     selected_connection_id: Arc<std::sync::RwLock<Option<Uuid>>>,
+    /// Schemas, tables and columns of the connections, for SQL completion
+    metadata: MetadataCache,
 }
 
 impl DBManager {
@@ -35,6 +41,7 @@ impl DBManager {
             config: Arc::new(RwLock::new(DBConfig::new())),
             config_dir: Arc::new(config_dir),
             selected_connection_id: Arc::new(std::sync::RwLock::new(None)),
+            metadata: MetadataCache::default(),
         }
     }
 
@@ -78,7 +85,7 @@ impl DBManager {
         password: Option<String>,
     ) -> Result<()> {
         if let Some(pwd) = password {
-            let entry = keyring::Entry::new("db-sight", &config.id.to_string())?;
+            let entry = keyring::Entry::new(KEYRING_SERVICE, &config.id.to_string())?;
             entry.set_password(&pwd)?;
             config.saved_password_len = Some(pwd.len() as u8);
         }
@@ -96,6 +103,15 @@ impl DBManager {
         Ok(())
     }
 
+    /// Read the saved password of a connection from the system keychain
+    pub fn load_password(config: &ConnectionConfig) -> Option<String> {
+        config.saved_password_len?;
+        keyring::Entry::new(KEYRING_SERVICE, &config.id.to_string())
+            .and_then(|entry| entry.get_password())
+            .map_err(|e| eprintln!("Failed to read password of {}: {}", config.name, e))
+            .ok()
+    }
+
     /// Get all connection configurations
     pub async fn get_all_configs(&self) -> Vec<ConnectionConfig> {
         self.config.read().await.connections.clone()
@@ -112,6 +128,25 @@ impl DBManager {
             .cloned()
     }
 
+    /// Remember the schema last browsed on a connection
+    pub async fn set_last_schema(&self, config_id: &Uuid, schema: &str) -> Result<()> {
+        let mut db_config = self.config.write().await;
+        let Some(config) = db_config
+            .connections
+            .iter_mut()
+            .find(|c| c.id == *config_id)
+        else {
+            return Ok(());
+        };
+        if config.last_schema.as_deref() == Some(schema) {
+            return Ok(());
+        }
+        config.last_schema = Some(schema.to_string());
+        drop(db_config);
+
+        self.persist_config().await
+    }
+
     // ========== Active Connection Management ==========
 
     /// Add active connection
@@ -164,6 +199,71 @@ impl DBManager {
         self.connections.read().await.clone()
     }
 
+    /// Get the driver of a saved connection, connecting and registering it on first use
+    pub async fn connect(&self, config_id: &Uuid) -> Result<Arc<dyn DatabaseDriver>, DBError> {
+        let key = config_id.to_string();
+        if let Some(driver) = self.get_connection(&key).await {
+            return Ok(driver);
+        }
+
+        let config = self.get_config_by_id(config_id).await.ok_or_else(|| {
+            DBError::ConnectionError(format!("No config found for connection {}", key))
+        })?;
+        let password = Self::load_password(&config);
+        let mut driver = DriverFactory::build(&config, password.as_deref())?;
+        driver.connect().await?;
+
+        let driver: Arc<dyn DatabaseDriver> = Arc::from(driver);
+        self.add_connection(key, driver.clone()).await;
+        Ok(driver)
+    }
+
+    /// Whether the connection has an explicit transaction open, connections that were
+    /// never connected have none
+    pub async fn in_transaction(&self, config_id: &Uuid) -> bool {
+        let Some(driver) = self.get_connection(&config_id.to_string()).await else {
+            return false;
+        };
+        match driver.as_transactional() {
+            Some(transactional) => transactional.in_transaction().await,
+            None => false,
+        }
+    }
+
+    // ========== SQL Completion ==========
+
+    /// Completions at the byte `offset` of `sql`, from the cached metadata of the
+    /// connection
+    pub async fn complete_sql(
+        &self,
+        config_id: &Uuid,
+        sql: &str,
+        offset: usize,
+    ) -> Result<Completions, DBError> {
+        let config = self.get_config_by_id(config_id).await.ok_or_else(|| {
+            DBError::ConnectionError(format!("No config found for connection {}", config_id))
+        })?;
+        let driver = self.connect(config_id).await?;
+        let Some(browser) = driver.as_schema_browser() else {
+            return Ok(Completions::default());
+        };
+        completion::complete(
+            &self.metadata,
+            *config_id,
+            browser,
+            sql,
+            offset,
+            config.db_type,
+            config.default_schema().as_deref(),
+        )
+        .await
+    }
+
+    /// Drop the cached metadata of a connection after its schema changed
+    pub async fn refresh_metadata(&self, config_id: &Uuid) {
+        self.metadata.invalidate(config_id).await;
+    }
+
     // ========== Combined Operations ==========
 
     /// Save connection config and add to active connections in one operation.
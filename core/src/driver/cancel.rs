@@ -0,0 +1,65 @@
+use std::sync::{Arc, Mutex};
+
+use crate::driver::DBError;
+
+#[derive(Debug, Default)]
+struct CancelState {
+    /// Server session running the script, e.g. the MySQL connection id
+    session: Option<i64>,
+    cancelled: bool,
+}
+
+/// Handle of a script run through [`QueryCanceller`](crate::driver::QueryCanceller), to stop
+/// it on the server while it runs
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    state: Arc<Mutex<CancelState>>,
+}
+
+impl CancelHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.lock().unwrap().cancelled
+    }
+
+    /// Mark the script cancelled, returns the session to stop if it is running
+    pub(crate) fn cancel(&self) -> Option<i64> {
+        let mut state = self.state.lock().unwrap();
+        state.cancelled = true;
+        state.session
+    }
+
+    /// Record the session the script runs on. Fails when it was cancelled before it
+    /// started.
+    pub(crate) fn start(&self, session: i64) -> Result<(), DBError> {
+        let mut state = self.state.lock().unwrap();
+        if state.cancelled {
+            return Err(DBError::Cancelled);
+        }
+        state.session = Some(session);
+        Ok(())
+    }
+
+    /// Fails once the script is cancelled, checked before each statement
+    pub(crate) fn check(&self) -> Result<(), DBError> {
+        if self.is_cancelled() {
+            return Err(DBError::Cancelled);
+        }
+        Ok(())
+    }
+
+    /// Forget the session once the script is done, so a late cancel can't stop a
+    /// statement of whoever uses the connection next. The error of a statement stopped
+    /// by the server becomes [`DBError::Cancelled`].
+    pub(crate) fn finish<T>(&self, result: Result<T, DBError>) -> Result<T, DBError> {
+        let mut state = self.state.lock().unwrap();
+        state.session = None;
+        match result {
+            Err(_) if state.cancelled => Err(DBError::Cancelled),
+            result => result,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{driver::DBError, model::schema::ObjectKind};
+
+/// `CREATE TABLE` statement with one column or constraint definition per line
+pub(crate) fn create_table(table_ident: &str, definitions: &[String]) -> String {
+    format!(
+        "CREATE TABLE {} (\n    {}\n);",
+        table_ident,
+        definitions.join(",\n    ")
+    )
+}
+
+/// Statements of an object definition, each ending with a semicolon, one blank line apart
+pub(crate) fn join_statements(statements: impl IntoIterator<Item = String>) -> String {
+    statements
+        .into_iter()
+        .map(|statement| {
+            let statement = statement.trim_end();
+            if statement.ends_with(';') {
+                statement.to_string()
+            } else {
+                format!("{};", statement)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+pub(crate) fn object_not_found(kind: ObjectKind, schema: &str, name: &str) -> DBError {
+    DBError::QueryError(format!(
+        "{} {}.{} not found, or its definition is not readable",
+        kind, schema, name
+    ))
+}
@@ -1,15 +1,43 @@
+use ::mongodb::error::Error as MongoError;
+use ::redis::RedisError;
 use async_trait::async_trait;
 use sqlx::Error as SqlxError;
 use thiserror::Error;
+use tiberius::error::Error as TiberiusError;
 
 use crate::model::{
-    schema::DBSchema,
-    table::{TableColumn, TableDataPage, TableInfo},
+    design::{AlterStatement, TableDesign},
+    document::DocumentPage,
+    key_value::{KeyEntry, KeyNode},
+    query::{BoundStatement, QueryParam, StatementResult},
+    schema::{DBSchema, ObjectKind},
+    structure::{ColumnDetail, TableStructure},
+    table::{RowChange, TableColumn, TableDataPage, TableInfo, TableQuery},
 };
 
+mod cancel;
+mod ddl;
+mod factory;
+mod mongodb;
+mod mssql;
 mod mysql;
+mod postgres;
+mod redis;
+mod script;
+mod sqlite;
+mod table_design;
+mod table_edit;
+mod table_query;
+mod transaction;
 
-pub use mysql::MySqlDriver;
+pub use cancel::CancelHandle;
+pub use factory::DriverFactory;
+pub use mongodb::MongoDriver;
+pub use mssql::MsSqlDriver;
+pub use mysql::{MySqlDriver, MySqlFlavor};
+pub use postgres::PostgresDriver;
+pub use redis::RedisDriver;
+pub use sqlite::{SqliteDriver, SQLITE_MEMORY_PATH};
 
 #[derive(Error, Debug)]
 pub enum DBError {
@@ -25,8 +53,18 @@ pub enum DBError {
     QueryError(String),
     #[error("Database transaction error: {0}")]
     TransactionError(String),
+    #[error("The connection is read-only, refused to run: {0}")]
+    ReadOnly(String),
+    #[error("Query cancelled")]
+    Cancelled,
     #[error("Sqlx error: {0}")]
     SqlxError(SqlxError),
+    #[error("Redis error: {0}")]
+    RedisError(RedisError),
+    #[error("MongoDB error: {0}")]
+    MongoError(MongoError),
+    #[error("SQL Server error: {0}")]
+    MsSqlError(TiberiusError),
 }
 
 impl From<SqlxError> for DBError {
@@ -35,6 +73,39 @@ impl From<SqlxError> for DBError {
     }
 }
 
+impl From<RedisError> for DBError {
+    fn from(value: RedisError) -> Self {
+        DBError::RedisError(value)
+    }
+}
+
+impl From<MongoError> for DBError {
+    fn from(value: MongoError) -> Self {
+        DBError::MongoError(value)
+    }
+}
+
+impl From<TiberiusError> for DBError {
+    fn from(value: TiberiusError) -> Self {
+        DBError::MsSqlError(value)
+    }
+}
+
+/// Optional features of a driver, so the UI can enable views per backend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DriverCapabilities {
+    pub schema_browser: bool,
+    pub sql_executor: bool,
+    pub key_value: bool,
+    pub document: bool,
+    pub transactional: bool,
+    pub table_editor: bool,
+    pub table_designer: bool,
+    pub query_canceller: bool,
+}
+
+/// Core connection trait every backend implements. Everything beyond connecting is an
+/// optional capability, exposed through the `as_*` accessors.
 #[async_trait]
 pub trait DatabaseDriver: Send + Sync {
     /// Database Type. E.g. MySQL, PostgreSQL, SQLite, etc.
@@ -46,6 +117,62 @@ pub trait DatabaseDriver: Send + Sync {
     /// Test connection
     async fn test_connection(&self) -> Result<(), DBError>;
 
+    /// Schema/table browsing, for backends organised as schemas of tables
+    fn as_schema_browser(&self) -> Option<&dyn SchemaBrowser> {
+        None
+    }
+
+    /// Running SQL statements
+    fn as_sql_executor(&self) -> Option<&dyn SqlExecutor> {
+        None
+    }
+
+    /// Key-value browsing, for backends that have a key space instead of tables
+    fn as_key_value(&self) -> Option<&dyn KeyValueBrowser> {
+        None
+    }
+
+    /// Raw document browsing, for backends whose rows are nested documents
+    fn as_document(&self) -> Option<&dyn DocumentBrowser> {
+        None
+    }
+
+    /// Explicit transactions
+    fn as_transactional(&self) -> Option<&dyn Transactional> {
+        None
+    }
+
+    /// Writing staged row changes back to tables
+    fn as_table_editor(&self) -> Option<&dyn TableEditor> {
+        None
+    }
+
+    /// Changing the columns, indexes and foreign keys of tables
+    fn as_table_designer(&self) -> Option<&dyn TableDesigner> {
+        None
+    }
+
+    /// Stopping running statements on the server
+    fn as_query_canceller(&self) -> Option<&dyn QueryCanceller> {
+        None
+    }
+
+    fn capabilities(&self) -> DriverCapabilities {
+        DriverCapabilities {
+            schema_browser: self.as_schema_browser().is_some(),
+            sql_executor: self.as_sql_executor().is_some(),
+            key_value: self.as_key_value().is_some(),
+            document: self.as_document().is_some(),
+            transactional: self.as_transactional().is_some(),
+            table_editor: self.as_table_editor().is_some(),
+            table_designer: self.as_table_designer().is_some(),
+            query_canceller: self.as_query_canceller().is_some(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait SchemaBrowser: Send + Sync {
     /// Get All Schemas
     async fn list_schemas(&self) -> Result<Vec<DBSchema>, DBError>;
 
@@ -59,12 +186,172 @@ pub trait DatabaseDriver: Send + Sync {
         table: &str,
     ) -> Result<Vec<TableColumn>, DBError>;
 
-    /// Get Table Data
+    /// Get the rows of a table matching `query`, `total` counts every matching row
     async fn fetch_table_data(
         &self,
         schema: &str,
         table: &str,
-        offset: u64,
-        limit: u64,
+        query: &TableQuery,
     ) -> Result<TableDataPage, DBError>;
+
+    /// Get the columns, indexes, constraints, triggers and storage options of a table
+    async fn get_table_structure(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<TableStructure, DBError> {
+        let columns = self.get_table_columns(schema, table).await?;
+        Ok(TableStructure {
+            columns: columns.into_iter().map(ColumnDetail::from).collect(),
+            ..Default::default()
+        })
+    }
+
+    /// Names of the views of a schema
+    async fn list_views(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        let tables = self.list_tables(schema).await?;
+        Ok(tables
+            .into_iter()
+            .filter(TableInfo::is_view)
+            .map(|table| table.name)
+            .collect())
+    }
+
+    /// Names of the stored procedures of a schema
+    async fn list_procedures(&self, _schema: &str) -> Result<Vec<String>, DBError> {
+        Ok(Vec::new())
+    }
+
+    /// Names of the functions of a schema, overloads listed once
+    async fn list_functions(&self, _schema: &str) -> Result<Vec<String>, DBError> {
+        Ok(Vec::new())
+    }
+
+    /// Names of the triggers of a schema
+    async fn list_triggers(&self, _schema: &str) -> Result<Vec<String>, DBError> {
+        Ok(Vec::new())
+    }
+
+    /// Names of the scheduled events of a schema
+    async fn list_events(&self, _schema: &str) -> Result<Vec<String>, DBError> {
+        Ok(Vec::new())
+    }
+
+    /// `CREATE` statement of a table, view, routine or trigger. Tables also get the
+    /// statements of their indexes and triggers. Engines that keep no DDL have it
+    /// rebuilt from the catalog.
+    async fn get_object_ddl(
+        &self,
+        _schema: &str,
+        _name: &str,
+        kind: ObjectKind,
+    ) -> Result<String, DBError> {
+        Err(DBError::QueryError(format!(
+            "{} definitions are not available for this connection",
+            kind
+        )))
+    }
+}
+
+#[async_trait]
+pub trait TableEditor: Send + Sync {
+    /// The statements `apply_changes` runs for `changes`, for review before applying
+    async fn preview_changes(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+    ) -> Result<Vec<BoundStatement>, DBError>;
+
+    /// Apply `changes` in one transaction. Every change has to hit exactly one row,
+    /// otherwise nothing is applied. Returns the number of rows changed.
+    async fn apply_changes(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+    ) -> Result<u64, DBError>;
+}
+
+#[async_trait]
+pub trait TableDesigner: Send + Sync {
+    /// The statements `apply_design` runs to turn the table into `design`, for review
+    /// before running them
+    async fn preview_design(
+        &self,
+        schema: &str,
+        table: &str,
+        design: &TableDesign,
+    ) -> Result<Vec<AlterStatement>, DBError>;
+
+    /// Alter the table into `design`. Engines without transactional DDL keep the
+    /// statements that ran before a failing one.
+    async fn apply_design(
+        &self,
+        schema: &str,
+        table: &str,
+        design: &TableDesign,
+    ) -> Result<(), DBError>;
+}
+
+#[async_trait]
+pub trait SqlExecutor: Send + Sync {
+    /// Execute one or more `;`-separated statements in order, stopping at the first error.
+    /// `params` are bound to the placeholders of a script holding a single statement.
+    async fn execute(
+        &self,
+        sql: &str,
+        params: &[QueryParam],
+    ) -> Result<Vec<StatementResult>, DBError>;
+}
+
+#[async_trait]
+pub trait QueryCanceller: Send + Sync {
+    /// Execute a script like [`SqlExecutor::execute`], `handle` can stop it while it runs
+    async fn execute_cancellable(
+        &self,
+        sql: &str,
+        handle: &CancelHandle,
+    ) -> Result<Vec<StatementResult>, DBError>;
+
+    /// Stop the statement running under `handle` on the server, its script then fails
+    /// with [`DBError::Cancelled`]. The connection stays usable, an open transaction
+    /// stays open.
+    async fn cancel(&self, handle: &CancelHandle) -> Result<(), DBError>;
+}
+
+#[async_trait]
+pub trait Transactional: Send + Sync {
+    /// Start a transaction, later statements run in it until commit or rollback
+    async fn begin(&self) -> Result<(), DBError>;
+
+    async fn commit(&self) -> Result<(), DBError>;
+
+    async fn rollback(&self) -> Result<(), DBError>;
+
+    /// Whether a transaction was begun and not yet committed or rolled back
+    async fn in_transaction(&self) -> bool;
+}
+
+#[async_trait]
+pub trait KeyValueBrowser: Send + Sync {
+    /// List the logical databases of the key space
+    async fn list_databases(&self) -> Result<Vec<DBSchema>, DBError>;
+
+    /// List the direct children of `prefix` in the key tree, an empty prefix lists the root
+    async fn list_key_nodes(&self, schema: &str, prefix: &str) -> Result<Vec<KeyNode>, DBError>;
+
+    /// Get a key with its type, TTL and value
+    async fn get_key(&self, schema: &str, key: &str) -> Result<KeyEntry, DBError>;
+}
+
+#[async_trait]
+pub trait DocumentBrowser: Send + Sync {
+    /// Get a page of documents as JSON, using the same query as `fetch_table_data`
+    async fn fetch_documents(
+        &self,
+        schema: &str,
+        collection: &str,
+        query: &TableQuery,
+    ) -> Result<DocumentPage, DBError>;
 }
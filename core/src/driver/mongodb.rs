@@ -0,0 +1,542 @@
+use async_trait::async_trait;
+use mongodb::{
+    bson::{doc, oid::ObjectId, spec::BinarySubtype, Binary, Bson, Document},
+    error::{Error as MongoError, ErrorKind},
+    options::ClientOptions,
+    results::CollectionType,
+    Client, Collection, Cursor,
+};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+
+use crate::{
+    driver::{DBError, DatabaseDriver, DocumentBrowser, SchemaBrowser},
+    model::{
+        document::DocumentPage,
+        query::{QueryParam, ResultColumn},
+        schema::DBSchema,
+        structure::{ColumnDetail, IndexColumn, TableIndex, TableStructure},
+        table::{Filter, SortDirection, TableColumn, TableDataPage, TableInfo, TableQuery},
+        value::{CellValue, ColumnKind},
+    },
+};
+
+/// Number of documents sampled to infer the column set of a collection
+const SAMPLE_SIZE: i64 = 100;
+/// Column holding the primary key of every document, always shown first
+const ID_FIELD: &str = "_id";
+
+/// Documents sampled from a collection
+type Sample = Arc<Vec<Document>>;
+
+pub struct MongoDriver {
+    pub uri: String,
+    pub client: Option<Client>,
+    /// Sample of each collection by database and name, taken once so the columns keep
+    /// their set and order between pages and reloads
+    samples: RwLock<HashMap<(String, String), Sample>>,
+}
+
+impl MongoDriver {
+    pub fn new(uri: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            client: None,
+            samples: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn client(&self) -> Result<&Client, DBError> {
+        self.client
+            .as_ref()
+            .ok_or(DBError::ConnectionError("Not connected".to_string()))
+    }
+
+    fn collection(&self, schema: &str, table: &str) -> Result<Collection<Document>, DBError> {
+        Ok(self.client()?.database(schema).collection(table))
+    }
+
+    async fn build_client(&self) -> Result<Client, DBError> {
+        let mut options = ClientOptions::parse(&self.uri)
+            .await
+            .map_err(Self::map_connect_error)?;
+        options.connect_timeout = Some(Duration::from_secs(10));
+        options.server_selection_timeout = Some(Duration::from_secs(10));
+        options.max_pool_size = Some(5);
+        Ok(Client::with_options(options)?)
+    }
+
+    /// The driver connects lazily, a `ping` forces the handshake and authentication
+    async fn ping(client: &Client) -> Result<(), DBError> {
+        client
+            .database("admin")
+            .run_command(doc! { "ping": 1 })
+            .await
+            .map(|_| ())
+            .map_err(Self::map_connect_error)
+    }
+
+    fn map_connect_error(e: MongoError) -> DBError {
+        match *e.kind {
+            ErrorKind::Authentication { .. } => DBError::AuthFailedError,
+            ErrorKind::Command(ref command) if command.code == 18 => DBError::AuthFailedError,
+            ErrorKind::ServerSelection { .. } => DBError::ConnectionTimeout,
+            _ => DBError::ConnectionError(format!("{:?}", e)),
+        }
+    }
+
+    async fn collect(mut cursor: Cursor<Document>) -> Result<Vec<Document>, DBError> {
+        let mut documents = Vec::new();
+        while cursor.advance().await? {
+            documents.push(cursor.deserialize_current()?);
+        }
+        Ok(documents)
+    }
+
+    async fn sample_documents(&self, schema: &str, table: &str) -> Result<Sample, DBError> {
+        let key = (schema.to_string(), table.to_string());
+        if let Some(samples) = self.samples.read().await.get(&key) {
+            return Ok(samples.clone());
+        }
+        let cursor = self
+            .collection(schema, table)?
+            .aggregate(vec![doc! { "$sample": { "size": SAMPLE_SIZE } }])
+            .await?;
+        let samples = Arc::new(Self::collect(cursor).await?);
+        self.samples.write().await.insert(key, samples.clone());
+        Ok(samples)
+    }
+
+    async fn find_page(
+        &self,
+        schema: &str,
+        table: &str,
+        query: &TableQuery,
+    ) -> Result<Vec<Document>, DBError> {
+        let mut filter = Self::query_filter(query);
+        let mut skip = query.offset;
+        // Keyset paging seeks past the last `_id` instead of skipping documents
+        if let Some(after) = &query.after {
+            let [last_id] = after.as_slice() else {
+                return Err(DBError::QueryError(
+                    "Keyset paging needs a value for each primary key column".to_string(),
+                ));
+            };
+            if !query.order_by.is_empty() {
+                return Err(DBError::QueryError(
+                    "Keyset paging only follows the primary key order".to_string(),
+                ));
+            }
+            let seek = doc! { ID_FIELD: { "$gt": Self::param_bson(ID_FIELD, last_id) } };
+            filter = if filter.is_empty() {
+                seek
+            } else {
+                doc! { "$and": [filter, seek] }
+            };
+            skip = 0;
+        }
+        let cursor = self
+            .collection(schema, table)?
+            .find(filter)
+            .sort(Self::query_sort(query))
+            .skip(skip)
+            .limit(query.limit as i64)
+            .await?;
+        Self::collect(cursor).await
+    }
+
+    /// Number of matching documents, and whether it is the collection's estimate
+    async fn count(
+        &self,
+        schema: &str,
+        table: &str,
+        query: &TableQuery,
+    ) -> Result<(u64, bool), DBError> {
+        let collection = self.collection(schema, table)?;
+        if query.estimate_total() {
+            Ok((collection.estimated_document_count().await?, true))
+        } else {
+            let total = collection
+                .count_documents(Self::query_filter(query))
+                .await?;
+            Ok((total, false))
+        }
+    }
+
+    fn query_filter(query: &TableQuery) -> Document {
+        query
+            .filter
+            .as_ref()
+            .map(Self::filter_document)
+            .unwrap_or_default()
+    }
+
+    /// Sort keys of the query, ending with `_id` so that pages are stable
+    fn query_sort(query: &TableQuery) -> Document {
+        let mut sort = Document::new();
+        for order in &query.order_by {
+            let direction = match order.direction {
+                SortDirection::Asc => 1,
+                SortDirection::Desc => -1,
+            };
+            sort.insert(order.column.clone(), direction);
+        }
+        if !sort.contains_key(ID_FIELD) {
+            sort.insert(ID_FIELD, 1);
+        }
+        sort
+    }
+
+    /// Translate a filter into a query document, `LIKE` patterns become anchored regexes
+    fn filter_document(filter: &Filter) -> Document {
+        match filter {
+            Filter::Eq(field, value) => doc! { field: Self::param_bson(field, value) },
+            Filter::NotEq(field, value) => {
+                doc! { field: { "$ne": Self::param_bson(field, value) } }
+            }
+            Filter::Like(field, pattern) => {
+                doc! { field: { "$regex": Self::like_regex(pattern) } }
+            }
+            Filter::In(field, values) => {
+                let values: Vec<Bson> = values.iter().map(|v| Self::param_bson(field, v)).collect();
+                doc! { field: { "$in": values } }
+            }
+            // Missing fields match `null` as well
+            Filter::IsNull(field) => doc! { field: Bson::Null },
+            Filter::IsNotNull(field) => doc! { field: { "$ne": Bson::Null } },
+            Filter::Between(field, low, high) => doc! {
+                field: {
+                    "$gte": Self::param_bson(field, low),
+                    "$lte": Self::param_bson(field, high),
+                }
+            },
+            Filter::And(filters) if filters.is_empty() => Document::new(),
+            Filter::Or(filters) if filters.is_empty() => doc! { ID_FIELD: { "$in": [] } },
+            Filter::And(filters) => {
+                let filters: Vec<Document> = filters.iter().map(Self::filter_document).collect();
+                doc! { "$and": filters }
+            }
+            Filter::Or(filters) => {
+                let filters: Vec<Document> = filters.iter().map(Self::filter_document).collect();
+                doc! { "$or": filters }
+            }
+        }
+    }
+
+    /// A hex string compared to `_id` is taken as the ObjectId it spells
+    fn param_bson(field: &str, value: &QueryParam) -> Bson {
+        match value {
+            QueryParam::Null => Bson::Null,
+            QueryParam::Bool(b) => Bson::Boolean(*b),
+            QueryParam::Int(n) => Bson::Int64(*n),
+            QueryParam::Float(f) => Bson::Double(*f),
+            QueryParam::Text(s) => match ObjectId::parse_str(s) {
+                Ok(oid) if field == ID_FIELD => Bson::ObjectId(oid),
+                _ => Bson::String(s.clone()),
+            },
+            QueryParam::Bytes(bytes) => Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: bytes.clone(),
+            }),
+        }
+    }
+
+    fn like_regex(pattern: &str) -> String {
+        let mut regex = String::from("^");
+        for c in pattern.chars() {
+            match c {
+                '%' => regex.push_str(".*"),
+                '_' => regex.push('.'),
+                c if "\\^$.|?*+()[]{}".contains(c) => {
+                    regex.push('\\');
+                    regex.push(c);
+                }
+                c => regex.push(c),
+            }
+        }
+        regex.push('$');
+        regex
+    }
+
+    /// Union of top-level fields in order of first appearance, `_id` first
+    fn infer_columns<'a>(documents: impl IntoIterator<Item = &'a Document>) -> Vec<String> {
+        let mut columns = vec![ID_FIELD.to_string()];
+        for document in documents {
+            for key in document.keys() {
+                if !columns.iter().any(|c| c == key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+        columns
+    }
+
+    /// Alias of a BSON type as accepted by the `$type` query operator
+    fn type_name(value: &Bson) -> &'static str {
+        match value {
+            Bson::Double(_) => "double",
+            Bson::String(_) => "string",
+            Bson::Document(_) => "object",
+            Bson::Array(_) => "array",
+            Bson::Binary(_) => "binData",
+            Bson::Undefined => "undefined",
+            Bson::ObjectId(_) => "objectId",
+            Bson::Boolean(_) => "bool",
+            Bson::DateTime(_) => "date",
+            Bson::Null => "null",
+            Bson::RegularExpression(_) => "regex",
+            Bson::DbPointer(_) => "dbPointer",
+            Bson::JavaScriptCode(_) => "javascript",
+            Bson::Symbol(_) => "symbol",
+            Bson::JavaScriptCodeWithScope(_) => "javascriptWithScope",
+            Bson::Int32(_) => "int",
+            Bson::Timestamp(_) => "timestamp",
+            Bson::Int64(_) => "long",
+            Bson::Decimal128(_) => "decimal",
+            Bson::MaxKey => "maxKey",
+            Bson::MinKey => "minKey",
+        }
+    }
+
+    fn column_kind(value: &Bson) -> ColumnKind {
+        match value {
+            Bson::Int32(_) | Bson::Int64(_) => ColumnKind::Integer,
+            Bson::Double(_) => ColumnKind::Float,
+            Bson::Decimal128(_) => ColumnKind::Decimal,
+            Bson::Boolean(_) => ColumnKind::Bool,
+            Bson::DateTime(_) => ColumnKind::DateTime,
+            Bson::Binary(_) => ColumnKind::Bytes,
+            Bson::Document(_) | Bson::Array(_) => ColumnKind::Json,
+            _ => ColumnKind::Text,
+        }
+    }
+
+    /// Column of a page, typed after its first non-null value
+    fn page_column<'a>(
+        name: String,
+        documents: impl IntoIterator<Item = &'a Document>,
+    ) -> ResultColumn {
+        let sample = documents
+            .into_iter()
+            .filter_map(|document| document.get(&name))
+            .find(|value| !matches!(value, Bson::Null | Bson::Undefined));
+        ResultColumn {
+            data_type: sample.map_or("null", Self::type_name).to_string(),
+            kind: sample.map(Self::column_kind).unwrap_or_default(),
+            name,
+        }
+    }
+
+    /// Scalars keep their type, nested values become relaxed Extended JSON
+    fn bson_value(value: Option<&Bson>) -> CellValue {
+        match value {
+            None | Some(Bson::Null) | Some(Bson::Undefined) => CellValue::Null,
+            Some(Bson::String(s)) => CellValue::Text(s.clone()),
+            Some(Bson::ObjectId(oid)) => CellValue::Text(oid.to_hex()),
+            Some(Bson::Boolean(b)) => CellValue::Bool(*b),
+            Some(Bson::Int32(n)) => CellValue::Int((*n).into()),
+            Some(Bson::Int64(n)) => CellValue::Int(*n),
+            Some(Bson::Double(n)) => CellValue::Float(*n),
+            Some(Bson::Decimal128(d)) => CellValue::Decimal(d.to_string()),
+            Some(Bson::DateTime(dt)) => dt
+                .try_to_rfc3339_string()
+                .ok()
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map_or(CellValue::Int(dt.timestamp_millis()), CellValue::DateTimeTz),
+            Some(Bson::Binary(binary)) => CellValue::Bytes(binary.bytes.clone()),
+            Some(other) => CellValue::Json(other.clone().into_relaxed_extjson()),
+        }
+    }
+
+    fn format_document(document: Document) -> String {
+        let json = Bson::Document(document).into_relaxed_extjson();
+        serde_json::to_string_pretty(&json).unwrap_or_else(|_| json.to_string())
+    }
+}
+
+#[async_trait]
+impl DatabaseDriver for MongoDriver {
+    fn name(&self) -> &'static str {
+        "MongoDB"
+    }
+
+    async fn connect(&mut self) -> Result<(), DBError> {
+        let client = self.build_client().await?;
+        Self::ping(&client).await?;
+        self.client = Some(client);
+        self.samples.get_mut().clear();
+        Ok(())
+    }
+
+    async fn test_connection(&self) -> Result<(), DBError> {
+        let client = self.build_client().await?;
+        let result = Self::ping(&client).await;
+        client.shutdown().await;
+        if let Err(e) = &result {
+            eprintln!("Test Connection Failed, Reason: {:?}", e);
+        }
+        result
+    }
+
+    fn as_schema_browser(&self) -> Option<&dyn SchemaBrowser> {
+        Some(self)
+    }
+
+    fn as_document(&self) -> Option<&dyn DocumentBrowser> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl SchemaBrowser for MongoDriver {
+    async fn list_schemas(&self) -> Result<Vec<DBSchema>, DBError> {
+        let names = self.client()?.list_database_names().await?;
+        Ok(names.into_iter().map(|name| DBSchema { name }).collect())
+    }
+
+    async fn list_tables(&self, schema: &str) -> Result<Vec<TableInfo>, DBError> {
+        let mut cursor = self.client()?.database(schema).list_collections().await?;
+        let mut tables = Vec::new();
+        while cursor.advance().await? {
+            let spec = cursor.deserialize_current()?;
+            let table_type = match spec.collection_type {
+                CollectionType::View => "VIEW",
+                CollectionType::Timeseries => "TIMESERIES",
+                _ => "COLLECTION",
+            };
+            tables.push(TableInfo {
+                name: spec.name,
+                table_type: table_type.to_string(),
+            });
+        }
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tables)
+    }
+
+    async fn get_table_columns(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableColumn>, DBError> {
+        let samples = self.sample_documents(schema, table).await?;
+        let columns = Self::infer_columns(samples.iter())
+            .into_iter()
+            .map(|name| {
+                let values: Vec<&Bson> = samples.iter().filter_map(|d| d.get(&name)).collect();
+                let data_type = values
+                    .iter()
+                    .find(|v| !matches!(v, Bson::Null))
+                    .map(|v| Self::type_name(v))
+                    .unwrap_or("null")
+                    .to_string();
+                TableColumn {
+                    nullable: values.len() < samples.len()
+                        || values.iter().any(|v| matches!(v, Bson::Null)),
+                    primary_key: (name == ID_FIELD).then_some(1),
+                    name,
+                    data_type,
+                    default: None,
+                }
+            })
+            .collect();
+        Ok(columns)
+    }
+
+    async fn fetch_table_data(
+        &self,
+        schema: &str,
+        table: &str,
+        query: &TableQuery,
+    ) -> Result<TableDataPage, DBError> {
+        // 1. Columns of the collection sample, then any field only present on this page
+        let samples = self.sample_documents(schema, table).await?;
+        let documents = self.find_page(schema, table, query).await?;
+        let columns = Self::infer_columns(samples.iter().chain(documents.iter()));
+
+        // 2. Flatten the top level of each document into a row
+        let rows = documents
+            .iter()
+            .map(|document| {
+                columns
+                    .iter()
+                    .map(|column| Self::bson_value(document.get(column)))
+                    .collect()
+            })
+            .collect();
+
+        // 3. Count the matching documents
+        let (total, total_estimated) = self.count(schema, table, query).await?;
+
+        Ok(TableDataPage {
+            columns: columns
+                .into_iter()
+                .map(|name| Self::page_column(name, samples.iter().chain(&documents)))
+                .collect(),
+            rows,
+            total,
+            total_estimated,
+            primary_key: vec![ID_FIELD.to_string()],
+            unique_key: Vec::new(),
+        })
+    }
+
+    /// Columns inferred from a sample plus the indexes of the collection
+    async fn get_table_structure(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<TableStructure, DBError> {
+        let columns = self.get_table_columns(schema, table).await?;
+        let mut cursor = self.collection(schema, table)?.list_indexes().await?;
+        let mut indexes = Vec::new();
+        while cursor.advance().await? {
+            let model = cursor.deserialize_current()?;
+            let options = model.options.unwrap_or_default();
+            let name = options.name.unwrap_or_default();
+            // Keys are 1 or -1 for ordered indexes, otherwise the index type, e.g. "text"
+            let method = model
+                .keys
+                .values()
+                .find_map(|value| value.as_str().map(String::from));
+            indexes.push(TableIndex {
+                primary: name == "_id_",
+                unique: name == "_id_" || options.unique.unwrap_or(false),
+                columns: model
+                    .keys
+                    .iter()
+                    .map(|(field, value)| IndexColumn {
+                        descending: matches!(value, Bson::Int32(-1) | Bson::Int64(-1))
+                            || value.as_f64() == Some(-1.0),
+                        ..IndexColumn::new(field.as_str())
+                    })
+                    .collect(),
+                name,
+                method,
+            });
+        }
+
+        Ok(TableStructure {
+            columns: columns.into_iter().map(ColumnDetail::from).collect(),
+            indexes,
+            ..Default::default()
+        })
+    }
+}
+
+#[async_trait]
+impl DocumentBrowser for MongoDriver {
+    async fn fetch_documents(
+        &self,
+        schema: &str,
+        collection: &str,
+        query: &TableQuery,
+    ) -> Result<DocumentPage, DBError> {
+        let documents = self.find_page(schema, collection, query).await?;
+        let (total, _) = self.count(schema, collection, query).await?;
+
+        Ok(DocumentPage {
+            documents: documents.into_iter().map(Self::format_document).collect(),
+            total,
+        })
+    }
+}
@@ -0,0 +1,1415 @@
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use tiberius::{
+    error::Error as TiberiusError,
+    time::chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime},
+    Client, ColumnData, ColumnType, Config, FromSql, QueryItem, Row, ToSql,
+};
+use tokio::{net::TcpStream, sync::Mutex};
+use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
+
+use crate::{
+    database::DatabaseType,
+    driver::{
+        ddl::{create_table, join_statements, object_not_found},
+        script::script_statements,
+        table_design::{
+            add_foreign_key, column_changes, create_index, transaction_open_error, ColumnChange,
+        },
+        table_edit::{change_statements, stale_row_error},
+        table_query::CompiledQuery,
+        DBError, DatabaseDriver, SchemaBrowser, SqlExecutor, TableDesigner, TableEditor,
+        Transactional,
+    },
+    model::{
+        design::{AlterStatement, ColumnDesign, TableDesign},
+        query::{
+            BoundStatement, ExecSummary, QueryParam, QueryResult, ResultColumn, ResultSet,
+            StatementResult,
+        },
+        schema::{DBSchema, ObjectKind},
+        structure::{
+            CheckConstraint, ColumnDetail, ForeignKey, IndexColumn, TableIndex, TableOptions,
+            TableStructure, TableTrigger,
+        },
+        table::{RowChange, TableColumn, TableDataPage, TableInfo, TableQuery},
+        value::{CellValue, ColumnKind},
+    },
+    sql::first_keyword,
+};
+
+/// SQL Server error number of `Login failed for user`
+const LOGIN_FAILED: u32 = 18456;
+/// Schemas from this id on belong to the fixed database roles (`db_owner`, ...)
+const FIRST_ROLE_SCHEMA_ID: i32 = 16384;
+
+type MsSqlClient = Client<Compat<TcpStream>>;
+
+pub struct MsSqlDriver {
+    /// ADO.NET connection string, see [`MsSqlDriver::ado_string`]
+    pub uri: String,
+    pub client: Option<Mutex<MsSqlClient>>,
+    /// Set between `begin` and `commit` or `rollback`. There is a single client, so
+    /// everything runs in the open transaction.
+    transaction_open: AtomicBool,
+    /// SQL Server has no session switch refusing writes, only the classification guards
+    pub read_only: bool,
+}
+
+impl MsSqlDriver {
+    pub fn new(uri: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            client: None,
+            transaction_open: AtomicBool::new(false),
+            read_only: false,
+        }
+    }
+
+    /// Refuse statements that write, and drop the editing capabilities
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Build an ADO.NET connection string. Without credentials the connection uses
+    /// integrated (Windows) authentication as the current user.
+    pub fn ado_string(
+        host: &str,
+        port: &str,
+        database: &str,
+        credentials: Option<(&str, &str)>,
+    ) -> String {
+        let mut ado = format!(
+            "server=tcp:{},{};database={};TrustServerCertificate=true;",
+            host,
+            port,
+            Self::ado_value(database)
+        );
+        match credentials {
+            Some((username, password)) => ado.push_str(&format!(
+                "user={};password={};",
+                Self::ado_value(username),
+                Self::ado_value(password)
+            )),
+            None => ado.push_str("IntegratedSecurity=true;"),
+        }
+        ado
+    }
+
+    /// Quote a connection string value when it contains delimiters
+    fn ado_value(value: &str) -> String {
+        if value.contains([';', '=', '"', '\'']) || value.trim() != value {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    fn client(&self) -> Result<&Mutex<MsSqlClient>, DBError> {
+        self.client
+            .as_ref()
+            .ok_or(DBError::ConnectionError("Not connected".to_string()))
+    }
+
+    /// Bracket-quote an identifier, e.g. dbo -> [dbo]
+    fn quote_ident(ident: &str) -> String {
+        format!("[{}]", ident.replace(']', "]]"))
+    }
+
+    async fn open_client(uri: &str) -> Result<MsSqlClient, DBError> {
+        let config = Config::from_ado_string(uri).map_err(Self::map_connect_error)?;
+        match Self::connect_to(config.clone(), config.get_addr()).await {
+            // Azure SQL may redirect the login to another node
+            Err(TiberiusError::Routing { host, port }) => {
+                Self::connect_to(config, format!("{}:{}", host, port))
+                    .await
+                    .map_err(Self::map_connect_error)
+            }
+            result => result.map_err(Self::map_connect_error),
+        }
+    }
+
+    async fn connect_to(config: Config, addr: String) -> Result<MsSqlClient, TiberiusError> {
+        let tcp = tokio::time::timeout(Duration::from_secs(10), TcpStream::connect(addr))
+            .await
+            .map_err(|_| TiberiusError::Io {
+                kind: std::io::ErrorKind::TimedOut,
+                message: "Connection timeout".to_string(),
+            })??;
+        tcp.set_nodelay(true)?;
+        Client::connect(config, tcp.compat_write()).await
+    }
+
+    fn map_connect_error(e: TiberiusError) -> DBError {
+        match &e {
+            TiberiusError::Server(token) if token.code() == LOGIN_FAILED => {
+                DBError::AuthFailedError
+            }
+            TiberiusError::Io { kind, .. } if *kind == std::io::ErrorKind::TimedOut => {
+                DBError::ConnectionTimeout
+            }
+            _ => DBError::ConnectionError(format!("{:?}", e)),
+        }
+    }
+
+    /// Row count kept in `sys.partitions`, `None` for views
+    async fn estimate_rows(
+        client: &mut MsSqlClient,
+        table_ident: &str,
+    ) -> Result<Option<u64>, DBError> {
+        let row = client
+            .query(
+                "SELECT SUM(rows) FROM sys.partitions \
+                 WHERE object_id = OBJECT_ID(@P1) AND index_id IN (0, 1)",
+                &[&table_ident],
+            )
+            .await?
+            .into_row()
+            .await?;
+        Ok(row.and_then(|row| row.get::<i64, _>(0)).map(|n| n as u64))
+    }
+
+    /// Columns with their description, identity flag and collation
+    async fn column_details(
+        client: &mut MsSqlClient,
+        table_ident: &str,
+        columns: Vec<TableColumn>,
+    ) -> Result<Vec<ColumnDetail>, DBError> {
+        let rows = client
+            .query(
+                "SELECT c.name, CAST(ep.value AS nvarchar(max)), c.is_identity, c.collation_name \
+                 FROM sys.columns c \
+                 LEFT JOIN sys.extended_properties ep ON ep.class = 1 \
+                 AND ep.major_id = c.object_id AND ep.minor_id = c.column_id \
+                 AND ep.name = 'MS_Description' \
+                 WHERE c.object_id = OBJECT_ID(@P1)",
+                &[&table_ident],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+
+        let mut rows: HashMap<String, Row> = rows
+            .into_iter()
+            .map(|row| (row.get::<&str, _>(0).unwrap_or_default().to_string(), row))
+            .collect();
+        Ok(columns
+            .into_iter()
+            .map(|column| {
+                let Some(row) = rows.remove(&column.name) else {
+                    return ColumnDetail::from(column);
+                };
+                ColumnDetail {
+                    column,
+                    comment: row.get::<&str, _>(1).map(String::from),
+                    auto_increment: row.get::<bool, _>(2).unwrap_or(false),
+                    // The code page follows from the collation
+                    charset: None,
+                    collation: row.get::<&str, _>(3).map(String::from),
+                }
+            })
+            .collect())
+    }
+
+    async fn table_indexes(
+        client: &mut MsSqlClient,
+        table_ident: &str,
+    ) -> Result<Vec<TableIndex>, DBError> {
+        let rows = client
+            .query(
+                "SELECT i.name, i.is_unique, i.is_primary_key, i.type_desc, c.name, \
+                 ic.is_descending_key \
+                 FROM sys.indexes i \
+                 JOIN sys.index_columns ic \
+                 ON ic.object_id = i.object_id AND ic.index_id = i.index_id \
+                 JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id \
+                 WHERE i.object_id = OBJECT_ID(@P1) AND ic.is_included_column = 0 \
+                 ORDER BY i.is_primary_key DESC, i.name, ic.key_ordinal",
+                &[&table_ident],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+
+        Ok(TableIndex::group(rows.iter().map(|row| {
+            let index = TableIndex {
+                name: row.get::<&str, _>(0).unwrap_or_default().to_string(),
+                columns: Vec::new(),
+                unique: row.get::<bool, _>(1).unwrap_or(false),
+                primary: row.get::<bool, _>(2).unwrap_or(false),
+                method: row.get::<&str, _>(3).map(String::from),
+            };
+            let column = IndexColumn {
+                descending: row.get::<bool, _>(5).unwrap_or(false),
+                ..IndexColumn::new(row.get::<&str, _>(4).unwrap_or_default())
+            };
+            (index, column)
+        })))
+    }
+
+    async fn table_foreign_keys(
+        client: &mut MsSqlClient,
+        table_ident: &str,
+    ) -> Result<Vec<ForeignKey>, DBError> {
+        let rows = client
+            .query(
+                "SELECT fk.name, pc.name, OBJECT_SCHEMA_NAME(fk.referenced_object_id), \
+                 OBJECT_NAME(fk.referenced_object_id), rc.name, \
+                 REPLACE(fk.update_referential_action_desc, '_', ' '), \
+                 REPLACE(fk.delete_referential_action_desc, '_', ' ') \
+                 FROM sys.foreign_keys fk \
+                 JOIN sys.foreign_key_columns fkc ON fkc.constraint_object_id = fk.object_id \
+                 JOIN sys.columns pc \
+                 ON pc.object_id = fkc.parent_object_id AND pc.column_id = fkc.parent_column_id \
+                 JOIN sys.columns rc ON rc.object_id = fkc.referenced_object_id \
+                 AND rc.column_id = fkc.referenced_column_id \
+                 WHERE fk.parent_object_id = OBJECT_ID(@P1) \
+                 ORDER BY fk.name, fkc.constraint_column_id",
+                &[&table_ident],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+
+        let text = |row: &Row, idx: usize| row.get::<&str, _>(idx).unwrap_or_default().to_string();
+        Ok(ForeignKey::group(rows.iter().map(|row| {
+            let key = ForeignKey {
+                name: text(row, 0),
+                columns: Vec::new(),
+                referenced_schema: text(row, 2),
+                referenced_table: text(row, 3),
+                referenced_columns: Vec::new(),
+                on_update: text(row, 5),
+                on_delete: text(row, 6),
+            };
+            (key, text(row, 1), text(row, 4))
+        })))
+    }
+
+    async fn table_checks(
+        client: &mut MsSqlClient,
+        table_ident: &str,
+    ) -> Result<Vec<CheckConstraint>, DBError> {
+        let rows = client
+            .query(
+                "SELECT name, definition FROM sys.check_constraints \
+                 WHERE parent_object_id = OBJECT_ID(@P1) ORDER BY name",
+                &[&table_ident],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| CheckConstraint {
+                name: row.get::<&str, _>(0).unwrap_or_default().to_string(),
+                expression: row.get::<&str, _>(1).unwrap_or_default().to_string(),
+            })
+            .collect())
+    }
+
+    /// Triggers with their events joined, one row comes back per trigger and event
+    async fn table_triggers(
+        client: &mut MsSqlClient,
+        table_ident: &str,
+    ) -> Result<Vec<TableTrigger>, DBError> {
+        let rows = client
+            .query(
+                "SELECT t.name, \
+                 CASE WHEN t.is_instead_of_trigger = 1 THEN 'INSTEAD OF' ELSE 'AFTER' END, \
+                 e.type_desc, OBJECT_DEFINITION(t.object_id) \
+                 FROM sys.triggers t \
+                 JOIN sys.trigger_events e ON e.object_id = t.object_id \
+                 WHERE t.parent_id = OBJECT_ID(@P1) \
+                 ORDER BY t.name, e.type",
+                &[&table_ident],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+
+        let mut triggers: Vec<TableTrigger> = Vec::new();
+        for row in &rows {
+            let name = row.get::<&str, _>(0).unwrap_or_default();
+            let event = row.get::<&str, _>(2).unwrap_or_default();
+            match triggers.last_mut() {
+                Some(last) if last.name == name => {
+                    last.event = format!("{} OR {}", last.event, event);
+                }
+                _ => triggers.push(TableTrigger {
+                    name: name.to_string(),
+                    timing: row.get::<&str, _>(1).unwrap_or_default().to_string(),
+                    event: event.to_string(),
+                    statement: row.get::<&str, _>(3).unwrap_or_default().to_string(),
+                }),
+            }
+        }
+        Ok(triggers)
+    }
+
+    /// Description, compression as the row format, and allocated size
+    async fn table_options(
+        client: &mut MsSqlClient,
+        table_ident: &str,
+    ) -> Result<TableOptions, DBError> {
+        let row = client
+            .query(
+                "SELECT CAST(ep.value AS nvarchar(max)), \
+                 (SELECT TOP 1 p.data_compression_desc FROM sys.partitions p \
+                 WHERE p.object_id = o.object_id), \
+                 (SELECT CAST(SUM(CAST(a.total_pages AS bigint)) * 8192 AS bigint) \
+                 FROM sys.partitions p \
+                 JOIN sys.allocation_units a ON a.container_id = p.partition_id \
+                 WHERE p.object_id = o.object_id) \
+                 FROM sys.objects o \
+                 LEFT JOIN sys.extended_properties ep ON ep.class = 1 \
+                 AND ep.major_id = o.object_id AND ep.minor_id = 0 \
+                 AND ep.name = 'MS_Description' \
+                 WHERE o.object_id = OBJECT_ID(@P1)",
+                &[&table_ident],
+            )
+            .await?
+            .into_row()
+            .await?;
+
+        Ok(row
+            .map(|row| TableOptions {
+                engine: None,
+                row_format: row.get::<&str, _>(1).map(String::from),
+                collation: None,
+                comment: row.get::<&str, _>(0).map(String::from),
+                size_bytes: row.get::<i64, _>(2).map(|size| size as u64),
+            })
+            .unwrap_or_default())
+    }
+
+    /// Column type as written in a definition, e.g. `nvarchar(50)` or `decimal(10, 2)`
+    fn type_sql(type_name: &str, max_length: i16, precision: u8, scale: u8) -> String {
+        let length = |bytes_per_char: i16| {
+            if max_length == -1 {
+                "max".to_string()
+            } else {
+                (max_length / bytes_per_char).to_string()
+            }
+        };
+        match type_name {
+            "varchar" | "char" | "varbinary" | "binary" => {
+                format!("{}({})", type_name, length(1))
+            }
+            "nvarchar" | "nchar" => format!("{}({})", type_name, length(2)),
+            "decimal" | "numeric" => format!("{}({}, {})", type_name, precision, scale),
+            "datetime2" | "time" | "datetimeoffset" => format!("{}({})", type_name, scale),
+            _ => type_name.to_string(),
+        }
+    }
+
+    /// Names of the objects of the given `sys.objects` types in a schema
+    async fn object_names(&self, schema: &str, types: &str) -> Result<Vec<String>, DBError> {
+        let mut client = self.client()?.lock().await;
+        let sql = format!(
+            "SELECT o.name FROM sys.objects o \
+             JOIN sys.schemas s ON s.schema_id = o.schema_id \
+             WHERE s.name = @P1 AND o.type IN ({}) AND o.is_ms_shipped = 0 \
+             ORDER BY o.name",
+            types
+        );
+        let rows = client
+            .query(sql, &[&schema])
+            .await?
+            .into_first_result()
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| row.get::<&str, _>(0).unwrap_or_default().to_string())
+            .collect())
+    }
+
+    /// Full type and default constraint of each column. `ALTER COLUMN` needs the type
+    /// even when only the nullability changes, and defaults are constraints that have
+    /// to be dropped by name.
+    async fn column_definitions(
+        client: &mut MsSqlClient,
+        table_ident: &str,
+    ) -> Result<HashMap<String, (String, Option<String>)>, DBError> {
+        let rows = client
+            .query(
+                "SELECT c.name, TYPE_NAME(c.user_type_id), c.max_length, c.precision, c.scale, \
+                 dc.name \
+                 FROM sys.columns c \
+                 LEFT JOIN sys.default_constraints dc ON dc.object_id = c.default_object_id \
+                 WHERE c.object_id = OBJECT_ID(@P1)",
+                &[&table_ident],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let data_type = Self::type_sql(
+                    row.get::<&str, _>(1).unwrap_or_default(),
+                    row.get::<i16, _>(2).unwrap_or_default(),
+                    row.get::<u8, _>(3).unwrap_or_default(),
+                    row.get::<u8, _>(4).unwrap_or_default(),
+                );
+                let constraint = row.get::<&str, _>(5).map(String::from);
+                (
+                    row.get::<&str, _>(0).unwrap_or_default().to_string(),
+                    (data_type, constraint),
+                )
+            })
+            .collect())
+    }
+
+    /// Column as in `ALTER TABLE .. ADD`, with the nullability spelled out since its
+    /// default depends on session settings
+    fn column_definition(column: &ColumnDesign) -> String {
+        let mut definition = format!(
+            "{} {} {}",
+            Self::quote_ident(&column.name),
+            column.data_type.trim(),
+            if column.nullable { "NULL" } else { "NOT NULL" }
+        );
+        if let Some(default) = &column.default {
+            definition.push_str(&format!(" DEFAULT {}", default.trim()));
+        }
+        definition
+    }
+
+    /// `CREATE TABLE` rebuilt from the catalog with its keys and constraints, followed
+    /// by the other indexes and the triggers
+    async fn table_ddl(
+        client: &mut MsSqlClient,
+        schema: &str,
+        table: &str,
+    ) -> Result<String, DBError> {
+        let table_ident = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+        let columns = client
+            .query(
+                "SELECT c.name, TYPE_NAME(c.user_type_id), c.max_length, c.precision, c.scale, \
+                 c.is_nullable, cc.definition, dc.name, dc.definition, \
+                 CAST(ic.seed_value AS bigint), CAST(ic.increment_value AS bigint) \
+                 FROM sys.columns c \
+                 LEFT JOIN sys.computed_columns cc \
+                 ON cc.object_id = c.object_id AND cc.column_id = c.column_id \
+                 LEFT JOIN sys.default_constraints dc ON dc.object_id = c.default_object_id \
+                 LEFT JOIN sys.identity_columns ic \
+                 ON ic.object_id = c.object_id AND ic.column_id = c.column_id \
+                 WHERE c.object_id = OBJECT_ID(@P1) \
+                 ORDER BY c.column_id",
+                &[&table_ident],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+        if columns.is_empty() {
+            return Err(object_not_found(ObjectKind::Table, schema, table));
+        }
+
+        let mut definitions: Vec<String> = columns
+            .iter()
+            .map(|row| {
+                let name = Self::quote_ident(row.get::<&str, _>(0).unwrap_or_default());
+                // Computed columns have an expression instead of a type
+                if let Some(expression) = row.get::<&str, _>(6) {
+                    return format!("{} AS {}", name, expression);
+                }
+                let mut definition = format!(
+                    "{} {}",
+                    name,
+                    Self::type_sql(
+                        row.get::<&str, _>(1).unwrap_or_default(),
+                        row.get::<i16, _>(2).unwrap_or_default(),
+                        row.get::<u8, _>(3).unwrap_or_default(),
+                        row.get::<u8, _>(4).unwrap_or_default(),
+                    )
+                );
+                if let (Some(seed), Some(increment)) = (row.get::<i64, _>(9), row.get::<i64, _>(10))
+                {
+                    definition.push_str(&format!(" IDENTITY({}, {})", seed, increment));
+                }
+                if row.get::<bool, _>(5) == Some(false) {
+                    definition.push_str(" NOT NULL");
+                }
+                if let (Some(constraint), Some(default)) =
+                    (row.get::<&str, _>(7), row.get::<&str, _>(8))
+                {
+                    definition.push_str(&format!(
+                        " CONSTRAINT {} DEFAULT {}",
+                        Self::quote_ident(constraint),
+                        default
+                    ));
+                }
+                definition
+            })
+            .collect();
+
+        let rows = client
+            .query(
+                "SELECT i.name, i.is_primary_key, i.is_unique_constraint, i.is_unique, \
+                 i.type_desc, c.name, ic.is_descending_key, ic.is_included_column, \
+                 i.filter_definition \
+                 FROM sys.indexes i \
+                 JOIN sys.index_columns ic \
+                 ON ic.object_id = i.object_id AND ic.index_id = i.index_id \
+                 JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id \
+                 WHERE i.object_id = OBJECT_ID(@P1) \
+                 ORDER BY i.is_primary_key DESC, i.index_id, ic.is_included_column, \
+                 ic.key_ordinal, ic.index_column_id",
+                &[&table_ident],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+        // Key and included columns of each index, rows come ordered by index
+        let mut indexes: Vec<(&Row, Vec<String>, Vec<String>)> = Vec::new();
+        for row in &rows {
+            let column = Self::quote_ident(row.get::<&str, _>(5).unwrap_or_default());
+            if indexes
+                .last()
+                .is_none_or(|(first, _, _)| first.get::<&str, _>(0) != row.get::<&str, _>(0))
+            {
+                indexes.push((row, Vec::new(), Vec::new()));
+            }
+            if let Some((_, keys, included)) = indexes.last_mut() {
+                if row.get::<bool, _>(7) == Some(true) {
+                    included.push(column);
+                } else if row.get::<bool, _>(6) == Some(true) {
+                    keys.push(format!("{} DESC", column));
+                } else {
+                    keys.push(column);
+                }
+            }
+        }
+
+        let mut statements = Vec::new();
+        for (row, keys, included) in &indexes {
+            let name = Self::quote_ident(row.get::<&str, _>(0).unwrap_or_default());
+            let index_type = row.get::<&str, _>(4).unwrap_or_default();
+            if row.get::<bool, _>(1) == Some(true) {
+                definitions.push(format!(
+                    "CONSTRAINT {} PRIMARY KEY {} ({})",
+                    name,
+                    index_type,
+                    keys.join(", ")
+                ));
+            } else if row.get::<bool, _>(2) == Some(true) {
+                definitions.push(format!(
+                    "CONSTRAINT {} UNIQUE {} ({})",
+                    name,
+                    index_type,
+                    keys.join(", ")
+                ));
+            } else {
+                let mut statement = format!(
+                    "CREATE {}{} INDEX {} ON {} ({})",
+                    if row.get::<bool, _>(3) == Some(true) {
+                        "UNIQUE "
+                    } else {
+                        ""
+                    },
+                    index_type,
+                    name,
+                    table_ident,
+                    keys.join(", ")
+                );
+                if !included.is_empty() {
+                    statement.push_str(&format!(" INCLUDE ({})", included.join(", ")));
+                }
+                if let Some(filter) = row.get::<&str, _>(8) {
+                    statement.push_str(&format!(" WHERE {}", filter));
+                }
+                statements.push(statement);
+            }
+        }
+
+        let quote_all = |columns: &[String]| {
+            columns
+                .iter()
+                .map(|c| Self::quote_ident(c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        for key in Self::table_foreign_keys(client, &table_ident).await? {
+            definitions.push(format!(
+                "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {}.{} ({}) ON UPDATE {} ON DELETE {}",
+                Self::quote_ident(&key.name),
+                quote_all(&key.columns),
+                Self::quote_ident(&key.referenced_schema),
+                Self::quote_ident(&key.referenced_table),
+                quote_all(&key.referenced_columns),
+                key.on_update,
+                key.on_delete
+            ));
+        }
+        for check in Self::table_checks(client, &table_ident).await? {
+            definitions.push(format!(
+                "CONSTRAINT {} CHECK {}",
+                Self::quote_ident(&check.name),
+                check.expression
+            ));
+        }
+        let triggers = Self::table_triggers(client, &table_ident).await?;
+
+        Ok(join_statements(
+            std::iter::once(create_table(&table_ident, &definitions))
+                .chain(statements)
+                .chain(triggers.into_iter().map(|trigger| trigger.statement)),
+        ))
+    }
+
+    /// Expression selecting a column, CLR types have no TDS representation and are
+    /// read through their text form
+    fn select_expr(column: &str, data_type: &str) -> String {
+        let ident = Self::quote_ident(column);
+        match data_type {
+            "geography" | "geometry" | "hierarchyid" => format!("{}.ToString()", ident),
+            "sql_variant" => format!("CONVERT(NVARCHAR(MAX), {})", ident),
+            _ => ident,
+        }
+    }
+
+    /// Kind of a column declared as `data_type` in `INFORMATION_SCHEMA`
+    fn type_kind(data_type: &str) -> ColumnKind {
+        match data_type {
+            "bit" => ColumnKind::Bool,
+            // Unlike in MySQL and PostgreSQL, `timestamp` is a row version
+            "timestamp" | "rowversion" => ColumnKind::Bytes,
+            _ => ColumnKind::from_type_name(data_type),
+        }
+    }
+
+    fn column_kind(column_type: ColumnType) -> ColumnKind {
+        match column_type {
+            ColumnType::Int1
+            | ColumnType::Int2
+            | ColumnType::Int4
+            | ColumnType::Int8
+            | ColumnType::Intn => ColumnKind::Integer,
+            ColumnType::Float4 | ColumnType::Float8 | ColumnType::Floatn => ColumnKind::Float,
+            ColumnType::Money
+            | ColumnType::Money4
+            | ColumnType::Decimaln
+            | ColumnType::Numericn => ColumnKind::Decimal,
+            ColumnType::Bit | ColumnType::Bitn => ColumnKind::Bool,
+            ColumnType::BigVarBin | ColumnType::BigBinary | ColumnType::Image => ColumnKind::Bytes,
+            ColumnType::Daten => ColumnKind::Date,
+            ColumnType::Timen => ColumnKind::Time,
+            ColumnType::Datetime
+            | ColumnType::Datetime4
+            | ColumnType::Datetimen
+            | ColumnType::Datetime2
+            | ColumnType::DatetimeOffsetn => ColumnKind::DateTime,
+            ColumnType::Guid => ColumnKind::Uuid,
+            _ => ColumnKind::Text,
+        }
+    }
+
+    fn cell_value(data: &ColumnData<'static>) -> CellValue {
+        let value = match data {
+            ColumnData::U8(v) => v.map(|n| CellValue::Int(n.into())),
+            ColumnData::I16(v) => v.map(|n| CellValue::Int(n.into())),
+            ColumnData::I32(v) => v.map(|n| CellValue::Int(n.into())),
+            ColumnData::I64(v) => v.map(CellValue::Int),
+            ColumnData::F32(v) => v.map(CellValue::from),
+            ColumnData::F64(v) => v.map(CellValue::Float),
+            ColumnData::Bit(v) => v.map(CellValue::Bool),
+            ColumnData::String(v) => v.as_ref().map(|s| CellValue::Text(s.to_string())),
+            ColumnData::Guid(v) => v.map(CellValue::Uuid),
+            ColumnData::Numeric(v) => v.map(|n| CellValue::Decimal(n.to_string())),
+            ColumnData::Xml(v) => v.as_ref().map(|x| CellValue::Text(x.to_string())),
+            ColumnData::Binary(v) => v.as_ref().map(|bytes| CellValue::Bytes(bytes.to_vec())),
+            ColumnData::Date(_) => NaiveDate::from_sql(data)
+                .ok()
+                .flatten()
+                .map(CellValue::Date),
+            ColumnData::Time(_) => NaiveTime::from_sql(data)
+                .ok()
+                .flatten()
+                .map(CellValue::Time),
+            ColumnData::DateTimeOffset(_) => DateTime::<FixedOffset>::from_sql(data)
+                .ok()
+                .flatten()
+                .map(CellValue::DateTimeTz),
+            ColumnData::DateTime(_) | ColumnData::SmallDateTime(_) | ColumnData::DateTime2(_) => {
+                NaiveDateTime::from_sql(data)
+                    .ok()
+                    .flatten()
+                    .map(CellValue::DateTime)
+            }
+        };
+        value.unwrap_or(CellValue::Null)
+    }
+
+    /// Run statements that each have to change exactly one row, inside a transaction
+    /// the caller opened
+    async fn run_changes(
+        client: &mut MsSqlClient,
+        statements: &[BoundStatement],
+    ) -> Result<u64, DBError> {
+        let mut total = 0;
+        for (idx, statement) in statements.iter().enumerate() {
+            let params: Vec<&dyn ToSql> =
+                statement.params.iter().map(|p| p as &dyn ToSql).collect();
+            let changed = client.execute(&statement.sql, &params).await?.total();
+            if changed != 1 {
+                return Err(stale_row_error(idx, changed));
+            }
+            total += changed;
+        }
+        Ok(total)
+    }
+
+    /// Row count and identity of the previous batch, `EXECUTE` keeps both intact
+    async fn last_summary(
+        client: &mut MsSqlClient,
+        statement: &str,
+    ) -> Result<ExecSummary, DBError> {
+        let row = client
+            .simple_query("SELECT CAST(@@ROWCOUNT AS BIGINT), CAST(@@IDENTITY AS BIGINT)")
+            .await?
+            .into_row()
+            .await?;
+        let rows_affected = row.as_ref().and_then(|r| r.get::<i64, _>(0)).unwrap_or(0);
+        // @@IDENTITY sticks to the session, only report it for inserts
+        let last_insert_id = row
+            .as_ref()
+            .and_then(|r| r.get::<i64, _>(1))
+            .filter(|_| first_keyword(statement) == "INSERT");
+        Ok(ExecSummary {
+            rows_affected: rows_affected as u64,
+            last_insert_id: last_insert_id.map(|id| id as u64),
+            warnings: Vec::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl DatabaseDriver for MsSqlDriver {
+    fn name(&self) -> &'static str {
+        "SQL Server"
+    }
+
+    async fn connect(&mut self) -> Result<(), DBError> {
+        let client = Self::open_client(&self.uri).await?;
+        self.client = Some(Mutex::new(client));
+        Ok(())
+    }
+
+    async fn test_connection(&self) -> Result<(), DBError> {
+        let mut client = Self::open_client(&self.uri).await?;
+        let result = match client.simple_query("SELECT 1").await {
+            Ok(stream) => stream.into_results().await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(_) => {
+                client.close().await?;
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("Test Connection Failed, Reason: {:?}", e);
+                Err(Self::map_connect_error(e))
+            }
+        }
+    }
+
+    fn as_schema_browser(&self) -> Option<&dyn SchemaBrowser> {
+        Some(self)
+    }
+
+    fn as_sql_executor(&self) -> Option<&dyn SqlExecutor> {
+        Some(self)
+    }
+
+    fn as_table_editor(&self) -> Option<&dyn TableEditor> {
+        (!self.read_only).then_some(self as &dyn TableEditor)
+    }
+
+    fn as_table_designer(&self) -> Option<&dyn TableDesigner> {
+        (!self.read_only).then_some(self as &dyn TableDesigner)
+    }
+
+    fn as_transactional(&self) -> Option<&dyn Transactional> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl SqlExecutor for MsSqlDriver {
+    async fn execute(
+        &self,
+        sql: &str,
+        params: &[QueryParam],
+    ) -> Result<Vec<StatementResult>, DBError> {
+        let statements = script_statements(
+            sql,
+            params,
+            DatabaseType::MicrosoftSQLServer,
+            self.read_only,
+        )?;
+        let params: Vec<&dyn ToSql> = params.iter().map(|p| p as &dyn ToSql).collect();
+        let mut client = self.client()?.lock().await;
+        let mut results = Vec::new();
+
+        for statement in statements {
+            let mut sets: Vec<ResultSet> = Vec::new();
+            {
+                // A plain batch keeps `USE` and friends in effect for the session
+                let mut stream = if params.is_empty() {
+                    client.simple_query(statement).await?
+                } else {
+                    client.query(statement, &params).await?
+                };
+                while let Some(item) = stream.try_next().await? {
+                    match item {
+                        QueryItem::Metadata(meta) => sets.push(ResultSet {
+                            columns: meta
+                                .columns()
+                                .iter()
+                                .map(|column| ResultColumn {
+                                    name: column.name().to_string(),
+                                    data_type: format!("{:?}", column.column_type()),
+                                    kind: Self::column_kind(column.column_type()),
+                                })
+                                .collect(),
+                            rows: Vec::new(),
+                        }),
+                        QueryItem::Row(row) => {
+                            if let Some(set) = sets.last_mut() {
+                                set.rows.push(
+                                    row.cells()
+                                        .map(|(_, data)| Self::cell_value(data))
+                                        .collect(),
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+
+            let outcomes = if sets.is_empty() {
+                vec![QueryResult::Affected(
+                    Self::last_summary(&mut client, statement).await?,
+                )]
+            } else {
+                sets.into_iter().map(QueryResult::Rows).collect()
+            };
+            results.extend(outcomes.into_iter().map(|result| StatementResult {
+                statement: statement.to_string(),
+                result,
+            }));
+        }
+        Ok(results)
+    }
+}
+
+impl ToSql for QueryParam {
+    fn to_sql(&self) -> ColumnData<'_> {
+        match self {
+            QueryParam::Null => ColumnData::String(None),
+            QueryParam::Bool(b) => ColumnData::Bit(Some(*b)),
+            QueryParam::Int(n) => ColumnData::I64(Some(*n)),
+            QueryParam::Float(f) => ColumnData::F64(Some(*f)),
+            QueryParam::Text(s) => ColumnData::String(Some(Cow::Borrowed(s))),
+            QueryParam::Bytes(bytes) => ColumnData::Binary(Some(Cow::Borrowed(bytes))),
+        }
+    }
+}
+
+#[async_trait]
+impl SchemaBrowser for MsSqlDriver {
+    async fn list_schemas(&self) -> Result<Vec<DBSchema>, DBError> {
+        let mut client = self.client()?.lock().await;
+        let rows = client
+            .query(
+                "SELECT name FROM sys.schemas WHERE schema_id < @P1 ORDER BY name",
+                &[&FIRST_ROLE_SCHEMA_ID],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| row.get::<&str, _>(0))
+            .map(|name| DBSchema {
+                name: name.to_string(),
+            })
+            .collect())
+    }
+
+    async fn list_tables(&self, schema: &str) -> Result<Vec<TableInfo>, DBError> {
+        let mut client = self.client()?.lock().await;
+        let rows = client
+            .query(
+                "SELECT o.name, CASE o.type WHEN 'V' THEN 'VIEW' ELSE 'BASE TABLE' END \
+                 FROM sys.objects o \
+                 JOIN sys.schemas s ON s.schema_id = o.schema_id \
+                 WHERE s.name = @P1 AND o.type IN ('U', 'V') AND o.is_ms_shipped = 0 \
+                 ORDER BY o.name",
+                &[&schema],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| TableInfo {
+                name: row.get::<&str, _>(0).unwrap_or_default().to_string(),
+                table_type: row.get::<&str, _>(1).unwrap_or_default().to_string(),
+            })
+            .collect())
+    }
+
+    async fn list_procedures(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        self.object_names(schema, "'P'").await
+    }
+
+    async fn list_functions(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        self.object_names(schema, "'FN', 'IF', 'TF'").await
+    }
+
+    /// DML triggers of the tables and views, database triggers belong to no schema
+    async fn list_triggers(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        self.object_names(schema, "'TR'").await
+    }
+
+    async fn get_table_columns(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableColumn>, DBError> {
+        let mut client = self.client()?.lock().await;
+        let rows = client
+            .query(
+                "SELECT c.COLUMN_NAME, c.DATA_TYPE, c.IS_NULLABLE, c.COLUMN_DEFAULT, \
+                 k.ORDINAL_POSITION \
+                 FROM INFORMATION_SCHEMA.COLUMNS c \
+                 LEFT JOIN INFORMATION_SCHEMA.TABLE_CONSTRAINTS t \
+                 ON t.TABLE_SCHEMA = c.TABLE_SCHEMA AND t.TABLE_NAME = c.TABLE_NAME \
+                 AND t.CONSTRAINT_TYPE = 'PRIMARY KEY' \
+                 LEFT JOIN INFORMATION_SCHEMA.KEY_COLUMN_USAGE k \
+                 ON k.CONSTRAINT_SCHEMA = t.CONSTRAINT_SCHEMA \
+                 AND k.CONSTRAINT_NAME = t.CONSTRAINT_NAME AND k.COLUMN_NAME = c.COLUMN_NAME \
+                 WHERE c.TABLE_SCHEMA = @P1 AND c.TABLE_NAME = @P2 \
+                 ORDER BY c.ORDINAL_POSITION",
+                &[&schema, &table],
+            )
+            .await?
+            .into_first_result()
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| TableColumn {
+                name: row.get::<&str, _>(0).unwrap_or_default().to_string(),
+                data_type: row.get::<&str, _>(1).unwrap_or_default().to_string(),
+                nullable: row.get::<&str, _>(2) == Some("YES"),
+                default: row.get::<&str, _>(3).map(String::from),
+                primary_key: row.get::<i32, _>(4).map(|position| position as u32),
+            })
+            .collect())
+    }
+
+    async fn fetch_table_data(
+        &self,
+        schema: &str,
+        table: &str,
+        query: &TableQuery,
+    ) -> Result<TableDataPage, DBError> {
+        // 1. Get columns
+        let columns = self.get_table_columns(schema, table).await?;
+        let primary_key = TableColumn::primary_key(&columns);
+        let table_ident = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+
+        // 2. Get rows, OFFSET ... FETCH requires an ORDER BY clause
+        let select_cols = columns
+            .iter()
+            .map(|c| {
+                format!(
+                    "{} AS {}",
+                    Self::select_expr(&c.name, &c.data_type),
+                    Self::quote_ident(&c.name)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut compiled = CompiledQuery::new(query, &columns, DatabaseType::MicrosoftSQLServer)?;
+        let order_sql = if compiled.order_sql.is_empty() {
+            " ORDER BY (SELECT NULL)".to_string()
+        } else {
+            compiled.order_sql.clone()
+        };
+        let offset = compiled.bind(QueryParam::Int(compiled.offset as i64));
+        let limit = compiled.bind(QueryParam::Int(query.limit as i64));
+        let sql = format!(
+            "SELECT {} FROM {}{}{} OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+            select_cols, table_ident, compiled.rows_where_sql, order_sql, offset, limit
+        );
+
+        let params: Vec<&dyn ToSql> = compiled.params.iter().map(|p| p as &dyn ToSql).collect();
+        let mut client = self.client()?.lock().await;
+        let unique_key = if primary_key.is_empty() {
+            let indexes = Self::table_indexes(&mut client, &table_ident).await?;
+            TableIndex::unique_key(&indexes, &columns)
+        } else {
+            Vec::new()
+        };
+        let rows = client
+            .query(sql, &params)
+            .await?
+            .into_first_result()
+            .await?;
+        let data: Vec<Vec<CellValue>> = rows
+            .iter()
+            .map(|row| {
+                row.cells()
+                    .map(|(_, data)| Self::cell_value(data))
+                    .collect()
+            })
+            .collect();
+
+        // 3. Count the matching rows, or take the estimate of the table statistics.
+        // An estimate below the rows just read is stale.
+        let estimate = if query.estimate_total() {
+            Self::estimate_rows(&mut client, &table_ident)
+                .await?
+                .filter(|n| *n >= compiled.offset + data.len() as u64)
+        } else {
+            None
+        };
+        let total = match estimate {
+            Some(estimate) => estimate,
+            None => {
+                let count_sql = format!(
+                    "SELECT COUNT_BIG(*) FROM {}{}",
+                    table_ident, compiled.where_sql
+                );
+                let params: Vec<&dyn ToSql> = compiled
+                    .count_params()
+                    .iter()
+                    .map(|p| p as &dyn ToSql)
+                    .collect();
+                client
+                    .query(count_sql, &params)
+                    .await?
+                    .into_row()
+                    .await?
+                    .and_then(|row| row.get::<i64, _>(0))
+                    .unwrap_or(0) as u64
+            }
+        };
+
+        Ok(TableDataPage {
+            columns: columns
+                .into_iter()
+                .map(|c| ResultColumn {
+                    kind: Self::type_kind(&c.data_type),
+                    name: c.name,
+                    data_type: c.data_type,
+                })
+                .collect(),
+            rows: data,
+            total,
+            total_estimated: estimate.is_some(),
+            primary_key,
+            unique_key,
+        })
+    }
+
+    async fn get_table_structure(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<TableStructure, DBError> {
+        let columns = self.get_table_columns(schema, table).await?;
+        let table_ident = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+        let mut client = self.client()?.lock().await;
+
+        Ok(TableStructure {
+            columns: Self::column_details(&mut client, &table_ident, columns).await?,
+            indexes: Self::table_indexes(&mut client, &table_ident).await?,
+            foreign_keys: Self::table_foreign_keys(&mut client, &table_ident).await?,
+            checks: Self::table_checks(&mut client, &table_ident).await?,
+            triggers: Self::table_triggers(&mut client, &table_ident).await?,
+            options: Self::table_options(&mut client, &table_ident).await?,
+        })
+    }
+
+    /// Views, routines and triggers keep their source, tables are rebuilt from the catalog
+    async fn get_object_ddl(
+        &self,
+        schema: &str,
+        name: &str,
+        kind: ObjectKind,
+    ) -> Result<String, DBError> {
+        let mut client = self.client()?.lock().await;
+        let types = match kind {
+            ObjectKind::Table => return Self::table_ddl(&mut client, schema, name).await,
+            ObjectKind::View => "'V'",
+            ObjectKind::Procedure => "'P'",
+            ObjectKind::Function => "'FN', 'IF', 'TF'",
+            ObjectKind::Trigger => "'TR'",
+            ObjectKind::Event => {
+                return Err(DBError::QueryError(
+                    "SQL Server has no scheduled events, they are SQL Server Agent jobs"
+                        .to_string(),
+                ))
+            }
+            ObjectKind::Sequence => {
+                return Err(DBError::QueryError(
+                    "Sequences are not listed for SQL Server".to_string(),
+                ))
+            }
+        };
+        // The source is NULL for encrypted modules and without VIEW DEFINITION
+        let sql = format!(
+            "SELECT OBJECT_DEFINITION(o.object_id) FROM sys.objects o \
+             JOIN sys.schemas s ON s.schema_id = o.schema_id \
+             WHERE s.name = @P1 AND o.name = @P2 AND o.type IN ({})",
+            types
+        );
+        let row = client
+            .query(sql, &[&schema, &name])
+            .await?
+            .into_row()
+            .await?;
+        row.as_ref()
+            .and_then(|row| row.get::<&str, _>(0))
+            .map(|ddl| join_statements([ddl.to_string()]))
+            .ok_or_else(|| object_not_found(kind, schema, name))
+    }
+}
+
+#[async_trait]
+impl TableEditor for MsSqlDriver {
+    async fn preview_changes(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+    ) -> Result<Vec<BoundStatement>, DBError> {
+        let table_ident = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+        change_statements(
+            changes,
+            &table_ident,
+            DatabaseType::MicrosoftSQLServer,
+            &HashMap::new(),
+        )
+    }
+
+    async fn apply_changes(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+    ) -> Result<u64, DBError> {
+        let statements = self.preview_changes(schema, table, changes).await?;
+        let mut client = self.client()?.lock().await;
+        if self.transaction_open.load(Ordering::SeqCst) {
+            // The changes join the open transaction, a savepoint takes back just them
+            client.execute("SAVE TRANSACTION table_edit", &[]).await?;
+            return match Self::run_changes(&mut client, &statements).await {
+                Ok(total) => Ok(total),
+                Err(e) => {
+                    client
+                        .execute("IF XACT_STATE() = 1 ROLLBACK TRANSACTION table_edit", &[])
+                        .await?;
+                    Err(e)
+                }
+            };
+        }
+        client.execute("BEGIN TRANSACTION", &[]).await?;
+        match Self::run_changes(&mut client, &statements).await {
+            Ok(total) => {
+                client.execute("COMMIT TRANSACTION", &[]).await?;
+                Ok(total)
+            }
+            Err(e) => {
+                // A failed statement may already have ended the transaction
+                client
+                    .execute("IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION", &[])
+                    .await?;
+                Err(e)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TableDesigner for MsSqlDriver {
+    async fn preview_design(
+        &self,
+        schema: &str,
+        table: &str,
+        design: &TableDesign,
+    ) -> Result<Vec<AlterStatement>, DBError> {
+        let table_ident = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+        let current = self.get_table_columns(schema, table).await?;
+        let definitions = {
+            let mut client = self.client()?.lock().await;
+            Self::column_definitions(&mut client, &table_ident).await?
+        };
+        let alter =
+            |action: String| AlterStatement::new(format!("ALTER TABLE {} {}", table_ident, action));
+        let drop_default = |column: &str| {
+            definitions
+                .get(column)
+                .and_then(|(_, constraint)| constraint.as_ref())
+                .map(|constraint| {
+                    alter(format!("DROP CONSTRAINT {}", Self::quote_ident(constraint)))
+                })
+        };
+
+        let mut statements = Vec::new();
+        for change in column_changes(&current, design)? {
+            match change {
+                ColumnChange::Drop(column) => {
+                    statements.extend(drop_default(&column.name));
+                    statements.push(alter(format!(
+                        "DROP COLUMN {}",
+                        Self::quote_ident(&column.name)
+                    )));
+                }
+                ColumnChange::Rename { from, to } => {
+                    let column = format!("{}.{}", table_ident, Self::quote_ident(&from));
+                    statements.push(AlterStatement::new(format!(
+                        "EXEC sp_rename N{}, N{}, 'COLUMN'",
+                        QueryParam::Text(column),
+                        QueryParam::Text(to.to_string())
+                    )));
+                }
+                ColumnChange::Alter {
+                    column,
+                    current,
+                    data_type,
+                    nullable,
+                    default,
+                } => {
+                    let name = Self::quote_ident(&column.name);
+                    if data_type || nullable {
+                        let full_type = match definitions.get(&current.name) {
+                            Some((full_type, _)) if !data_type => full_type.as_str(),
+                            _ => column.data_type.trim(),
+                        };
+                        statements.push(alter(format!(
+                            "ALTER COLUMN {} {} {}",
+                            name,
+                            full_type,
+                            if column.nullable { "NULL" } else { "NOT NULL" }
+                        )));
+                    }
+                    if default {
+                        statements.extend(drop_default(&current.name));
+                        if let Some(default) = &column.default {
+                            statements.push(alter(format!(
+                                "ADD DEFAULT {} FOR {}",
+                                default.trim(),
+                                name
+                            )));
+                        }
+                    }
+                }
+                ColumnChange::Add(column) => {
+                    statements.push(alter(format!("ADD {}", Self::column_definition(column))));
+                }
+            }
+        }
+        for index in &design.indexes {
+            statements.push(AlterStatement::new(create_index(
+                index,
+                &Self::quote_ident(&index.name),
+                &table_ident,
+                DatabaseType::MicrosoftSQLServer,
+            )));
+        }
+        for key in &design.foreign_keys {
+            statements.push(AlterStatement::new(add_foreign_key(
+                key,
+                schema,
+                &table_ident,
+                DatabaseType::MicrosoftSQLServer,
+            )));
+        }
+        Ok(statements)
+    }
+
+    async fn apply_design(
+        &self,
+        schema: &str,
+        table: &str,
+        design: &TableDesign,
+    ) -> Result<(), DBError> {
+        if self.transaction_open.load(Ordering::SeqCst) {
+            return Err(transaction_open_error());
+        }
+        let statements = self.preview_design(schema, table, design).await?;
+        let mut client = self.client()?.lock().await;
+        client.execute("BEGIN TRANSACTION", &[]).await?;
+        for statement in &statements {
+            if let Err(e) = client.execute(statement.sql.as_str(), &[]).await {
+                // A failed statement may already have ended the transaction
+                client
+                    .execute("IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION", &[])
+                    .await?;
+                return Err(e.into());
+            }
+        }
+        client.execute("COMMIT TRANSACTION", &[]).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Transactional for MsSqlDriver {
+    async fn begin(&self) -> Result<(), DBError> {
+        let mut client = self.client()?.lock().await;
+        if self.transaction_open.load(Ordering::SeqCst) {
+            return Err(DBError::TransactionError(
+                "A transaction is already open".to_string(),
+            ));
+        }
+        client.execute("BEGIN TRANSACTION", &[]).await?;
+        self.transaction_open.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn commit(&self) -> Result<(), DBError> {
+        let mut client = self.client()?.lock().await;
+        if !self.transaction_open.swap(false, Ordering::SeqCst) {
+            return Err(DBError::TransactionError(
+                "No transaction is open".to_string(),
+            ));
+        }
+        client.execute("COMMIT TRANSACTION", &[]).await?;
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<(), DBError> {
+        let mut client = self.client()?.lock().await;
+        if !self.transaction_open.swap(false, Ordering::SeqCst) {
+            return Err(DBError::TransactionError(
+                "No transaction is open".to_string(),
+            ));
+        }
+        // An error may have rolled the transaction back already
+        client
+            .execute("IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION", &[])
+            .await?;
+        Ok(())
+    }
+
+    async fn in_transaction(&self) -> bool {
+        self.transaction_open.load(Ordering::SeqCst)
+    }
+}
@@ -2,23 +2,82 @@ use async_trait::async_trait;
 use sqlx::{
     decode::Decode,
     error::Error as SqlxError,
-    mysql::{MySqlPoolOptions, MySqlValueRef},
-    types::{chrono::NaiveDateTime, JsonValue},
-    MySql, MySqlPool, Row, ValueRef,
+    mysql::{types::MySqlTime, MySqlConnection, MySqlPoolOptions, MySqlRow, MySqlValueRef},
+    types::{
+        chrono::{NaiveDate, NaiveDateTime, NaiveTime},
+        JsonValue,
+    },
+    Column, Connection, Executor, MySql, MySqlPool, Row, TypeInfo, ValueRef,
 };
-use std::{borrow::Cow, time::Duration};
+use std::{borrow::Cow, collections::HashMap, time::Duration};
 
 use crate::{
-    driver::{DBError, DatabaseDriver},
+    database::DatabaseType,
+    driver::{
+        ddl::object_not_found,
+        script::{
+            bind_params, changes_session, collect_results, is_empty_summary, restore_empty_result,
+            script_statements,
+        },
+        table_design::{
+            add_foreign_key, column_changes, column_definition, create_index, run_migration,
+            transaction_open_error, ColumnChange,
+        },
+        table_edit::{apply_statements, change_statements},
+        table_query::{quote_ident, CompiledQuery},
+        transaction::PinnedTransaction,
+        CancelHandle, DBError, DatabaseDriver, QueryCanceller, SchemaBrowser, SqlExecutor,
+        TableDesigner, TableEditor, Transactional,
+    },
     model::{
-        schema::DBSchema,
-        table::{TableColumn, TableDataPage, TableInfo},
+        design::{AlterStatement, TableDesign},
+        query::{
+            BoundStatement, ExecSummary, QueryParam, QueryResult, ResultColumn, StatementResult,
+        },
+        schema::{DBSchema, ObjectKind},
+        structure::{
+            CheckConstraint, ColumnDetail, ForeignKey, IndexColumn, TableIndex, TableOptions,
+            TableStructure, TableTrigger,
+        },
+        table::{RowChange, TableColumn, TableDataPage, TableInfo, TableQuery},
+        value::CellValue,
     },
 };
 
+/// What `MODIFY COLUMN` has to repeat to keep a column as it is, besides its type,
+/// nullability and default
+struct ColumnAttributes {
+    /// Current default as an SQL expression
+    default: Option<String>,
+    collation: Option<String>,
+    /// `AUTO_INCREMENT` or `ON UPDATE ...`
+    extra: Option<String>,
+    comment: Option<String>,
+}
+
+/// Server flavor speaking the MySQL protocol, detected from `VERSION()` on connect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MySqlFlavor {
+    MySql,
+    MariaDB,
+}
+
+impl MySqlFlavor {
+    fn from_version(version: &str) -> Self {
+        if version.to_lowercase().contains("mariadb") {
+            MySqlFlavor::MariaDB
+        } else {
+            MySqlFlavor::MySql
+        }
+    }
+}
+
 pub struct MySqlDriver {
     pub uri: String,
     pub pool: Option<MySqlPool>,
+    pub flavor: MySqlFlavor,
+    pub read_only: bool,
+    transaction: PinnedTransaction<MySql>,
 }
 
 impl MySqlDriver {
@@ -26,7 +85,84 @@ impl MySqlDriver {
         Self {
             uri: uri.into(),
             pool: None,
+            flavor: MySqlFlavor::MySql,
+            read_only: false,
+            transaction: PinnedTransaction::new(),
+        }
+    }
+
+    /// Refuse statements that write, and drop the editing capabilities
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Run a script for [`SqlExecutor::execute`], with a `handle` it can be stopped by
+    /// [`QueryCanceller::cancel`]
+    async fn run_script(
+        &self,
+        sql: &str,
+        params: &[QueryParam],
+        handle: Option<&CancelHandle>,
+    ) -> Result<Vec<StatementResult>, DBError> {
+        let statements = script_statements(sql, params, DatabaseType::MySql, self.read_only)?;
+        // Run the whole script on one connection, so session state like `USE` carries over.
+        // Inside a transaction that is the connection pinned for it.
+        let mut conn = self.transaction.session(self.pool()?).await?;
+        if changes_session(&statements, DatabaseType::MySql) {
+            conn.close_on_drop();
+        }
+        let Some(handle) = handle else {
+            return Self::run_statements(&mut conn, &statements, params, None).await;
+        };
+        let session: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()")
+            .fetch_one(&mut *conn)
+            .await?;
+        handle.start(session as i64)?;
+        let result = Self::run_statements(&mut conn, &statements, params, Some(handle)).await;
+        handle.finish(result)
+    }
+
+    async fn run_statements(
+        conn: &mut MySqlConnection,
+        statements: &[&str],
+        params: &[QueryParam],
+        handle: Option<&CancelHandle>,
+    ) -> Result<Vec<StatementResult>, DBError> {
+        let mut results = Vec::new();
+
+        for &statement in statements {
+            if let Some(handle) = handle {
+                handle.check()?;
+            }
+            let stream = if params.is_empty() {
+                // Text protocol, not every statement can be prepared
+                conn.fetch_many(statement)
+            } else {
+                conn.fetch_many(bind_params(sqlx::query(statement), params))
+            };
+            let mut outcomes = collect_results(stream, Self::format_row, |done| ExecSummary {
+                rows_affected: done.rows_affected(),
+                last_insert_id: Some(done.last_insert_id()).filter(|id| *id > 0),
+                warnings: Vec::new(),
+            })
+            .await?;
+
+            if let Some(QueryResult::Affected(summary)) = outcomes.last_mut() {
+                summary.warnings = Self::show_warnings(conn).await?;
+            }
+            if is_empty_summary(&outcomes) {
+                if let Ok(describe) = conn.describe(statement).await {
+                    restore_empty_result(&mut outcomes, describe);
+                }
+            }
+
+            results.extend(outcomes.into_iter().map(|result| StatementResult {
+                statement: statement.to_string(),
+                result,
+            }));
         }
+        Ok(results)
     }
 
     fn pool(&self) -> Result<&MySqlPool, DBError> {
@@ -35,77 +171,482 @@ impl MySqlDriver {
             .ok_or(DBError::ConnectionError("Not connected".to_string()))
     }
 
-    fn format_mysql_value(v: MySqlValueRef<'_>) -> String {
+    fn mysql_value(v: MySqlValueRef<'_>, is_json: bool) -> CellValue {
         if v.is_null() {
-            return "NULL".to_string();
+            return CellValue::Null;
         }
 
-        // Try to decode as date/time types first
-        if let Ok(dt) = <NaiveDateTime as Decode<MySql>>::decode(v.clone()) {
-            return dt.format("%Y-%m-%d %H:%M:%S").to_string();
+        // JSON columns (including MariaDB's LONGTEXT alias) are normalized as JSON documents
+        let type_name = v.type_info().name().to_string();
+        if is_json || type_name == "JSON" {
+            if let Some(j) = Self::decode_json_text(v.clone()) {
+                return CellValue::Json(j);
+            }
         }
 
-        // Try to decode as date type
-        if let Ok(date) = <chrono::NaiveDate as Decode<MySql>>::decode(v.clone()) {
-            return date.format("%Y-%m-%d").to_string();
-        }
+        // Decode by the column type, both the text and the binary protocol are handled
+        let value = match type_name.as_str() {
+            "BOOLEAN" => <bool as Decode<MySql>>::decode(v.clone()).map(CellValue::Bool),
+            "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => {
+                <i64 as Decode<MySql>>::decode(v.clone()).map(CellValue::Int)
+            }
+            "BIT" | "YEAR" => <u64 as Decode<MySql>>::decode(v.clone()).map(CellValue::UInt),
+            name if name.ends_with("UNSIGNED") => {
+                <u64 as Decode<MySql>>::decode(v.clone()).map(CellValue::UInt)
+            }
+            "FLOAT" => <f32 as Decode<MySql>>::decode(v.clone()).map(CellValue::from),
+            "DOUBLE" => <f64 as Decode<MySql>>::decode(v.clone()).map(CellValue::Float),
+            // DECIMAL is sent as a string by both protocols
+            "DECIMAL" => <&str as Decode<MySql>>::decode(v.clone())
+                .map(|s| CellValue::Decimal(s.to_string())),
+            "DATE" => <NaiveDate as Decode<MySql>>::decode(v.clone()).map(CellValue::Date),
+            // TIME is an interval that may be negative or exceed a day
+            "TIME" => <NaiveTime as Decode<MySql>>::decode(v.clone())
+                .map(CellValue::Time)
+                .or_else(|_| {
+                    <MySqlTime as Decode<MySql>>::decode(v.clone())
+                        .map(|t| CellValue::Text(t.to_string()))
+                }),
+            "DATETIME" | "TIMESTAMP" => {
+                <NaiveDateTime as Decode<MySql>>::decode(v.clone()).map(CellValue::DateTime)
+            }
+            "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB"
+            | "GEOMETRY" => <Vec<u8> as Decode<MySql>>::decode(v.clone()).map(CellValue::Bytes),
+            _ => <String as Decode<MySql>>::decode(v.clone()).map(CellValue::Text),
+        };
 
-        // Try to decode as time type
-        if let Ok(time) = <chrono::NaiveTime as Decode<MySql>>::decode(v.clone()) {
-            return time.format("%H:%M:%S").to_string();
-        }
+        // Keep whatever the server sent when the value doesn't decode as its type
+        value.unwrap_or_else(|_| match <Vec<u8> as Decode<MySql>>::decode(v) {
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(s) => CellValue::Text(s),
+                Err(e) => CellValue::Bytes(e.into_bytes()),
+            },
+            Err(_) => CellValue::Text("<err>".to_string()),
+        })
+    }
 
-        // Try integer types before string to avoid empty string issues
-        if let Ok(n) = <i64 as Decode<MySql>>::decode(v.clone()) {
-            return n.to_string();
-        }
-        if let Ok(n) = <i32 as Decode<MySql>>::decode(v.clone()) {
-            return n.to_string();
-        }
-        if let Ok(n) = <u64 as Decode<MySql>>::decode(v.clone()) {
-            return n.to_string();
-        }
-        if let Ok(n) = <u32 as Decode<MySql>>::decode(v.clone()) {
-            return n.to_string();
-        }
+    fn format_row(row: &MySqlRow) -> Vec<CellValue> {
+        row.columns()
+            .iter()
+            .map(|column| match row.try_get_raw(column.ordinal()) {
+                Ok(v) => Self::mysql_value(v, false),
+                Err(_) => CellValue::Text("<err>".to_string()),
+            })
+            .collect()
+    }
 
-        // Try floating point types
-        if let Ok(f) = <f64 as Decode<MySql>>::decode(v.clone()) {
-            return f.to_string();
-        }
-        if let Ok(f) = <f32 as Decode<MySql>>::decode(v.clone()) {
-            return f.to_string();
-        }
+    /// Warnings raised by the last statement run on this connection
+    async fn show_warnings(conn: &mut MySqlConnection) -> Result<Vec<String>, DBError> {
+        let rows = conn.fetch_all("SHOW WARNINGS").await?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "{} {}: {}",
+                    row.try_get::<String, _>("Level").unwrap_or_default(),
+                    row.try_get::<u32, _>("Code").unwrap_or_default(),
+                    row.try_get::<String, _>("Message").unwrap_or_default()
+                )
+            })
+            .collect())
+    }
 
-        // Try boolean
-        if let Ok(b) = <bool as Decode<MySql>>::decode(v.clone()) {
-            return b.to_string();
-        }
+    fn decode_json_text(v: MySqlValueRef<'_>) -> Option<JsonValue> {
+        let text = match <String as Decode<MySql>>::decode(v.clone()) {
+            Ok(s) => s,
+            Err(_) => {
+                let bytes = <Vec<u8> as Decode<MySql>>::decode(v).ok()?;
+                String::from_utf8(bytes).ok()?
+            }
+        };
+        serde_json::from_str(&text).ok()
+    }
 
-        // Try JSON
-        if let Ok(j) = <JsonValue as Decode<MySql>>::decode(v.clone()) {
-            return j.to_string();
+    /// MariaDB declares `JSON` columns as `LONGTEXT` guarded by `CHECK (json_valid(col))`,
+    /// returns the column name of such a constraint clause.
+    fn json_check_column(clause: &str) -> Option<String> {
+        let clause = clause.trim();
+        let prefix = "json_valid(";
+        if !clause
+            .get(..prefix.len())
+            .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+        {
+            return None;
         }
+        let column = clause[prefix.len()..].strip_suffix(')')?.trim();
+        Some(column.trim_matches('`').to_string())
+    }
 
-        // Try string (after numeric types to avoid empty string issues)
-        if let Ok(s) = <String as Decode<MySql>>::decode(v.clone()) {
-            // Only return string if it's not empty, otherwise try bytes
-            if !s.is_empty() {
-                return s;
-            }
-        }
+    /// Row count kept in `information_schema.TABLES`, exact for MyISAM and an
+    /// estimate for InnoDB. `None` for views.
+    async fn estimate_rows(&self, schema: &str, table: &str) -> Result<Option<u64>, DBError> {
+        let rows: Option<Option<i64>> = sqlx::query_scalar(
+            "SELECT CAST(TABLE_ROWS AS SIGNED) FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_optional(self.pool()?)
+        .await?;
+        Ok(rows.flatten().map(|n| n as u64))
+    }
 
-        // Try bytes as fallback
-        if let Ok(bytes) = <Vec<u8> as Decode<MySql>>::decode(v.clone()) {
-            if let Ok(s) = std::str::from_utf8(&bytes) {
-                if !s.is_empty() {
-                    return s.to_string();
+    /// Names of the columns MariaDB stores as JSON in the given table
+    async fn mariadb_json_columns(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<String>, DBError> {
+        let clauses: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT CAST(CHECK_CLAUSE AS CHAR(1024))
+            FROM INFORMATION_SCHEMA.CHECK_CONSTRAINTS
+            WHERE CONSTRAINT_SCHEMA = ? AND TABLE_NAME = ?
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(clauses
+            .iter()
+            .filter_map(|clause| Self::json_check_column(clause))
+            .collect())
+    }
+
+    /// Columns with their comment, auto-increment, charset and collation
+    async fn column_details(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<ColumnDetail>, DBError> {
+        let columns = self.get_table_columns(schema, table).await?;
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                CAST(COLUMN_NAME AS CHAR(255)) AS COLUMN_NAME,
+                CAST(COLUMN_COMMENT AS CHAR(2048)) AS COLUMN_COMMENT,
+                CAST(EXTRA AS CHAR(255)) AS EXTRA,
+                CAST(CHARACTER_SET_NAME AS CHAR(64)) AS CHARACTER_SET_NAME,
+                CAST(COLLATION_NAME AS CHAR(64)) AS COLLATION_NAME
+            FROM INFORMATION_SCHEMA.COLUMNS
+            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        let mut rows: HashMap<String, MySqlRow> = rows
+            .into_iter()
+            .map(|row| (row.get("COLUMN_NAME"), row))
+            .collect();
+        Ok(columns
+            .into_iter()
+            .map(|column| {
+                let Some(row) = rows.remove(&column.name) else {
+                    return ColumnDetail::from(column);
+                };
+                let extra: String = row.try_get("EXTRA").unwrap_or_default();
+                ColumnDetail {
+                    column,
+                    comment: row
+                        .try_get::<Option<String>, _>("COLUMN_COMMENT")
+                        .ok()
+                        .flatten()
+                        .filter(|comment| !comment.is_empty()),
+                    auto_increment: extra.to_ascii_lowercase().contains("auto_increment"),
+                    charset: row.try_get("CHARACTER_SET_NAME").ok().flatten(),
+                    collation: row.try_get("COLLATION_NAME").ok().flatten(),
                 }
-            }
-            return "<binary>".to_string();
-        }
+            })
+            .collect())
+    }
 
-        "<unsupported>".to_string()
+    /// First column of the rows of a catalog query taking the schema as its parameter
+    async fn object_names(&self, sql: &str, schema: &str) -> Result<Vec<String>, DBError> {
+        Ok(sqlx::query_scalar(sql)
+            .bind(schema)
+            .fetch_all(self.pool()?)
+            .await?)
+    }
+
+    /// Attributes of each column, see `ColumnAttributes`
+    async fn column_attributes(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<HashMap<String, ColumnAttributes>, DBError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                CAST(COLUMN_NAME AS CHAR(255)) AS COLUMN_NAME,
+                CAST(COLUMN_DEFAULT AS CHAR(2048)) AS COLUMN_DEFAULT,
+                CAST(EXTRA AS CHAR(255)) AS EXTRA,
+                CAST(COLLATION_NAME AS CHAR(64)) AS COLLATION_NAME,
+                CAST(COLUMN_COMMENT AS CHAR(2048)) AS COLUMN_COMMENT
+            FROM INFORMATION_SCHEMA.COLUMNS
+            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let extra: String = row.try_get("EXTRA").unwrap_or_default();
+                let default: Option<String> = row.try_get("COLUMN_DEFAULT").ok().flatten();
+                // MariaDB reports defaults as SQL already, MySQL reports the value of
+                // literals and marks expressions as DEFAULT_GENERATED
+                let default = match (self.flavor, default) {
+                    (_, None) => None,
+                    (MySqlFlavor::MariaDB, Some(default)) if default == "NULL" => None,
+                    (MySqlFlavor::MariaDB, Some(default)) => Some(default),
+                    (MySqlFlavor::MySql, Some(default)) if extra.contains("DEFAULT_GENERATED") => {
+                        if default.to_uppercase().starts_with("CURRENT_TIMESTAMP") {
+                            Some(default)
+                        } else {
+                            Some(format!("({})", default))
+                        }
+                    }
+                    (MySqlFlavor::MySql, Some(default)) if default.starts_with("b'") => {
+                        Some(default)
+                    }
+                    (MySqlFlavor::MySql, Some(default)) => {
+                        Some(QueryParam::Text(default).to_string())
+                    }
+                };
+                let extra = extra.replace("DEFAULT_GENERATED", "");
+                let attributes = ColumnAttributes {
+                    default,
+                    collation: row.try_get("COLLATION_NAME").ok().flatten(),
+                    extra: Some(extra.trim().to_string())
+                        .filter(|extra| !extra.is_empty() && !extra.contains("GENERATED")),
+                    comment: row
+                        .try_get::<Option<String>, _>("COLUMN_COMMENT")
+                        .ok()
+                        .flatten()
+                        .filter(|comment| !comment.is_empty()),
+                };
+                (row.get("COLUMN_NAME"), attributes)
+            })
+            .collect())
+    }
+
+    async fn table_indexes(&self, schema: &str, table: &str) -> Result<Vec<TableIndex>, DBError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                CAST(INDEX_NAME AS CHAR(255)) AS INDEX_NAME,
+                CAST(COLUMN_NAME AS CHAR(255)) AS COLUMN_NAME,
+                CAST(NON_UNIQUE AS SIGNED) AS NON_UNIQUE,
+                CAST(SUB_PART AS SIGNED) AS SUB_PART,
+                CAST(COLLATION AS CHAR(1)) AS COLLATION,
+                CAST(INDEX_TYPE AS CHAR(16)) AS INDEX_TYPE
+            FROM INFORMATION_SCHEMA.STATISTICS
+            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+            ORDER BY INDEX_NAME <> 'PRIMARY', INDEX_NAME, SEQ_IN_INDEX
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(TableIndex::group(rows.into_iter().map(|row| {
+            let name: String = row.get("INDEX_NAME");
+            let index = TableIndex {
+                primary: name == "PRIMARY",
+                name,
+                columns: Vec::new(),
+                unique: row.get::<i64, _>("NON_UNIQUE") == 0,
+                method: row.try_get("INDEX_TYPE").ok().flatten(),
+            };
+            // Functional key parts have no column name
+            let column = IndexColumn {
+                name: row
+                    .try_get::<Option<String>, _>("COLUMN_NAME")
+                    .ok()
+                    .flatten()
+                    .unwrap_or_else(|| "(expression)".to_string()),
+                prefix_length: row
+                    .try_get::<Option<i64>, _>("SUB_PART")
+                    .ok()
+                    .flatten()
+                    .map(|length| length as u32),
+                descending: row.try_get::<Option<String>, _>("COLLATION").ok().flatten()
+                    == Some("D".to_string()),
+            };
+            (index, column)
+        })))
+    }
+
+    async fn table_foreign_keys(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<ForeignKey>, DBError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                CAST(k.CONSTRAINT_NAME AS CHAR(255)) AS CONSTRAINT_NAME,
+                CAST(k.COLUMN_NAME AS CHAR(255)) AS COLUMN_NAME,
+                CAST(k.REFERENCED_TABLE_SCHEMA AS CHAR(255)) AS REFERENCED_TABLE_SCHEMA,
+                CAST(k.REFERENCED_TABLE_NAME AS CHAR(255)) AS REFERENCED_TABLE_NAME,
+                CAST(k.REFERENCED_COLUMN_NAME AS CHAR(255)) AS REFERENCED_COLUMN_NAME,
+                CAST(r.UPDATE_RULE AS CHAR(64)) AS UPDATE_RULE,
+                CAST(r.DELETE_RULE AS CHAR(64)) AS DELETE_RULE
+            FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE k
+            JOIN INFORMATION_SCHEMA.REFERENTIAL_CONSTRAINTS r
+                ON r.CONSTRAINT_SCHEMA = k.CONSTRAINT_SCHEMA
+                AND r.CONSTRAINT_NAME = k.CONSTRAINT_NAME
+                AND r.TABLE_NAME = k.TABLE_NAME
+            WHERE k.TABLE_SCHEMA = ? AND k.TABLE_NAME = ?
+                AND k.REFERENCED_TABLE_NAME IS NOT NULL
+            ORDER BY k.CONSTRAINT_NAME, k.ORDINAL_POSITION
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(ForeignKey::group(rows.into_iter().map(|row| {
+            let key = ForeignKey {
+                name: row.get("CONSTRAINT_NAME"),
+                columns: Vec::new(),
+                referenced_schema: row.get("REFERENCED_TABLE_SCHEMA"),
+                referenced_table: row.get("REFERENCED_TABLE_NAME"),
+                referenced_columns: Vec::new(),
+                on_update: row.get("UPDATE_RULE"),
+                on_delete: row.get("DELETE_RULE"),
+            };
+            (
+                key,
+                row.get("COLUMN_NAME"),
+                row.get("REFERENCED_COLUMN_NAME"),
+            )
+        })))
+    }
+
+    /// Check constraints, MySQL before 8.0.16 neither enforces nor lists them
+    async fn table_checks(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<CheckConstraint>, DBError> {
+        // Only MariaDB has the table name in CHECK_CONSTRAINTS
+        let sql = if self.flavor == MySqlFlavor::MariaDB {
+            r#"
+            SELECT
+                CAST(CONSTRAINT_NAME AS CHAR(255)) AS CONSTRAINT_NAME,
+                CAST(CHECK_CLAUSE AS CHAR) AS CHECK_CLAUSE
+            FROM INFORMATION_SCHEMA.CHECK_CONSTRAINTS
+            WHERE CONSTRAINT_SCHEMA = ? AND TABLE_NAME = ?
+            ORDER BY CONSTRAINT_NAME
+            "#
+        } else {
+            r#"
+            SELECT
+                CAST(c.CONSTRAINT_NAME AS CHAR(255)) AS CONSTRAINT_NAME,
+                CAST(c.CHECK_CLAUSE AS CHAR) AS CHECK_CLAUSE
+            FROM INFORMATION_SCHEMA.TABLE_CONSTRAINTS t
+            JOIN INFORMATION_SCHEMA.CHECK_CONSTRAINTS c
+                ON c.CONSTRAINT_SCHEMA = t.CONSTRAINT_SCHEMA
+                AND c.CONSTRAINT_NAME = t.CONSTRAINT_NAME
+            WHERE t.TABLE_SCHEMA = ? AND t.TABLE_NAME = ? AND t.CONSTRAINT_TYPE = 'CHECK'
+            ORDER BY c.CONSTRAINT_NAME
+            "#
+        };
+        let rows = match sqlx::query(sql)
+            .bind(schema)
+            .bind(table)
+            .fetch_all(self.pool()?)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(SqlxError::Database(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(rows
+            .into_iter()
+            .map(|row| CheckConstraint {
+                name: row.get("CONSTRAINT_NAME"),
+                expression: row.get("CHECK_CLAUSE"),
+            })
+            .collect())
+    }
+
+    async fn table_triggers(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableTrigger>, DBError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                CAST(TRIGGER_NAME AS CHAR(255)) AS TRIGGER_NAME,
+                CAST(ACTION_TIMING AS CHAR(16)) AS ACTION_TIMING,
+                CAST(EVENT_MANIPULATION AS CHAR(16)) AS EVENT_MANIPULATION,
+                CAST(ACTION_STATEMENT AS CHAR) AS ACTION_STATEMENT
+            FROM INFORMATION_SCHEMA.TRIGGERS
+            WHERE EVENT_OBJECT_SCHEMA = ? AND EVENT_OBJECT_TABLE = ?
+            ORDER BY ACTION_TIMING, EVENT_MANIPULATION, ACTION_ORDER
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TableTrigger {
+                name: row.get("TRIGGER_NAME"),
+                timing: row.get("ACTION_TIMING"),
+                event: row.get("EVENT_MANIPULATION"),
+                statement: row.get("ACTION_STATEMENT"),
+            })
+            .collect())
+    }
+
+    async fn table_options(&self, schema: &str, table: &str) -> Result<TableOptions, DBError> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                CAST(ENGINE AS CHAR(64)) AS ENGINE,
+                CAST(ROW_FORMAT AS CHAR(64)) AS ROW_FORMAT,
+                CAST(TABLE_COLLATION AS CHAR(64)) AS TABLE_COLLATION,
+                CAST(TABLE_COMMENT AS CHAR(2048)) AS TABLE_COMMENT,
+                CAST(DATA_LENGTH + INDEX_LENGTH AS UNSIGNED) AS SIZE_BYTES
+            FROM INFORMATION_SCHEMA.TABLES
+            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_optional(self.pool()?)
+        .await?;
+
+        Ok(row
+            .map(|row| TableOptions {
+                engine: row.try_get("ENGINE").ok().flatten(),
+                row_format: row.try_get("ROW_FORMAT").ok().flatten(),
+                collation: row.try_get("TABLE_COLLATION").ok().flatten(),
+                comment: row
+                    .try_get::<Option<String>, _>("TABLE_COMMENT")
+                    .ok()
+                    .flatten()
+                    .filter(|comment| !comment.is_empty()),
+                size_bytes: row.try_get("SIZE_BYTES").ok().flatten(),
+            })
+            .unwrap_or_default())
     }
 
     fn is_auth_error(e: &SqlxError) -> bool {
@@ -125,22 +666,42 @@ impl MySqlDriver {
         false
     }
 
-    fn build_pool_options() -> MySqlPoolOptions {
-        MySqlPoolOptions::new()
+    fn build_pool_options(read_only: bool) -> MySqlPoolOptions {
+        let options = MySqlPoolOptions::new()
             .max_connections(5)
-            .acquire_timeout(Duration::from_secs(10))
+            .acquire_timeout(Duration::from_secs(10));
+        if !read_only {
+            return options;
+        }
+        // The server refuses writes too, whatever slips past the classification
+        options.after_connect(|conn, _| {
+            Box::pin(async move {
+                conn.execute("SET SESSION TRANSACTION READ ONLY").await?;
+                Ok(())
+            })
+        })
     }
 }
 
 #[async_trait]
 impl DatabaseDriver for MySqlDriver {
     fn name(&self) -> &'static str {
-        "MySQL"
+        match self.flavor {
+            MySqlFlavor::MySql => "MySQL",
+            MySqlFlavor::MariaDB => "MariaDB",
+        }
     }
 
     async fn connect(&mut self) -> Result<(), DBError> {
-        match Self::build_pool_options().connect(&self.uri).await {
+        match Self::build_pool_options(self.read_only)
+            .connect(&self.uri)
+            .await
+        {
             Ok(pool) => {
+                let version: String = sqlx::query_scalar("SELECT VERSION()")
+                    .fetch_one(&pool)
+                    .await?;
+                self.flavor = MySqlFlavor::from_version(&version);
                 self.pool = Some(pool);
                 return Ok(());
             }
@@ -157,7 +718,10 @@ impl DatabaseDriver for MySqlDriver {
     }
 
     async fn test_connection(&self) -> Result<(), DBError> {
-        match Self::build_pool_options().connect(&self.uri).await {
+        match Self::build_pool_options(self.read_only)
+            .connect(&self.uri)
+            .await
+        {
             Ok(pool) => {
                 sqlx::query("SELECT 1").fetch_one(&pool).await?;
                 return Ok(());
@@ -175,6 +739,67 @@ impl DatabaseDriver for MySqlDriver {
         }
     }
 
+    fn as_schema_browser(&self) -> Option<&dyn SchemaBrowser> {
+        Some(self)
+    }
+
+    fn as_sql_executor(&self) -> Option<&dyn SqlExecutor> {
+        Some(self)
+    }
+
+    fn as_query_canceller(&self) -> Option<&dyn QueryCanceller> {
+        Some(self)
+    }
+
+    fn as_table_editor(&self) -> Option<&dyn TableEditor> {
+        (!self.read_only).then_some(self as &dyn TableEditor)
+    }
+
+    fn as_table_designer(&self) -> Option<&dyn TableDesigner> {
+        (!self.read_only).then_some(self as &dyn TableDesigner)
+    }
+
+    fn as_transactional(&self) -> Option<&dyn Transactional> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl SqlExecutor for MySqlDriver {
+    async fn execute(
+        &self,
+        sql: &str,
+        params: &[QueryParam],
+    ) -> Result<Vec<StatementResult>, DBError> {
+        self.run_script(sql, params, None).await
+    }
+}
+
+#[async_trait]
+impl QueryCanceller for MySqlDriver {
+    async fn execute_cancellable(
+        &self,
+        sql: &str,
+        handle: &CancelHandle,
+    ) -> Result<Vec<StatementResult>, DBError> {
+        self.run_script(sql, &[], Some(handle)).await
+    }
+
+    async fn cancel(&self, handle: &CancelHandle) -> Result<(), DBError> {
+        let Some(session) = handle.cancel() else {
+            return Ok(());
+        };
+        // A connection of its own, the pool may be taken up by the statements to stop
+        let mut conn = MySqlConnection::connect(&self.uri).await?;
+        conn.execute(format!("KILL QUERY {}", session).as_str())
+            .await?;
+        conn.close().await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SchemaBrowser for MySqlDriver {
     async fn list_schemas(&self) -> Result<Vec<DBSchema>, DBError> {
         let rows = sqlx::query("SHOW DATABASES")
             .fetch_all(self.pool()?)
@@ -198,6 +823,31 @@ impl DatabaseDriver for MySqlDriver {
     }
 
     async fn list_tables(&self, schema: &str) -> Result<Vec<TableInfo>, DBError> {
+        // MariaDB reports sequences and system-versioned tables as their own table types
+        if self.flavor == MySqlFlavor::MariaDB {
+            let rows = sqlx::query(
+                r#"
+                SELECT
+                    CAST(TABLE_NAME AS CHAR(255)) AS TABLE_NAME,
+                    CAST(TABLE_TYPE AS CHAR(64)) AS TABLE_TYPE
+                FROM INFORMATION_SCHEMA.TABLES
+                WHERE TABLE_SCHEMA = ?
+                ORDER BY TABLE_NAME
+                "#,
+            )
+            .bind(schema)
+            .fetch_all(self.pool()?)
+            .await?;
+
+            return Ok(rows
+                .into_iter()
+                .map(|row| TableInfo {
+                    name: row.get("TABLE_NAME"),
+                    table_type: row.get("TABLE_TYPE"),
+                })
+                .collect());
+        }
+
         let sql = format!("SHOW FULL TABLES FROM `{}`", schema);
 
         let rows = sqlx::query(&sql).fetch_all(self.pool()?).await?;
@@ -229,6 +879,42 @@ impl DatabaseDriver for MySqlDriver {
         Ok(tables)
     }
 
+    async fn list_procedures(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        self.object_names(
+            "SELECT CAST(ROUTINE_NAME AS CHAR(255)) FROM INFORMATION_SCHEMA.ROUTINES \
+             WHERE ROUTINE_SCHEMA = ? AND ROUTINE_TYPE = 'PROCEDURE' ORDER BY ROUTINE_NAME",
+            schema,
+        )
+        .await
+    }
+
+    async fn list_functions(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        self.object_names(
+            "SELECT CAST(ROUTINE_NAME AS CHAR(255)) FROM INFORMATION_SCHEMA.ROUTINES \
+             WHERE ROUTINE_SCHEMA = ? AND ROUTINE_TYPE = 'FUNCTION' ORDER BY ROUTINE_NAME",
+            schema,
+        )
+        .await
+    }
+
+    async fn list_triggers(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        self.object_names(
+            "SELECT CAST(TRIGGER_NAME AS CHAR(255)) FROM INFORMATION_SCHEMA.TRIGGERS \
+             WHERE TRIGGER_SCHEMA = ? ORDER BY TRIGGER_NAME",
+            schema,
+        )
+        .await
+    }
+
+    async fn list_events(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        self.object_names(
+            "SELECT CAST(EVENT_NAME AS CHAR(255)) FROM INFORMATION_SCHEMA.EVENTS \
+             WHERE EVENT_SCHEMA = ? ORDER BY EVENT_NAME",
+            schema,
+        )
+        .await
+    }
+
     async fn get_table_columns(
         &self,
         schema: &str,
@@ -237,13 +923,19 @@ impl DatabaseDriver for MySqlDriver {
         let rows = sqlx::query(
             r#"
             SELECT 
-                CAST(COLUMN_NAME AS CHAR(255)) AS COLUMN_NAME, 
-                CAST(COLUMN_TYPE AS CHAR(255)) AS COLUMN_TYPE, 
-                CAST(IS_NULLABLE AS CHAR(10)) AS IS_NULLABLE, 
-                COLUMN_DEFAULT
-            FROM INFORMATION_SCHEMA.COLUMNS
-            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
-            ORDER BY ORDINAL_POSITION
+                CAST(c.COLUMN_NAME AS CHAR(255)) AS COLUMN_NAME, 
+                CAST(c.COLUMN_TYPE AS CHAR(255)) AS COLUMN_TYPE, 
+                CAST(c.IS_NULLABLE AS CHAR(10)) AS IS_NULLABLE, 
+                c.COLUMN_DEFAULT,
+                CAST(k.ORDINAL_POSITION AS SIGNED) AS PK_POSITION
+            FROM INFORMATION_SCHEMA.COLUMNS c
+            LEFT JOIN INFORMATION_SCHEMA.KEY_COLUMN_USAGE k
+                ON k.TABLE_SCHEMA = c.TABLE_SCHEMA
+                AND k.TABLE_NAME = c.TABLE_NAME
+                AND k.COLUMN_NAME = c.COLUMN_NAME
+                AND k.CONSTRAINT_NAME = 'PRIMARY'
+            WHERE c.TABLE_SCHEMA = ? AND c.TABLE_NAME = ?
+            ORDER BY c.ORDINAL_POSITION
             "#,
         )
         .bind(schema)
@@ -251,13 +943,31 @@ impl DatabaseDriver for MySqlDriver {
         .fetch_all(self.pool()?)
         .await?;
 
+        let json_columns = if self.flavor == MySqlFlavor::MariaDB {
+            self.mariadb_json_columns(schema, table).await?
+        } else {
+            Vec::new()
+        };
+
         Ok(rows
             .into_iter()
-            .map(|row| TableColumn {
-                name: row.get("COLUMN_NAME"),
-                data_type: row.get("COLUMN_TYPE"),
-                nullable: row.get::<String, _>("IS_NULLABLE") == "YES",
-                default: row.try_get("COLUMN_DEFAULT").ok(),
+            .map(|row| {
+                let name: String = row.get("COLUMN_NAME");
+                let mut data_type: String = row.get("COLUMN_TYPE");
+                if json_columns.contains(&name) && data_type.eq_ignore_ascii_case("longtext") {
+                    data_type = "json".to_string();
+                }
+                TableColumn {
+                    name,
+                    data_type,
+                    nullable: row.get::<String, _>("IS_NULLABLE") == "YES",
+                    default: row.try_get("COLUMN_DEFAULT").ok(),
+                    primary_key: row
+                        .try_get::<Option<i64>, _>("PK_POSITION")
+                        .ok()
+                        .flatten()
+                        .map(|position| position as u32),
+                }
             })
             .collect())
     }
@@ -266,8 +976,7 @@ impl DatabaseDriver for MySqlDriver {
         &self,
         schema: &str,
         table: &str,
-        offset: u64,
-        limit: u64,
+        query: &TableQuery,
     ) -> Result<TableDataPage, DBError> {
         // 1. Get columns
         let columns = self.get_table_columns(schema, table).await?;
@@ -276,53 +985,392 @@ impl DatabaseDriver for MySqlDriver {
                 columns: vec![],
                 rows: vec![],
                 total: 0,
+                total_estimated: false,
+                primary_key: vec![],
+                unique_key: vec![],
             });
         }
+        let primary_key = TableColumn::primary_key(&columns);
+        let unique_key = if primary_key.is_empty() {
+            TableIndex::unique_key(&self.table_indexes(schema, table).await?, &columns)
+        } else {
+            Vec::new()
+        };
 
         let col_names: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+        let json_cols: Vec<bool> = columns
+            .iter()
+            .map(|c| c.data_type.eq_ignore_ascii_case("json"))
+            .collect();
         let quoted_cols = col_names
             .iter()
-            .map(|c| format!("`{}`", c))
+            .map(|c| quote_ident(c, DatabaseType::MySql))
             .collect::<Vec<_>>()
             .join(",");
 
-        let full_table = format!("`{}`.`{}`", schema, table);
+        let full_table = format!(
+            "{}.{}",
+            quote_ident(schema, DatabaseType::MySql),
+            quote_ident(table, DatabaseType::MySql)
+        );
+        let mut compiled = CompiledQuery::new(query, &columns, DatabaseType::MySql)?;
 
         // 2. Fetch rows
+        let limit = compiled.bind(QueryParam::Int(query.limit as i64));
+        let offset = compiled.bind(QueryParam::Int(compiled.offset as i64));
         let sql = format!(
-            "SELECT {} FROM {} LIMIT {} OFFSET {}",
-            quoted_cols, full_table, limit, offset
+            "SELECT {} FROM {}{}{} LIMIT {} OFFSET {}",
+            quoted_cols, full_table, compiled.rows_where_sql, compiled.order_sql, limit, offset
         );
 
-        let rows = sqlx::query(&sql).fetch_all(self.pool()?).await?;
+        let rows = bind_params(sqlx::query(&sql), &compiled.params)
+            .fetch_all(self.pool()?)
+            .await?;
 
         let mut parsed_rows = Vec::new();
 
         for row in rows {
             let mut r = Vec::new();
-            for col in &col_names {
+            for (col, is_json) in col_names.iter().zip(&json_cols) {
                 let val = row.try_get_raw(col.as_str());
 
-                let s = match val {
-                    Ok(v) => Self::format_mysql_value(v),
-                    Err(_) => "<err>".to_string(),
+                let value = match val {
+                    Ok(v) => Self::mysql_value(v, *is_json),
+                    Err(_) => CellValue::Text("<err>".to_string()),
                 };
 
-                r.push(s);
+                r.push(value);
             }
             parsed_rows.push(r);
         }
 
-        // 3. Count total
-        let total_sql = format!("SELECT COUNT(*) AS cnt FROM {}", full_table);
-        let total: i64 = sqlx::query_scalar(&total_sql)
-            .fetch_one(self.pool()?)
-            .await?;
+        // 3. Count the matching rows, or take the estimate of the table statistics.
+        // An estimate below the rows just read is stale.
+        let estimate = if query.estimate_total() {
+            self.estimate_rows(schema, table)
+                .await?
+                .filter(|n| *n >= compiled.offset + parsed_rows.len() as u64)
+        } else {
+            None
+        };
+        let total = match estimate {
+            Some(estimate) => estimate,
+            None => {
+                let total_sql = format!(
+                    "SELECT COUNT(*) AS cnt FROM {}{}",
+                    full_table, compiled.where_sql
+                );
+                let total: i64 = bind_params(sqlx::query(&total_sql), compiled.count_params())
+                    .fetch_one(self.pool()?)
+                    .await?
+                    .try_get(0)?;
+                total as u64
+            }
+        };
 
         Ok(TableDataPage {
-            columns: col_names,
+            columns: columns
+                .into_iter()
+                .map(|c| ResultColumn::new(c.name, c.data_type))
+                .collect(),
             rows: parsed_rows,
-            total: total as u64,
+            total,
+            total_estimated: estimate.is_some(),
+            primary_key,
+            unique_key,
+        })
+    }
+
+    async fn get_table_structure(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<TableStructure, DBError> {
+        Ok(TableStructure {
+            columns: self.column_details(schema, table).await?,
+            indexes: self.table_indexes(schema, table).await?,
+            foreign_keys: self.table_foreign_keys(schema, table).await?,
+            checks: self.table_checks(schema, table).await?,
+            triggers: self.table_triggers(schema, table).await?,
+            options: self.table_options(schema, table).await?,
+        })
+    }
+
+    async fn get_object_ddl(
+        &self,
+        schema: &str,
+        name: &str,
+        kind: ObjectKind,
+    ) -> Result<String, DBError> {
+        let column = match kind {
+            // MariaDB names the column of a sequence like the one of a table
+            ObjectKind::Table | ObjectKind::Sequence => "Create Table",
+            ObjectKind::View => "Create View",
+            ObjectKind::Procedure => "Create Procedure",
+            ObjectKind::Function => "Create Function",
+            ObjectKind::Trigger => "SQL Original Statement",
+            ObjectKind::Event => "Create Event",
+        };
+        // Not every SHOW CREATE can be prepared, so it goes over the text protocol
+        let sql = format!(
+            "SHOW CREATE {} {}.{}",
+            kind,
+            quote_ident(schema, DatabaseType::MySql),
+            quote_ident(name, DatabaseType::MySql)
+        );
+        let row = self.pool()?.fetch_optional(sql.as_str()).await?;
+        // The routine body is NULL without the privilege to read it
+        let ddl = row.and_then(|row| match row.try_get::<Option<String>, _>(column) {
+            Ok(ddl) => ddl,
+            Err(_) => row
+                .try_get::<Option<Vec<u8>>, _>(column)
+                .ok()
+                .flatten()
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+        });
+        ddl.map(|ddl| format!("{};", ddl))
+            .ok_or_else(|| object_not_found(kind, schema, name))
+    }
+}
+
+#[async_trait]
+impl TableEditor for MySqlDriver {
+    async fn preview_changes(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+    ) -> Result<Vec<BoundStatement>, DBError> {
+        let table_ident = format!(
+            "{}.{}",
+            quote_ident(schema, DatabaseType::MySql),
+            quote_ident(table, DatabaseType::MySql)
+        );
+        change_statements(changes, &table_ident, DatabaseType::MySql, &HashMap::new())
+    }
+
+    async fn apply_changes(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+    ) -> Result<u64, DBError> {
+        let statements = self.preview_changes(schema, table, changes).await?;
+        // The connection reports matched rather than changed rows, so an update that
+        // keeps a value still counts its row
+        apply_statements(self.pool()?, &self.transaction, &statements, |done| {
+            done.rows_affected()
         })
+        .await
+    }
+}
+
+/// Which changes are instant follows MySQL 8.0.29 and MariaDB 10.5, older servers
+/// rebuild the table for some of them
+#[async_trait]
+impl TableDesigner for MySqlDriver {
+    async fn preview_design(
+        &self,
+        schema: &str,
+        table: &str,
+        design: &TableDesign,
+    ) -> Result<Vec<AlterStatement>, DBError> {
+        let db_type = DatabaseType::MySql;
+        let table_ident = format!(
+            "{}.{}",
+            quote_ident(schema, db_type),
+            quote_ident(table, db_type)
+        );
+        let current = self.get_table_columns(schema, table).await?;
+        let mut attributes = self.column_attributes(schema, table).await?;
+        let alter = |action: String, instant: bool| AlterStatement {
+            sql: format!("ALTER TABLE {} {}", table_ident, action),
+            instant: Some(instant),
+        };
+
+        let mut statements = Vec::new();
+        for change in column_changes(&current, design)? {
+            let statement = match change {
+                ColumnChange::Drop(column) => alter(
+                    format!("DROP COLUMN {}", quote_ident(&column.name, db_type)),
+                    true,
+                ),
+                ColumnChange::Rename { from, to } => alter(
+                    format!(
+                        "RENAME COLUMN {} TO {}",
+                        quote_ident(&from, db_type),
+                        quote_ident(&to, db_type)
+                    ),
+                    true,
+                ),
+                // Only the default changes, the rest of the column stays as it is
+                ColumnChange::Alter {
+                    column,
+                    data_type: false,
+                    nullable: false,
+                    ..
+                } => {
+                    let action = match &column.default {
+                        Some(default) => format!("SET DEFAULT {}", default.trim()),
+                        None => "DROP DEFAULT".to_string(),
+                    };
+                    alter(
+                        format!(
+                            "ALTER COLUMN {} {}",
+                            quote_ident(&column.name, db_type),
+                            action
+                        ),
+                        true,
+                    )
+                }
+                // MODIFY redefines the whole column, so it repeats what stays
+                ColumnChange::Alter {
+                    column,
+                    current,
+                    data_type,
+                    default,
+                    ..
+                } => {
+                    let kept = attributes.remove(&current.name);
+                    let mut definition = format!(
+                        "{} {}",
+                        quote_ident(&column.name, db_type),
+                        column.data_type.trim()
+                    );
+                    if let Some(collation) = kept.as_ref().and_then(|a| a.collation.as_ref()) {
+                        if !data_type {
+                            definition.push_str(&format!(" COLLATE {}", collation));
+                        }
+                    }
+                    definition.push_str(if column.nullable {
+                        " NULL"
+                    } else {
+                        " NOT NULL"
+                    });
+                    let default_sql = if default {
+                        column.default.clone()
+                    } else {
+                        kept.as_ref().and_then(|a| a.default.clone())
+                    };
+                    if let Some(default_sql) = default_sql {
+                        definition.push_str(&format!(" DEFAULT {}", default_sql.trim()));
+                    }
+                    if let Some(extra) = kept.as_ref().and_then(|a| a.extra.as_ref()) {
+                        definition.push_str(&format!(" {}", extra));
+                    }
+                    if let Some(comment) = kept.and_then(|a| a.comment) {
+                        definition.push_str(&format!(" COMMENT {}", QueryParam::Text(comment)));
+                    }
+                    alter(format!("MODIFY COLUMN {}", definition), false)
+                }
+                ColumnChange::Add(column) => alter(
+                    format!("ADD COLUMN {}", column_definition(column, db_type)),
+                    true,
+                ),
+            };
+            statements.push(statement);
+        }
+        // Secondary indexes and foreign keys are built in place, not instantly
+        for index in &design.indexes {
+            statements.push(AlterStatement {
+                sql: create_index(
+                    index,
+                    &quote_ident(&index.name, db_type),
+                    &table_ident,
+                    db_type,
+                ),
+                instant: Some(false),
+            });
+        }
+        for key in &design.foreign_keys {
+            statements.push(AlterStatement {
+                sql: add_foreign_key(key, schema, &table_ident, db_type),
+                instant: Some(false),
+            });
+        }
+        Ok(statements)
+    }
+
+    async fn apply_design(
+        &self,
+        schema: &str,
+        table: &str,
+        design: &TableDesign,
+    ) -> Result<(), DBError> {
+        // DDL commits the open transaction implicitly
+        if self.transaction.is_open() {
+            return Err(transaction_open_error());
+        }
+        let statements = self.preview_design(schema, table, design).await?;
+        run_migration(self.pool()?, &statements, false).await
+    }
+}
+
+#[async_trait]
+impl Transactional for MySqlDriver {
+    async fn begin(&self) -> Result<(), DBError> {
+        self.transaction
+            .begin(self.pool()?, "START TRANSACTION")
+            .await
+    }
+
+    async fn commit(&self) -> Result<(), DBError> {
+        self.transaction.finish("COMMIT").await
+    }
+
+    async fn rollback(&self) -> Result<(), DBError> {
+        self.transaction.finish("ROLLBACK").await
+    }
+
+    async fn in_transaction(&self) -> bool {
+        self.transaction.is_open()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flavor_from_version() {
+        for version in [
+            "10.11.6-MariaDB-1:10.11.6+maria~ubu2204",
+            "11.4.2-MariaDB",
+            "5.5.5-10.6.16-MariaDB-log",
+        ] {
+            assert_eq!(
+                MySqlFlavor::from_version(version),
+                MySqlFlavor::MariaDB,
+                "{version}"
+            );
+        }
+        for version in ["8.0.36", "8.4.0-commercial", "5.7.44-log"] {
+            assert_eq!(
+                MySqlFlavor::from_version(version),
+                MySqlFlavor::MySql,
+                "{version}"
+            );
+        }
+    }
+
+    #[test]
+    fn json_check_columns() {
+        assert_eq!(
+            MySqlDriver::json_check_column("json_valid(`payload`)"),
+            Some("payload".to_string())
+        );
+        assert_eq!(
+            MySqlDriver::json_check_column("  JSON_VALID(`meta data`) "),
+            Some("meta data".to_string())
+        );
+        assert_eq!(
+            MySqlDriver::json_check_column("json_valid(payload)"),
+            Some("payload".to_string())
+        );
+        assert_eq!(MySqlDriver::json_check_column("`age` > 0"), None);
+        assert_eq!(
+            MySqlDriver::json_check_column("json_valid(`a`) and `b` > 0"),
+            None
+        );
     }
 }
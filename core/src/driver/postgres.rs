@@ -0,0 +1,1349 @@
+use async_trait::async_trait;
+use sqlx::{
+    decode::Decode,
+    error::Error as SqlxError,
+    postgres::{types::Oid, PgConnection, PgPoolOptions, PgRow, PgValueFormat, PgValueRef},
+    types::{
+        chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc},
+        JsonValue, Uuid,
+    },
+    Connection, Executor, PgPool, Postgres, Row, TypeInfo, ValueRef,
+};
+use std::{borrow::Cow, collections::HashMap, time::Duration};
+
+use crate::{
+    database::DatabaseType,
+    driver::{
+        ddl::{create_table, join_statements, object_not_found},
+        script::{
+            bind_params, changes_session, collect_results, is_empty_summary, restore_empty_result,
+            script_statements,
+        },
+        table_design::{
+            add_foreign_key, column_changes, column_definition, create_index, run_migration,
+            transaction_open_error, ColumnChange,
+        },
+        table_edit::{apply_statements, change_statements},
+        table_query::CompiledQuery,
+        transaction::PinnedTransaction,
+        CancelHandle, DBError, DatabaseDriver, QueryCanceller, SchemaBrowser, SqlExecutor,
+        TableDesigner, TableEditor, Transactional,
+    },
+    model::{
+        design::{AlterStatement, TableDesign},
+        query::{BoundStatement, ExecSummary, QueryParam, ResultColumn, StatementResult},
+        schema::{DBSchema, ObjectKind},
+        structure::{
+            CheckConstraint, ColumnDetail, ForeignKey, IndexColumn, TableIndex, TableOptions,
+            TableStructure, TableTrigger,
+        },
+        table::{RowChange, TableColumn, TableDataPage, TableInfo, TableQuery},
+        value::{CellValue, ColumnKind},
+    },
+};
+
+/// `information_schema` names of the types [`PostgresDriver::pg_value`] decodes from
+/// their binary form
+const PG_BINARY_TYPES: &[&str] = &[
+    "boolean",
+    "smallint",
+    "integer",
+    "bigint",
+    "oid",
+    "real",
+    "double precision",
+    "numeric",
+    "uuid",
+    "json",
+    "jsonb",
+    "date",
+    "time without time zone",
+    "timestamp without time zone",
+    "timestamp with time zone",
+    "bytea",
+    "text",
+    "character varying",
+    "character",
+    "name",
+];
+
+pub struct PostgresDriver {
+    pub uri: String,
+    pub pool: Option<PgPool>,
+    pub read_only: bool,
+    transaction: PinnedTransaction<Postgres>,
+}
+
+impl PostgresDriver {
+    pub fn new(uri: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            pool: None,
+            read_only: false,
+            transaction: PinnedTransaction::new(),
+        }
+    }
+
+    /// Refuse statements that write, and drop the editing capabilities
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Run a script for [`SqlExecutor::execute`], with a `handle` it can be stopped by
+    /// [`QueryCanceller::cancel`]
+    async fn run_script(
+        &self,
+        sql: &str,
+        params: &[QueryParam],
+        handle: Option<&CancelHandle>,
+    ) -> Result<Vec<StatementResult>, DBError> {
+        let statements = script_statements(sql, params, DatabaseType::Postgre, self.read_only)?;
+        // Run the whole script on one connection, so session state like `SET` carries over.
+        // Inside a transaction that is the connection pinned for it.
+        let mut conn = self.transaction.session(self.pool()?).await?;
+        if changes_session(&statements, DatabaseType::Postgre) {
+            conn.close_on_drop();
+        }
+        let Some(handle) = handle else {
+            return Self::run_statements(&mut conn, &statements, params, None).await;
+        };
+        let session: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+            .fetch_one(&mut *conn)
+            .await?;
+        handle.start(session.into())?;
+        let result = Self::run_statements(&mut conn, &statements, params, Some(handle)).await;
+        handle.finish(result)
+    }
+
+    async fn run_statements(
+        conn: &mut PgConnection,
+        statements: &[&str],
+        params: &[QueryParam],
+        handle: Option<&CancelHandle>,
+    ) -> Result<Vec<StatementResult>, DBError> {
+        let mut results = Vec::new();
+
+        for &statement in statements {
+            if let Some(handle) = handle {
+                handle.check()?;
+            }
+            let stream = if params.is_empty() {
+                // Simple query protocol, values come back as text
+                conn.fetch_many(statement)
+            } else {
+                conn.fetch_many(bind_params(sqlx::query(statement), params))
+            };
+            let mut outcomes = collect_results(stream, Self::format_row, |done| ExecSummary {
+                rows_affected: done.rows_affected(),
+                ..Default::default()
+            })
+            .await?;
+
+            if is_empty_summary(&outcomes) {
+                if let Ok(describe) = conn.describe(statement).await {
+                    restore_empty_result(&mut outcomes, describe);
+                }
+            }
+
+            results.extend(outcomes.into_iter().map(|result| StatementResult {
+                statement: statement.to_string(),
+                result,
+            }));
+        }
+        Ok(results)
+    }
+
+    fn pool(&self) -> Result<&PgPool, DBError> {
+        self.pool
+            .as_ref()
+            .ok_or(DBError::ConnectionError("Not connected".to_string()))
+    }
+
+    /// Quote an identifier, doubling any embedded double quotes
+    fn quote_ident(ident: &str) -> String {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+
+    /// Row estimate the planner keeps in `pg_class`. `None` for views and for tables
+    /// that were never analyzed.
+    async fn estimate_rows(&self, schema: &str, table: &str) -> Result<Option<u64>, DBError> {
+        let rows: Option<i64> = sqlx::query_scalar(
+            "SELECT c.reltuples::int8 FROM pg_class c \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('r', 'm')",
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_optional(self.pool()?)
+        .await?;
+        Ok(rows.filter(|n| *n >= 0).map(|n| n as u64))
+    }
+
+    /// Full type of each column, e.g. `character varying(20)` or `public.mood`, as
+    /// `format_type` spells it so that it can be used in a cast
+    async fn column_types(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<HashMap<String, String>, DBError> {
+        let rows = sqlx::query(
+            "SELECT a.attname::text, format_type(a.atttypid, a.atttypmod) FROM pg_attribute a \
+             JOIN pg_class c ON c.oid = a.attrelid \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = $1 AND c.relname = $2 AND a.attnum > 0 AND NOT a.attisdropped",
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect())
+    }
+
+    /// Columns with their comment, collation and whether they take serial or identity values
+    async fn column_details(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<ColumnDetail>, DBError> {
+        let columns = self.get_table_columns(schema, table).await?;
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                a.attname::text AS name,
+                col_description(a.attrelid, a.attnum) AS comment,
+                COALESCE(
+                    a.attidentity <> '' OR pg_get_expr(d.adbin, d.adrelid) LIKE 'nextval(%',
+                    false
+                ) AS auto_increment,
+                co.collname::text AS collation
+            FROM pg_attribute a
+            JOIN pg_class c ON c.oid = a.attrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+            LEFT JOIN pg_collation co
+                ON co.oid = a.attcollation AND co.collname <> 'default'
+            WHERE n.nspname = $1 AND c.relname = $2 AND a.attnum > 0 AND NOT a.attisdropped
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        let mut rows: HashMap<String, PgRow> =
+            rows.into_iter().map(|row| (row.get("name"), row)).collect();
+        Ok(columns
+            .into_iter()
+            .map(|column| {
+                let Some(row) = rows.remove(&column.name) else {
+                    return ColumnDetail::from(column);
+                };
+                ColumnDetail {
+                    column,
+                    comment: row.try_get("comment").ok().flatten(),
+                    auto_increment: row.try_get("auto_increment").unwrap_or(false),
+                    // The encoding belongs to the database, not to columns
+                    charset: None,
+                    collation: row.try_get("collation").ok().flatten(),
+                }
+            })
+            .collect())
+    }
+
+    async fn table_indexes(&self, schema: &str, table: &str) -> Result<Vec<TableIndex>, DBError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                i.relname::text AS name,
+                ix.indisunique AS is_unique,
+                ix.indisprimary AS is_primary,
+                am.amname::text AS method,
+                pg_get_indexdef(ix.indexrelid, k.n, true) AS column_name,
+                (ix.indoption[k.n - 1] & 1) = 1 AS descending
+            FROM pg_index ix
+            JOIN pg_class c ON c.oid = ix.indrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            JOIN pg_class i ON i.oid = ix.indexrelid
+            JOIN pg_am am ON am.oid = i.relam
+            CROSS JOIN LATERAL generate_series(1, ix.indnkeyatts::int4) AS k(n)
+            WHERE n.nspname = $1 AND c.relname = $2
+            ORDER BY ix.indisprimary DESC, i.relname, k.n
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(TableIndex::group(rows.into_iter().map(|row| {
+            let index = TableIndex {
+                name: row.get("name"),
+                columns: Vec::new(),
+                unique: row.get("is_unique"),
+                primary: row.get("is_primary"),
+                method: row.try_get("method").ok(),
+            };
+            let column = IndexColumn {
+                descending: row.try_get("descending").unwrap_or(false),
+                ..IndexColumn::new(row.get::<String, _>("column_name"))
+            };
+            (index, column)
+        })))
+    }
+
+    async fn table_foreign_keys(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<ForeignKey>, DBError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                con.conname::text AS name,
+                a.attname::text AS column_name,
+                rn.nspname::text AS referenced_schema,
+                rc.relname::text AS referenced_table,
+                ra.attname::text AS referenced_column,
+                con.confupdtype::text AS on_update,
+                con.confdeltype::text AS on_delete
+            FROM pg_constraint con
+            JOIN pg_class c ON c.oid = con.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            CROSS JOIN LATERAL unnest(con.conkey, con.confkey)
+                WITH ORDINALITY AS k(attnum, referenced_attnum, position)
+            JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+            JOIN pg_class rc ON rc.oid = con.confrelid
+            JOIN pg_namespace rn ON rn.oid = rc.relnamespace
+            JOIN pg_attribute ra
+                ON ra.attrelid = con.confrelid AND ra.attnum = k.referenced_attnum
+            WHERE con.contype = 'f' AND n.nspname = $1 AND c.relname = $2
+            ORDER BY con.conname, k.position
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(ForeignKey::group(rows.into_iter().map(|row| {
+            let key = ForeignKey {
+                name: row.get("name"),
+                columns: Vec::new(),
+                referenced_schema: row.get("referenced_schema"),
+                referenced_table: row.get("referenced_table"),
+                referenced_columns: Vec::new(),
+                on_update: Self::referential_action(&row.get::<String, _>("on_update")),
+                on_delete: Self::referential_action(&row.get::<String, _>("on_delete")),
+            };
+            (key, row.get("column_name"), row.get("referenced_column"))
+        })))
+    }
+
+    /// Spell out a `pg_constraint` action code
+    fn referential_action(code: &str) -> String {
+        match code {
+            "r" => "RESTRICT",
+            "c" => "CASCADE",
+            "n" => "SET NULL",
+            "d" => "SET DEFAULT",
+            _ => "NO ACTION",
+        }
+        .to_string()
+    }
+
+    async fn table_checks(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<CheckConstraint>, DBError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT con.conname::text AS name, pg_get_constraintdef(con.oid, true) AS expression
+            FROM pg_constraint con
+            JOIN pg_class c ON c.oid = con.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE con.contype = 'c' AND n.nspname = $1 AND c.relname = $2
+            ORDER BY con.conname
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| CheckConstraint {
+                name: row.get("name"),
+                expression: row.get("expression"),
+            })
+            .collect())
+    }
+
+    async fn table_triggers(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableTrigger>, DBError> {
+        // Timing and events are bits of tgtype, see pg_trigger.h
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                t.tgname::text AS name,
+                CASE
+                    WHEN t.tgtype & 2 = 2 THEN 'BEFORE'
+                    WHEN t.tgtype & 64 = 64 THEN 'INSTEAD OF'
+                    ELSE 'AFTER'
+                END AS timing,
+                concat_ws(' OR ',
+                    CASE WHEN t.tgtype & 4 = 4 THEN 'INSERT' END,
+                    CASE WHEN t.tgtype & 16 = 16 THEN 'UPDATE' END,
+                    CASE WHEN t.tgtype & 8 = 8 THEN 'DELETE' END,
+                    CASE WHEN t.tgtype & 32 = 32 THEN 'TRUNCATE' END
+                ) AS event,
+                pg_get_triggerdef(t.oid, true) AS statement
+            FROM pg_trigger t
+            JOIN pg_class c ON c.oid = t.tgrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE NOT t.tgisinternal AND n.nspname = $1 AND c.relname = $2
+            ORDER BY t.tgname
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TableTrigger {
+                name: row.get("name"),
+                timing: row.get("timing"),
+                event: row.get("event"),
+                statement: row.get("statement"),
+            })
+            .collect())
+    }
+
+    async fn table_options(&self, schema: &str, table: &str) -> Result<TableOptions, DBError> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                am.amname::text AS engine,
+                obj_description(c.oid, 'pg_class') AS comment,
+                pg_total_relation_size(c.oid) AS size_bytes
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            LEFT JOIN pg_am am ON am.oid = c.relam
+            WHERE n.nspname = $1 AND c.relname = $2
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_optional(self.pool()?)
+        .await?;
+
+        Ok(row
+            .map(|row| TableOptions {
+                engine: row.try_get("engine").ok().flatten(),
+                row_format: None,
+                collation: None,
+                comment: row.try_get("comment").ok().flatten(),
+                size_bytes: row
+                    .try_get::<Option<i64>, _>("size_bytes")
+                    .ok()
+                    .flatten()
+                    .map(|size| size as u64),
+            })
+            .unwrap_or_default())
+    }
+
+    /// `CREATE TABLE` rebuilt from the catalog, followed by the indexes that back no
+    /// constraint, the triggers and the comments
+    async fn table_ddl(&self, schema: &str, table: &str) -> Result<String, DBError> {
+        let table_ident = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+        let columns = sqlx::query(
+            r#"
+            SELECT
+                a.attname::text AS name,
+                format_type(a.atttypid, a.atttypmod) AS data_type,
+                a.attnotnull AS not_null,
+                pg_get_expr(d.adbin, d.adrelid) AS default_expr,
+                a.attidentity::text AS identity,
+                a.attgenerated::text AS generated,
+                col_description(a.attrelid, a.attnum) AS comment
+            FROM pg_attribute a
+            JOIN pg_class c ON c.oid = a.attrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+            WHERE n.nspname = $1 AND c.relname = $2 AND a.attnum > 0 AND NOT a.attisdropped
+            ORDER BY a.attnum
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+        if columns.is_empty() {
+            return Err(object_not_found(ObjectKind::Table, schema, table));
+        }
+
+        let mut definitions = Vec::new();
+        let mut comments = Vec::new();
+        for row in &columns {
+            let name: String = row.get("name");
+            let mut definition = format!(
+                "{} {}",
+                Self::quote_ident(&name),
+                row.get::<String, _>("data_type")
+            );
+            let default_expr: Option<String> = row.get("default_expr");
+            match (
+                row.get::<String, _>("identity").as_str(),
+                row.get::<String, _>("generated").as_str(),
+                default_expr,
+            ) {
+                ("a", _, _) => definition.push_str(" GENERATED ALWAYS AS IDENTITY"),
+                ("d", _, _) => definition.push_str(" GENERATED BY DEFAULT AS IDENTITY"),
+                // The default of a generated column holds its expression
+                (_, "s", Some(expr)) => {
+                    definition.push_str(&format!(" GENERATED ALWAYS AS ({}) STORED", expr))
+                }
+                (_, _, Some(expr)) => definition.push_str(&format!(" DEFAULT {}", expr)),
+                _ => {}
+            }
+            if row.get::<bool, _>("not_null") {
+                definition.push_str(" NOT NULL");
+            }
+            definitions.push(definition);
+            if let Some(comment) = row.get::<Option<String>, _>("comment") {
+                comments.push(format!(
+                    "COMMENT ON COLUMN {}.{} IS {}",
+                    table_ident,
+                    Self::quote_ident(&name),
+                    QueryParam::Text(comment)
+                ));
+            }
+        }
+
+        // Primary key first, NOT NULL constraints are already on the columns
+        let constraints = sqlx::query(
+            r#"
+            SELECT con.conname::text AS name, pg_get_constraintdef(con.oid, true) AS definition
+            FROM pg_constraint con
+            JOIN pg_class c ON c.oid = con.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relname = $2 AND con.contype IN ('p', 'u', 'f', 'c', 'x')
+            ORDER BY con.contype <> 'p', con.contype, con.conname
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+        definitions.extend(constraints.iter().map(|row| {
+            format!(
+                "CONSTRAINT {} {}",
+                Self::quote_ident(&row.get::<String, _>("name")),
+                row.get::<String, _>("definition")
+            )
+        }));
+
+        let dependents: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT pg_get_indexdef(ix.indexrelid)
+            FROM pg_index ix
+            JOIN pg_class c ON c.oid = ix.indrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relname = $2
+                AND NOT EXISTS (
+                    SELECT 1 FROM pg_constraint con
+                    WHERE con.conindid = ix.indexrelid AND con.conrelid = ix.indrelid
+                )
+            UNION ALL
+            SELECT pg_get_triggerdef(t.oid, true)
+            FROM pg_trigger t
+            JOIN pg_class c ON c.oid = t.tgrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relname = $2 AND NOT t.tgisinternal
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        let table_comment: Option<String> = sqlx::query_scalar(
+            "SELECT obj_description(c.oid, 'pg_class') FROM pg_class c \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = $1 AND c.relname = $2",
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_optional(self.pool()?)
+        .await?
+        .flatten();
+        if let Some(comment) = table_comment {
+            comments.insert(
+                0,
+                format!(
+                    "COMMENT ON TABLE {} IS {}",
+                    table_ident,
+                    QueryParam::Text(comment)
+                ),
+            );
+        }
+
+        Ok(join_statements(
+            std::iter::once(create_table(&table_ident, &definitions))
+                .chain(dependents)
+                .chain(comments),
+        ))
+    }
+
+    /// First column of the rows of a catalog query taking the schema as its parameter
+    async fn object_names(&self, sql: &str, schema: &str) -> Result<Vec<String>, DBError> {
+        Ok(sqlx::query_scalar(sql)
+            .bind(schema)
+            .fetch_all(self.pool()?)
+            .await?)
+    }
+
+    /// Definitions of a view, routine, trigger or sequence. Routines may be overloaded and
+    /// triggers of different tables may share a name, so there can be several.
+    async fn object_definitions(
+        &self,
+        schema: &str,
+        name: &str,
+        kind: ObjectKind,
+    ) -> Result<Vec<String>, DBError> {
+        let sql = match kind {
+            ObjectKind::Table => return Ok(vec![self.table_ddl(schema, name).await?]),
+            ObjectKind::View => {
+                r#"
+                SELECT CASE c.relkind
+                    WHEN 'm' THEN 'CREATE MATERIALIZED VIEW '
+                    ELSE 'CREATE OR REPLACE VIEW '
+                END || quote_ident(n.nspname) || '.' || quote_ident(c.relname) || E' AS\n'
+                    || pg_get_viewdef(c.oid, true)
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('v', 'm')
+                "#
+            }
+            ObjectKind::Procedure => {
+                r#"
+                SELECT pg_get_functiondef(p.oid)
+                FROM pg_proc p
+                JOIN pg_namespace n ON n.oid = p.pronamespace
+                WHERE n.nspname = $1 AND p.proname = $2 AND p.prokind = 'p'
+                ORDER BY p.oid
+                "#
+            }
+            ObjectKind::Function => {
+                r#"
+                SELECT pg_get_functiondef(p.oid)
+                FROM pg_proc p
+                JOIN pg_namespace n ON n.oid = p.pronamespace
+                WHERE n.nspname = $1 AND p.proname = $2 AND p.prokind IN ('f', 'w')
+                ORDER BY p.oid
+                "#
+            }
+            ObjectKind::Trigger => {
+                r#"
+                SELECT pg_get_triggerdef(t.oid, true)
+                FROM pg_trigger t
+                JOIN pg_class c ON c.oid = t.tgrelid
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = $1 AND t.tgname = $2 AND NOT t.tgisinternal
+                ORDER BY c.relname
+                "#
+            }
+            ObjectKind::Event => {
+                return Err(DBError::QueryError(
+                    "PostgreSQL has no scheduled events".to_string(),
+                ))
+            }
+            ObjectKind::Sequence => {
+                r#"
+                SELECT 'CREATE SEQUENCE ' || quote_ident(schemaname) || '.'
+                    || quote_ident(sequencename) || ' AS ' || data_type
+                    || ' INCREMENT BY ' || increment_by
+                    || ' MINVALUE ' || min_value || ' MAXVALUE ' || max_value
+                    || ' START WITH ' || start_value || ' CACHE ' || cache_size
+                    || CASE WHEN cycle THEN ' CYCLE' ELSE ' NO CYCLE' END
+                FROM pg_sequences
+                WHERE schemaname = $1 AND sequencename = $2
+                "#
+            }
+        };
+        Ok(sqlx::query_scalar(sql)
+            .bind(schema)
+            .bind(name)
+            .fetch_all(self.pool()?)
+            .await?)
+    }
+
+    fn pg_value(v: PgValueRef<'_>) -> CellValue {
+        if v.is_null() {
+            return CellValue::Null;
+        }
+
+        // The simple query protocol returns every value in the server's text output
+        let type_name = v.type_info().name().to_string();
+        if v.format() == PgValueFormat::Text {
+            return match <&str as Decode<Postgres>>::decode(v) {
+                Ok(text) => CellValue::parse(text, ColumnKind::from_type_name(&type_name)),
+                Err(_) => CellValue::Text("<err>".to_string()),
+            };
+        }
+
+        let value = match type_name.as_str() {
+            "BOOL" => <bool as Decode<Postgres>>::decode(v).map(CellValue::Bool),
+            "INT2" => <i16 as Decode<Postgres>>::decode(v).map(|n| CellValue::Int(n.into())),
+            "INT4" => <i32 as Decode<Postgres>>::decode(v).map(|n| CellValue::Int(n.into())),
+            "INT8" => <i64 as Decode<Postgres>>::decode(v).map(CellValue::Int),
+            "OID" => <Oid as Decode<Postgres>>::decode(v).map(|oid| CellValue::UInt(oid.0.into())),
+            "FLOAT4" => <f32 as Decode<Postgres>>::decode(v).map(CellValue::from),
+            "FLOAT8" => <f64 as Decode<Postgres>>::decode(v).map(CellValue::Float),
+            "NUMERIC" => <&[u8] as Decode<Postgres>>::decode(v).map(|bytes| {
+                Self::numeric_text(bytes)
+                    .map(CellValue::Decimal)
+                    .unwrap_or_else(|| CellValue::Bytes(bytes.to_vec()))
+            }),
+            "UUID" => <Uuid as Decode<Postgres>>::decode(v).map(CellValue::Uuid),
+            "JSON" | "JSONB" => <JsonValue as Decode<Postgres>>::decode(v).map(CellValue::Json),
+            "DATE" => <NaiveDate as Decode<Postgres>>::decode(v).map(CellValue::Date),
+            "TIME" => <NaiveTime as Decode<Postgres>>::decode(v).map(CellValue::Time),
+            "TIMESTAMP" => <NaiveDateTime as Decode<Postgres>>::decode(v).map(CellValue::DateTime),
+            "TIMESTAMPTZ" => <DateTime<Utc> as Decode<Postgres>>::decode(v)
+                .map(|dt| CellValue::DateTimeTz(dt.fixed_offset())),
+            "BYTEA" => <Vec<u8> as Decode<Postgres>>::decode(v).map(CellValue::Bytes),
+            // Text-like types are sent as plain UTF-8 in binary format too
+            "TEXT" | "VARCHAR" | "BPCHAR" | "CHAR" | "NAME" | "XML" => {
+                <String as Decode<Postgres>>::decode(v).map(CellValue::Text)
+            }
+            _ => <Vec<u8> as Decode<Postgres>>::decode(v).map(CellValue::Bytes),
+        };
+        value.unwrap_or_else(|_| CellValue::Text("<err>".to_string()))
+    }
+
+    /// Render the binary form of a NUMERIC: digit count, weight of the first digit,
+    /// sign and display scale, followed by base-10000 digits
+    fn numeric_text(bytes: &[u8]) -> Option<String> {
+        let word = |idx: usize| -> Option<i16> {
+            let pair = bytes.get(idx * 2..idx * 2 + 2)?;
+            Some(i16::from_be_bytes([pair[0], pair[1]]))
+        };
+        let ndigits = word(0)? as usize;
+        let weight = word(1)? as isize;
+        let sign = word(2)? as u16;
+        let scale = word(3)? as u16 as usize;
+        match sign {
+            0xC000 => return Some("NaN".to_string()),
+            0xD000 => return Some("Infinity".to_string()),
+            0xF000 => return Some("-Infinity".to_string()),
+            _ => {}
+        }
+        let digits = (0..ndigits)
+            .map(|idx| word(4 + idx))
+            .collect::<Option<Vec<i16>>>()?;
+        let digit = |idx: isize| -> i16 {
+            usize::try_from(idx)
+                .ok()
+                .and_then(|idx| digits.get(idx).copied())
+                .unwrap_or(0)
+        };
+
+        let mut text = String::new();
+        if sign == 0x4000 {
+            text.push('-');
+        }
+        if weight < 0 {
+            text.push('0');
+        } else {
+            text.push_str(&digit(0).to_string());
+            for idx in 1..=weight {
+                text.push_str(&format!("{:04}", digit(idx)));
+            }
+        }
+        if scale > 0 {
+            let mut fraction = String::new();
+            let mut idx = weight + 1;
+            while fraction.len() < scale {
+                fraction.push_str(&format!("{:04}", digit(idx)));
+                idx += 1;
+            }
+            fraction.truncate(scale);
+            text.push('.');
+            text.push_str(&fraction);
+        }
+        Some(text)
+    }
+
+    fn format_row(row: &PgRow) -> Vec<CellValue> {
+        (0..row.len())
+            .map(|idx| match row.try_get_raw(idx) {
+                Ok(v) => Self::pg_value(v),
+                Err(_) => CellValue::Text("<err>".to_string()),
+            })
+            .collect()
+    }
+
+    fn is_auth_error(e: &SqlxError) -> bool {
+        if let SqlxError::Database(db_err) = e {
+            if let Some(code) = db_err.code() {
+                // 28P01: invalid_password, 28000: invalid_authorization_specification
+                return code == Cow::Borrowed("28P01") || code == Cow::Borrowed("28000");
+            }
+        }
+        false
+    }
+
+    fn is_timeout_error(e: &SqlxError) -> bool {
+        if let SqlxError::PoolTimedOut = e {
+            return true;
+        }
+        false
+    }
+
+    fn build_pool_options(read_only: bool) -> PgPoolOptions {
+        let options = PgPoolOptions::new()
+            .max_connections(5)
+            .acquire_timeout(Duration::from_secs(10));
+        if !read_only {
+            return options;
+        }
+        // The server refuses writes too, whatever slips past the classification
+        options.after_connect(|conn, _| {
+            Box::pin(async move {
+                conn.execute("SET default_transaction_read_only = on")
+                    .await?;
+                Ok(())
+            })
+        })
+    }
+
+    fn map_connect_error(e: SqlxError) -> DBError {
+        if Self::is_auth_error(&e) {
+            DBError::AuthFailedError
+        } else if Self::is_timeout_error(&e) {
+            DBError::ConnectionTimeout
+        } else {
+            DBError::ConnectionError(format!("{:?}", e))
+        }
+    }
+}
+
+#[async_trait]
+impl DatabaseDriver for PostgresDriver {
+    fn name(&self) -> &'static str {
+        "PostgreSQL"
+    }
+
+    async fn connect(&mut self) -> Result<(), DBError> {
+        let pool = Self::build_pool_options(self.read_only)
+            .connect(&self.uri)
+            .await
+            .map_err(Self::map_connect_error)?;
+        self.pool = Some(pool);
+        Ok(())
+    }
+
+    async fn test_connection(&self) -> Result<(), DBError> {
+        match Self::build_pool_options(self.read_only)
+            .connect(&self.uri)
+            .await
+        {
+            Ok(pool) => {
+                sqlx::query("SELECT 1").fetch_one(&pool).await?;
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("Test Connection Failed, Reason: {:?}", e);
+                Err(Self::map_connect_error(e))
+            }
+        }
+    }
+
+    fn as_schema_browser(&self) -> Option<&dyn SchemaBrowser> {
+        Some(self)
+    }
+
+    fn as_sql_executor(&self) -> Option<&dyn SqlExecutor> {
+        Some(self)
+    }
+
+    fn as_query_canceller(&self) -> Option<&dyn QueryCanceller> {
+        Some(self)
+    }
+
+    fn as_table_editor(&self) -> Option<&dyn TableEditor> {
+        (!self.read_only).then_some(self as &dyn TableEditor)
+    }
+
+    fn as_table_designer(&self) -> Option<&dyn TableDesigner> {
+        (!self.read_only).then_some(self as &dyn TableDesigner)
+    }
+
+    fn as_transactional(&self) -> Option<&dyn Transactional> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl SqlExecutor for PostgresDriver {
+    async fn execute(
+        &self,
+        sql: &str,
+        params: &[QueryParam],
+    ) -> Result<Vec<StatementResult>, DBError> {
+        self.run_script(sql, params, None).await
+    }
+}
+
+#[async_trait]
+impl QueryCanceller for PostgresDriver {
+    async fn execute_cancellable(
+        &self,
+        sql: &str,
+        handle: &CancelHandle,
+    ) -> Result<Vec<StatementResult>, DBError> {
+        self.run_script(sql, &[], Some(handle)).await
+    }
+
+    async fn cancel(&self, handle: &CancelHandle) -> Result<(), DBError> {
+        let Some(session) = handle.cancel() else {
+            return Ok(());
+        };
+        // A connection of its own, the pool may be taken up by the statements to stop
+        let mut conn = PgConnection::connect(&self.uri).await?;
+        sqlx::query("SELECT pg_cancel_backend($1)")
+            .bind(session as i32)
+            .execute(&mut conn)
+            .await?;
+        conn.close().await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SchemaBrowser for PostgresDriver {
+    async fn list_schemas(&self) -> Result<Vec<DBSchema>, DBError> {
+        // Skip TOAST and per-session temporary namespaces, they are internal to the server
+        let rows = sqlx::query(
+            r#"
+            SELECT nspname::text AS name
+            FROM pg_catalog.pg_namespace
+            WHERE nspname NOT LIKE 'pg\_toast%' AND nspname NOT LIKE 'pg\_temp\_%'
+            ORDER BY nspname
+            "#,
+        )
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DBSchema {
+                name: row.get("name"),
+            })
+            .collect())
+    }
+
+    async fn list_tables(&self, schema: &str) -> Result<Vec<TableInfo>, DBError> {
+        // Map relkind to the same table type names MySQL reports
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                c.relname::text AS name,
+                CASE c.relkind
+                    WHEN 'v' THEN 'VIEW'
+                    WHEN 'm' THEN 'MATERIALIZED VIEW'
+                    WHEN 'f' THEN 'FOREIGN TABLE'
+                    ELSE 'BASE TABLE'
+                END AS table_type
+            FROM pg_catalog.pg_class c
+            JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relkind IN ('r', 'p', 'v', 'm', 'f')
+            ORDER BY c.relname
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TableInfo {
+                name: row.get("name"),
+                table_type: row.get("table_type"),
+            })
+            .collect())
+    }
+
+    async fn list_procedures(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        self.object_names(
+            "SELECT DISTINCT p.proname::text FROM pg_proc p \
+             JOIN pg_namespace n ON n.oid = p.pronamespace \
+             WHERE n.nspname = $1 AND p.prokind = 'p' ORDER BY 1",
+            schema,
+        )
+        .await
+    }
+
+    async fn list_functions(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        self.object_names(
+            "SELECT DISTINCT p.proname::text FROM pg_proc p \
+             JOIN pg_namespace n ON n.oid = p.pronamespace \
+             WHERE n.nspname = $1 AND p.prokind IN ('f', 'w') ORDER BY 1",
+            schema,
+        )
+        .await
+    }
+
+    async fn list_triggers(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        self.object_names(
+            "SELECT DISTINCT t.tgname::text FROM pg_trigger t \
+             JOIN pg_class c ON c.oid = t.tgrelid \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = $1 AND NOT t.tgisinternal ORDER BY 1",
+            schema,
+        )
+        .await
+    }
+
+    async fn get_table_columns(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableColumn>, DBError> {
+        // information_schema uses domain types, cast them so they decode as text
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                column_name::text AS column_name,
+                data_type::text AS data_type,
+                is_nullable::text AS is_nullable,
+                column_default::text AS column_default,
+                (
+                    SELECT kcu.ordinal_position::int4
+                    FROM information_schema.table_constraints tc
+                    JOIN information_schema.key_column_usage kcu
+                        ON kcu.constraint_schema = tc.constraint_schema
+                        AND kcu.constraint_name = tc.constraint_name
+                    WHERE tc.constraint_type = 'PRIMARY KEY'
+                        AND tc.table_schema = c.table_schema
+                        AND tc.table_name = c.table_name
+                        AND kcu.column_name = c.column_name
+                ) AS pk_position
+            FROM information_schema.columns c
+            WHERE table_schema = $1 AND table_name = $2
+            ORDER BY ordinal_position
+            "#,
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TableColumn {
+                name: row.get("column_name"),
+                data_type: row.get("data_type"),
+                nullable: row.get::<String, _>("is_nullable") == "YES",
+                default: row.try_get("column_default").ok().flatten(),
+                primary_key: row
+                    .try_get::<Option<i32>, _>("pk_position")
+                    .ok()
+                    .flatten()
+                    .map(|position| position as u32),
+            })
+            .collect())
+    }
+
+    async fn fetch_table_data(
+        &self,
+        schema: &str,
+        table: &str,
+        query: &TableQuery,
+    ) -> Result<TableDataPage, DBError> {
+        // 1. Get columns
+        let columns = self.get_table_columns(schema, table).await?;
+        if columns.is_empty() {
+            return Ok(TableDataPage {
+                columns: vec![],
+                rows: vec![],
+                total: 0,
+                total_estimated: false,
+                primary_key: vec![],
+                unique_key: vec![],
+            });
+        }
+        let primary_key = TableColumn::primary_key(&columns);
+        let unique_key = if primary_key.is_empty() {
+            TableIndex::unique_key(&self.table_indexes(schema, table).await?, &columns)
+        } else {
+            Vec::new()
+        };
+
+        let col_names: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+        // Types without a binary decoder are rendered by the server's text output function
+        let select_cols = columns
+            .iter()
+            .map(|c| {
+                if PG_BINARY_TYPES.contains(&c.data_type.as_str()) {
+                    Self::quote_ident(&c.name)
+                } else {
+                    format!("{}::text", Self::quote_ident(&c.name))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let full_table = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+
+        let mut compiled = CompiledQuery::new(query, &columns, DatabaseType::Postgre)?;
+
+        // 2. Fetch rows
+        let limit = compiled.bind(QueryParam::Int(query.limit as i64));
+        let offset = compiled.bind(QueryParam::Int(compiled.offset as i64));
+        let sql = format!(
+            "SELECT {} FROM {}{}{} LIMIT {} OFFSET {}",
+            select_cols, full_table, compiled.rows_where_sql, compiled.order_sql, limit, offset
+        );
+
+        let rows = bind_params(sqlx::query(&sql), &compiled.params)
+            .fetch_all(self.pool()?)
+            .await?;
+
+        let parsed_rows: Vec<Vec<CellValue>> = rows
+            .into_iter()
+            .map(|row| {
+                (0..col_names.len())
+                    .map(|idx| match row.try_get_raw(idx) {
+                        Ok(v) => Self::pg_value(v),
+                        Err(_) => CellValue::Text("<err>".to_string()),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // 3. Count the matching rows, or take the estimate of the table statistics.
+        // An estimate below the rows just read is stale.
+        let estimate = if query.estimate_total() {
+            self.estimate_rows(schema, table)
+                .await?
+                .filter(|n| *n >= compiled.offset + parsed_rows.len() as u64)
+        } else {
+            None
+        };
+        let total = match estimate {
+            Some(estimate) => estimate,
+            None => {
+                let total_sql = format!(
+                    "SELECT COUNT(*) AS cnt FROM {}{}",
+                    full_table, compiled.where_sql
+                );
+                let total: i64 = bind_params(sqlx::query(&total_sql), compiled.count_params())
+                    .fetch_one(self.pool()?)
+                    .await?
+                    .try_get(0)?;
+                total as u64
+            }
+        };
+
+        Ok(TableDataPage {
+            columns: columns
+                .into_iter()
+                .map(|c| ResultColumn::new(c.name, c.data_type))
+                .collect(),
+            rows: parsed_rows,
+            total,
+            total_estimated: estimate.is_some(),
+            primary_key,
+            unique_key,
+        })
+    }
+
+    async fn get_table_structure(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<TableStructure, DBError> {
+        Ok(TableStructure {
+            columns: self.column_details(schema, table).await?,
+            indexes: self.table_indexes(schema, table).await?,
+            foreign_keys: self.table_foreign_keys(schema, table).await?,
+            checks: self.table_checks(schema, table).await?,
+            triggers: self.table_triggers(schema, table).await?,
+            options: self.table_options(schema, table).await?,
+        })
+    }
+
+    async fn get_object_ddl(
+        &self,
+        schema: &str,
+        name: &str,
+        kind: ObjectKind,
+    ) -> Result<String, DBError> {
+        let definitions = self.object_definitions(schema, name, kind).await?;
+        if definitions.is_empty() {
+            return Err(object_not_found(kind, schema, name));
+        }
+        Ok(join_statements(definitions))
+    }
+}
+
+#[async_trait]
+impl TableEditor for PostgresDriver {
+    async fn preview_changes(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+    ) -> Result<Vec<BoundStatement>, DBError> {
+        // Parameters bound as text aren't converted to the column type implicitly
+        let casts = self.column_types(schema, table).await?;
+        let table_ident = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+        change_statements(changes, &table_ident, DatabaseType::Postgre, &casts)
+    }
+
+    async fn apply_changes(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+    ) -> Result<u64, DBError> {
+        let statements = self.preview_changes(schema, table, changes).await?;
+        apply_statements(self.pool()?, &self.transaction, &statements, |done| {
+            done.rows_affected()
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl TableDesigner for PostgresDriver {
+    async fn preview_design(
+        &self,
+        schema: &str,
+        table: &str,
+        design: &TableDesign,
+    ) -> Result<Vec<AlterStatement>, DBError> {
+        let db_type = DatabaseType::Postgre;
+        let table_ident = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+        let current = self.get_table_columns(schema, table).await?;
+
+        let mut statements = Vec::new();
+        for change in column_changes(&current, design)? {
+            let actions = match change {
+                ColumnChange::Drop(column) => {
+                    vec![format!("DROP COLUMN {}", Self::quote_ident(&column.name))]
+                }
+                ColumnChange::Rename { from, to } => vec![format!(
+                    "RENAME COLUMN {} TO {}",
+                    Self::quote_ident(&from),
+                    Self::quote_ident(&to)
+                )],
+                ColumnChange::Alter {
+                    column,
+                    data_type,
+                    nullable,
+                    default,
+                    ..
+                } => {
+                    let name = Self::quote_ident(&column.name);
+                    let mut actions = Vec::new();
+                    if data_type {
+                        // Values are converted the way a cast would
+                        actions.push(format!(
+                            "ALTER COLUMN {0} TYPE {1} USING {0}::{1}",
+                            name,
+                            column.data_type.trim()
+                        ));
+                    }
+                    if nullable {
+                        actions.push(format!(
+                            "ALTER COLUMN {} {} NOT NULL",
+                            name,
+                            if column.nullable { "DROP" } else { "SET" }
+                        ));
+                    }
+                    if default {
+                        actions.push(match &column.default {
+                            Some(default) => {
+                                format!("ALTER COLUMN {} SET DEFAULT {}", name, default.trim())
+                            }
+                            None => format!("ALTER COLUMN {} DROP DEFAULT", name),
+                        });
+                    }
+                    actions
+                }
+                ColumnChange::Add(column) => {
+                    vec![format!("ADD COLUMN {}", column_definition(column, db_type))]
+                }
+            };
+            statements.push(AlterStatement::new(format!(
+                "ALTER TABLE {} {}",
+                table_ident,
+                actions.join(", ")
+            )));
+        }
+        // Indexes are created in the schema of their table
+        for index in &design.indexes {
+            statements.push(AlterStatement::new(create_index(
+                index,
+                &Self::quote_ident(&index.name),
+                &table_ident,
+                db_type,
+            )));
+        }
+        for key in &design.foreign_keys {
+            statements.push(AlterStatement::new(add_foreign_key(
+                key,
+                schema,
+                &table_ident,
+                db_type,
+            )));
+        }
+        Ok(statements)
+    }
+
+    async fn apply_design(
+        &self,
+        schema: &str,
+        table: &str,
+        design: &TableDesign,
+    ) -> Result<(), DBError> {
+        // The table would stay locked by the open transaction
+        if self.transaction.is_open() {
+            return Err(transaction_open_error());
+        }
+        let statements = self.preview_design(schema, table, design).await?;
+        run_migration(self.pool()?, &statements, true).await
+    }
+}
+
+#[async_trait]
+impl Transactional for PostgresDriver {
+    async fn begin(&self) -> Result<(), DBError> {
+        self.transaction.begin(self.pool()?, "BEGIN").await
+    }
+
+    async fn commit(&self) -> Result<(), DBError> {
+        self.transaction.finish("COMMIT").await
+    }
+
+    async fn rollback(&self) -> Result<(), DBError> {
+        self.transaction.finish("ROLLBACK").await
+    }
+
+    async fn in_transaction(&self) -> bool {
+        self.transaction.is_open()
+    }
+}
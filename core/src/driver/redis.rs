@@ -0,0 +1,461 @@
+use async_trait::async_trait;
+use redis::{
+    aio::MultiplexedConnection, streams::StreamRangeReply, Client, ErrorKind, RedisError, Value,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    driver::{DBError, DatabaseDriver, KeyValueBrowser},
+    model::{
+        key_value::{KeyEntry, KeyNode, KeyType, KeyValue, KEY_SEPARATOR},
+        schema::DBSchema,
+    },
+};
+
+/// Number of logical databases assumed when `CONFIG GET databases` is not permitted
+const DEFAULT_DATABASES: i64 = 16;
+/// Upper bound of keys scanned to build one level of the key tree
+const MAX_SCAN_KEYS: usize = 10_000;
+/// Upper bound of elements read from a collection value
+const MAX_VALUE_ITEMS: usize = 1_000;
+/// `COUNT` hint passed to every `SCAN` family command
+const SCAN_BATCH: usize = 500;
+
+pub struct RedisDriver {
+    pub uri: String,
+    pub client: Option<Client>,
+    /// One multiplexed connection per logical database, opened on first use
+    connections: Mutex<HashMap<i64, MultiplexedConnection>>,
+}
+
+impl RedisDriver {
+    pub fn new(uri: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            client: None,
+            connections: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn client(&self) -> Result<&Client, DBError> {
+        self.client
+            .as_ref()
+            .ok_or(DBError::ConnectionError("Not connected".to_string()))
+    }
+
+    /// Logical databases are exposed as schemas named `db0`, `db1`, ...
+    fn schema_to_db(schema: &str) -> Result<i64, DBError> {
+        schema
+            .strip_prefix("db")
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(|| DBError::QueryError(format!("Invalid Redis database: {}", schema)))
+    }
+
+    /// Escape glob metacharacters so a key prefix can be used in `SCAN MATCH`
+    fn escape_pattern(prefix: &str) -> String {
+        let mut escaped = String::with_capacity(prefix.len());
+        for c in prefix.chars() {
+            if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    fn lossy(bytes: Vec<u8>) -> String {
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    fn map_connect_error(e: RedisError) -> DBError {
+        if e.kind() == ErrorKind::AuthenticationFailed
+            || e.to_string().contains("WRONGPASS")
+            || e.to_string().contains("NOAUTH")
+        {
+            DBError::AuthFailedError
+        } else if e.is_timeout() {
+            DBError::ConnectionTimeout
+        } else {
+            DBError::ConnectionError(format!("{:?}", e))
+        }
+    }
+
+    async fn open_connection(client: &Client, db: i64) -> Result<MultiplexedConnection, DBError> {
+        let mut info = client.get_connection_info().clone();
+        info.redis.db = db;
+        // Without a password no `AUTH` is sent and the login falls back to `default`,
+        // while ACL users with `nopass` accept any password
+        if info.redis.username.is_some() && info.redis.password.is_none() {
+            info.redis.password = Some(String::new());
+        }
+        let client = Client::open(info)?;
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            client.get_multiplexed_async_connection(),
+        )
+        .await
+        .map_err(|_| DBError::ConnectionTimeout)?
+        .map_err(Self::map_connect_error)
+    }
+
+    async fn connection(&self, schema: &str) -> Result<MultiplexedConnection, DBError> {
+        let db = Self::schema_to_db(schema)?;
+        let mut connections = self.connections.lock().await;
+        if let Some(conn) = connections.get(&db) {
+            return Ok(conn.clone());
+        }
+        let conn = Self::open_connection(self.client()?, db).await?;
+        connections.insert(db, conn.clone());
+        Ok(conn)
+    }
+
+    /// Scan the keys starting with `prefix`, stopping after `MAX_SCAN_KEYS`
+    async fn scan_keys(
+        conn: &mut MultiplexedConnection,
+        prefix: &str,
+    ) -> Result<Vec<String>, DBError> {
+        let pattern = format!("{}*", Self::escape_pattern(prefix));
+        let mut cursor: u64 = 0;
+        let mut keys = Vec::new();
+        loop {
+            let (next, batch): (u64, Vec<Vec<u8>>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH)
+                .query_async(conn)
+                .await?;
+            keys.extend(batch.into_iter().map(Self::lossy));
+            cursor = next;
+            if cursor == 0 || keys.len() >= MAX_SCAN_KEYS {
+                break;
+            }
+        }
+        keys.truncate(MAX_SCAN_KEYS);
+        Ok(keys)
+    }
+
+    /// Group keys into the direct children of `prefix`
+    fn build_key_nodes(prefix: &str, keys: Vec<String>) -> Vec<KeyNode> {
+        let mut prefixes: BTreeMap<String, u64> = BTreeMap::new();
+        let mut leaves: Vec<String> = Vec::new();
+
+        for key in keys {
+            let Some(rest) = key.strip_prefix(prefix) else {
+                continue;
+            };
+            match rest.find(KEY_SEPARATOR) {
+                Some(idx) => {
+                    let child = format!("{}{}", prefix, &rest[..=idx]);
+                    *prefixes.entry(child).or_default() += 1;
+                }
+                None => leaves.push(key),
+            }
+        }
+        leaves.sort();
+
+        let label_of = |key: &str| {
+            key[prefix.len()..]
+                .trim_end_matches(KEY_SEPARATOR)
+                .to_string()
+        };
+
+        prefixes
+            .into_iter()
+            .map(|(key, key_count)| KeyNode {
+                label: label_of(&key),
+                key,
+                is_prefix: true,
+                key_count,
+            })
+            .chain(leaves.into_iter().map(|key| KeyNode {
+                label: label_of(&key),
+                key,
+                is_prefix: false,
+                key_count: 1,
+            }))
+            .collect()
+    }
+
+    /// Read a hash or set with `HSCAN`/`SSCAN`, stopping after `MAX_VALUE_ITEMS`
+    async fn scan_collection(
+        conn: &mut MultiplexedConnection,
+        command: &str,
+        key: &str,
+    ) -> Result<Vec<Value>, DBError> {
+        let mut cursor: u64 = 0;
+        let mut items = Vec::new();
+        loop {
+            let (next, batch): (u64, Vec<Value>) = redis::cmd(command)
+                .arg(key)
+                .arg(cursor)
+                .arg("COUNT")
+                .arg(SCAN_BATCH)
+                .query_async(conn)
+                .await?;
+            items.extend(batch);
+            cursor = next;
+            if cursor == 0 || items.len() >= MAX_VALUE_ITEMS * 2 {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn value_to_string(value: &Value) -> String {
+        match redis::from_redis_value::<Vec<u8>>(value) {
+            Ok(bytes) => Self::lossy(bytes),
+            Err(_) => format!("{:?}", value),
+        }
+    }
+
+    async fn read_value(
+        conn: &mut MultiplexedConnection,
+        key: &str,
+        key_type: KeyType,
+    ) -> Result<KeyValue, DBError> {
+        let limit = MAX_VALUE_ITEMS as isize - 1;
+        let value = match key_type {
+            KeyType::String => {
+                let value: Vec<u8> = redis::cmd("GET").arg(key).query_async(conn).await?;
+                KeyValue::String(Self::lossy(value))
+            }
+            KeyType::Hash => {
+                let items = Self::scan_collection(conn, "HSCAN", key).await?;
+                KeyValue::Hash(
+                    items
+                        .chunks_exact(2)
+                        .take(MAX_VALUE_ITEMS)
+                        .map(|pair| {
+                            (
+                                Self::value_to_string(&pair[0]),
+                                Self::value_to_string(&pair[1]),
+                            )
+                        })
+                        .collect(),
+                )
+            }
+            KeyType::List => {
+                let items: Vec<Vec<u8>> = redis::cmd("LRANGE")
+                    .arg(key)
+                    .arg(0)
+                    .arg(limit)
+                    .query_async(conn)
+                    .await?;
+                KeyValue::List(items.into_iter().map(Self::lossy).collect())
+            }
+            KeyType::Set => {
+                let items = Self::scan_collection(conn, "SSCAN", key).await?;
+                KeyValue::Set(
+                    items
+                        .iter()
+                        .take(MAX_VALUE_ITEMS)
+                        .map(Self::value_to_string)
+                        .collect(),
+                )
+            }
+            KeyType::ZSet => {
+                let items: Vec<(Vec<u8>, f64)> = redis::cmd("ZRANGE")
+                    .arg(key)
+                    .arg(0)
+                    .arg(limit)
+                    .arg("WITHSCORES")
+                    .query_async(conn)
+                    .await?;
+                KeyValue::ZSet(
+                    items
+                        .into_iter()
+                        .map(|(member, score)| (Self::lossy(member), score))
+                        .collect(),
+                )
+            }
+            KeyType::Stream => {
+                let reply: StreamRangeReply = redis::cmd("XRANGE")
+                    .arg(key)
+                    .arg("-")
+                    .arg("+")
+                    .arg("COUNT")
+                    .arg(MAX_VALUE_ITEMS)
+                    .query_async(conn)
+                    .await?;
+                KeyValue::Stream(
+                    reply
+                        .ids
+                        .into_iter()
+                        .map(|entry| {
+                            let mut fields: Vec<(String, String)> = entry
+                                .map
+                                .iter()
+                                .map(|(field, value)| (field.clone(), Self::value_to_string(value)))
+                                .collect();
+                            fields.sort();
+                            (entry.id, fields)
+                        })
+                        .collect(),
+                )
+            }
+            KeyType::Unknown => KeyValue::Missing,
+        };
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl DatabaseDriver for RedisDriver {
+    fn name(&self) -> &'static str {
+        "Redis"
+    }
+
+    async fn connect(&mut self) -> Result<(), DBError> {
+        let client = Client::open(self.uri.as_str()).map_err(Self::map_connect_error)?;
+        let mut conn = Self::open_connection(&client, 0).await?;
+        redis::cmd("PING")
+            .query_async::<String>(&mut conn)
+            .await
+            .map_err(Self::map_connect_error)?;
+        self.connections.lock().await.insert(0, conn);
+        self.client = Some(client);
+        Ok(())
+    }
+
+    async fn test_connection(&self) -> Result<(), DBError> {
+        let client = Client::open(self.uri.as_str()).map_err(Self::map_connect_error)?;
+        let mut conn = Self::open_connection(&client, 0).await?;
+        match redis::cmd("PING").query_async::<String>(&mut conn).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                eprintln!("Test Connection Failed, Reason: {:?}", e);
+                Err(Self::map_connect_error(e))
+            }
+        }
+    }
+
+    fn as_key_value(&self) -> Option<&dyn KeyValueBrowser> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl KeyValueBrowser for RedisDriver {
+    async fn list_databases(&self) -> Result<Vec<DBSchema>, DBError> {
+        let mut conn = self.connection("db0").await?;
+        // Managed services often disable CONFIG, fall back to the server default
+        let databases = redis::cmd("CONFIG")
+            .arg("GET")
+            .arg("databases")
+            .query_async::<Vec<String>>(&mut conn)
+            .await
+            .ok()
+            .and_then(|reply| reply.get(1).and_then(|n| n.parse().ok()))
+            .unwrap_or(DEFAULT_DATABASES);
+
+        Ok((0..databases)
+            .map(|db| DBSchema {
+                name: format!("db{}", db),
+            })
+            .collect())
+    }
+
+    async fn list_key_nodes(&self, schema: &str, prefix: &str) -> Result<Vec<KeyNode>, DBError> {
+        let mut conn = self.connection(schema).await?;
+        let keys = Self::scan_keys(&mut conn, prefix).await?;
+        Ok(Self::build_key_nodes(prefix, keys))
+    }
+
+    async fn get_key(&self, schema: &str, key: &str) -> Result<KeyEntry, DBError> {
+        let mut conn = self.connection(schema).await?;
+        let type_name: String = redis::cmd("TYPE").arg(key).query_async(&mut conn).await?;
+        let key_type = KeyType::from_type_name(&type_name);
+        // -1: no expiry, -2: key does not exist
+        let ttl: i64 = redis::cmd("TTL").arg(key).query_async(&mut conn).await?;
+        let value = Self::read_value(&mut conn, key, key_type).await?;
+
+        Ok(KeyEntry {
+            key: key.to_string(),
+            key_type,
+            ttl: (ttl >= 0).then_some(ttl),
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes(prefix: &str, keys: &[&str]) -> Vec<(String, String, bool, u64)> {
+        let keys = keys.iter().map(|key| key.to_string()).collect();
+        RedisDriver::build_key_nodes(prefix, keys)
+            .into_iter()
+            .map(|node| (node.label, node.key, node.is_prefix, node.key_count))
+            .collect()
+    }
+
+    fn node(
+        label: &str,
+        key: &str,
+        is_prefix: bool,
+        key_count: u64,
+    ) -> (String, String, bool, u64) {
+        (label.to_string(), key.to_string(), is_prefix, key_count)
+    }
+
+    #[test]
+    fn prefixes_before_keys() {
+        assert_eq!(
+            nodes("", &["session", "user:2", "user:1", "cache:a:b"]),
+            vec![
+                node("cache", "cache:", true, 1),
+                node("user", "user:", true, 2),
+                node("session", "session", false, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_separators_group_one_level() {
+        assert_eq!(
+            nodes(
+                "app:",
+                &["app:user:1:name", "app:user:2", "app:config", "other:x"]
+            ),
+            vec![
+                node("user", "app:user:", true, 2),
+                node("config", "app:config", false, 1),
+            ]
+        );
+        assert_eq!(
+            nodes("app:user:", &["app:user:1:name", "app:user:2"]),
+            vec![
+                node("1", "app:user:1:", true, 1),
+                node("2", "app:user:2", false, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn key_that_is_also_a_prefix() {
+        assert_eq!(
+            nodes("", &["user", "user:1"]),
+            vec![
+                node("user", "user:", true, 1),
+                node("user", "user", false, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn glob_metacharacters_are_escaped() {
+        assert_eq!(RedisDriver::escape_pattern("user:"), "user:");
+        assert_eq!(
+            RedisDriver::escape_pattern(r"a*b?c[d]e\f"),
+            r"a\*b\?c\[d\]e\\f"
+        );
+        assert_eq!(RedisDriver::escape_pattern("é:^-"), "é:^-");
+    }
+}
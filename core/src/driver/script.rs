@@ -0,0 +1,157 @@
+use futures::{stream::BoxStream, TryStreamExt};
+use sqlx::{
+    query::Query, Column, Database, Describe, Either, Encode, Error as SqlxError, Row, Type,
+    TypeInfo,
+};
+
+use crate::{
+    database::DatabaseType,
+    driver::DBError,
+    model::{
+        query::{ExecSummary, QueryParam, QueryResult, ResultColumn, ResultSet},
+        value::CellValue,
+    },
+    sql::{allowed_read_only, classify_statement, split_statements, StatementKind},
+};
+
+/// Split a script for [`SqlExecutor::execute`](crate::driver::SqlExecutor::execute),
+/// parameters can only be bound to a script holding a single statement. Read-only
+/// connections refuse the whole script when any statement of it writes or could lift
+/// the read-only setting, see [`allowed_read_only`].
+pub(crate) fn script_statements<'a>(
+    sql: &'a str,
+    params: &[QueryParam],
+    db_type: DatabaseType,
+    read_only: bool,
+) -> Result<Vec<&'a str>, DBError> {
+    let statements = split_statements(sql, db_type);
+    if !params.is_empty() && statements.len() > 1 {
+        return Err(DBError::QueryError(
+            "Parameters can only be bound to a single statement".to_string(),
+        ));
+    }
+    if read_only {
+        let write = statements
+            .iter()
+            .find(|statement| !allowed_read_only(statement, db_type));
+        if let Some(statement) = write {
+            return Err(DBError::ReadOnly(statement.to_string()));
+        }
+    }
+    Ok(statements)
+}
+
+/// Whether any statement changes the session, e.g. `SET`, `USE` or `BEGIN`. The state
+/// would stay with the connection after the script, see [`Session::close_on_drop`].
+///
+/// [`Session::close_on_drop`]: crate::driver::transaction::Session::close_on_drop
+pub(crate) fn changes_session(statements: &[&str], db_type: DatabaseType) -> bool {
+    statements
+        .iter()
+        .any(|statement| classify_statement(statement, db_type).kind == StatementKind::Session)
+}
+
+pub(crate) fn bind_params<'q, DB>(
+    mut query: Query<'q, DB, <DB as Database>::Arguments<'q>>,
+    params: &'q [QueryParam],
+) -> Query<'q, DB, <DB as Database>::Arguments<'q>>
+where
+    DB: Database,
+    bool: Encode<'q, DB> + Type<DB>,
+    i64: Encode<'q, DB> + Type<DB>,
+    f64: Encode<'q, DB> + Type<DB>,
+    &'q str: Encode<'q, DB> + Type<DB>,
+    Option<&'q str>: Encode<'q, DB> + Type<DB>,
+    &'q [u8]: Encode<'q, DB> + Type<DB>,
+{
+    for param in params {
+        query = match param {
+            QueryParam::Null => query.bind(None::<&str>),
+            QueryParam::Bool(b) => query.bind(*b),
+            QueryParam::Int(n) => query.bind(*n),
+            QueryParam::Float(f) => query.bind(*f),
+            QueryParam::Text(s) => query.bind(s.as_str()),
+            QueryParam::Bytes(bytes) => query.bind(bytes.as_slice()),
+        };
+    }
+    query
+}
+
+pub(crate) fn result_columns<'a, C: Column + 'a>(
+    columns: impl IntoIterator<Item = &'a C>,
+) -> Vec<ResultColumn> {
+    columns
+        .into_iter()
+        .map(|column| ResultColumn::new(column.name(), column.type_info().name()))
+        .collect()
+}
+
+/// Turn the output of one statement into result sets and summaries. A statement may
+/// produce several of them, e.g. a MySQL `CALL`.
+pub(crate) async fn collect_results<R: Row, Q>(
+    mut stream: BoxStream<'_, Result<Either<Q, R>, SqlxError>>,
+    format_row: impl Fn(&R) -> Vec<CellValue>,
+    summarize: impl Fn(Q) -> ExecSummary,
+) -> Result<Vec<QueryResult>, DBError> {
+    let mut results = Vec::new();
+    let mut current: Option<ResultSet> = None;
+
+    while let Some(item) = stream.try_next().await? {
+        match item {
+            Either::Left(done) => match current.take() {
+                Some(set) => results.push(QueryResult::Rows(set)),
+                None => results.push(QueryResult::Affected(summarize(done))),
+            },
+            Either::Right(row) => current
+                .get_or_insert_with(|| ResultSet {
+                    columns: result_columns(row.columns()),
+                    rows: Vec::new(),
+                })
+                .rows
+                .push(format_row(&row)),
+        }
+    }
+    if let Some(set) = current {
+        results.push(QueryResult::Rows(set));
+    }
+    Ok(results)
+}
+
+/// A query matching no rows only yields a summary, just like a write that changed
+/// nothing. Such results need the statement described to tell them apart.
+pub(crate) fn is_empty_summary(results: &[QueryResult]) -> bool {
+    matches!(results, [QueryResult::Affected(summary)] if summary.rows_affected == 0)
+}
+
+/// Replace the summary of a query matching no rows with its empty result set
+pub(crate) fn restore_empty_result<DB: Database>(
+    results: &mut [QueryResult],
+    describe: Describe<DB>,
+) {
+    if is_empty_summary(results) && !describe.columns().is_empty() {
+        results[0] = QueryResult::Rows(ResultSet {
+            columns: result_columns(describe.columns()),
+            rows: Vec::new(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_statements_are_detected() {
+        let postgres = ["SELECT 1", "SET search_path TO audit"];
+        assert!(changes_session(&postgres, DatabaseType::Postgre));
+        let mysql = ["USE shop", "SELECT * FROM orders"];
+        assert!(changes_session(&mysql, DatabaseType::MySql));
+        assert!(changes_session(&["SET @total = 0"], DatabaseType::MySql));
+    }
+
+    #[test]
+    fn plain_statements_keep_the_connection() {
+        let statements = ["SELECT 1", "UPDATE orders SET paid = true WHERE id = 1"];
+        assert!(!changes_session(&statements, DatabaseType::Postgre));
+    }
+}
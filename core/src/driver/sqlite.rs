@@ -0,0 +1,767 @@
+use async_trait::async_trait;
+use sqlx::{
+    decode::Decode,
+    error::Error as SqlxError,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteRow, SqliteValueRef},
+    Executor, Row, Sqlite, SqlitePool, TypeInfo, ValueRef,
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    database::DatabaseType,
+    driver::{
+        ddl::{join_statements, object_not_found},
+        script::{
+            bind_params, changes_session, collect_results, is_empty_summary, restore_empty_result,
+            script_statements,
+        },
+        table_design::{
+            column_changes, column_definition, create_index, run_migration, transaction_open_error,
+            ColumnChange,
+        },
+        table_edit::{apply_statements, change_statements},
+        table_query::CompiledQuery,
+        transaction::PinnedTransaction,
+        DBError, DatabaseDriver, SchemaBrowser, SqlExecutor, TableDesigner, TableEditor,
+        Transactional,
+    },
+    model::{
+        design::{AlterStatement, TableDesign},
+        query::{BoundStatement, ExecSummary, QueryParam, ResultColumn, StatementResult},
+        schema::{DBSchema, ObjectKind},
+        structure::{
+            ColumnDetail, ForeignKey, IndexColumn, TableIndex, TableStructure, TableTrigger,
+        },
+        table::{RowChange, TableColumn, TableDataPage, TableInfo, TableQuery},
+        value::CellValue,
+    },
+    sql::first_keyword,
+};
+
+/// Special file name that opens a private in-memory database
+pub const SQLITE_MEMORY_PATH: &str = ":memory:";
+
+pub struct SqliteDriver {
+    pub path: PathBuf,
+    pub pool: Option<SqlitePool>,
+    pub read_only: bool,
+    transaction: PinnedTransaction<Sqlite>,
+}
+
+impl SqliteDriver {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            pool: None,
+            read_only: false,
+            transaction: PinnedTransaction::new(),
+        }
+    }
+
+    /// Refuse statements that write, and drop the editing capabilities
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    fn pool(&self) -> Result<&SqlitePool, DBError> {
+        self.pool
+            .as_ref()
+            .ok_or(DBError::ConnectionError("Not connected".to_string()))
+    }
+
+    fn is_memory(&self) -> bool {
+        self.path == Path::new(SQLITE_MEMORY_PATH)
+    }
+
+    /// Quote an identifier, doubling any embedded double quotes
+    fn quote_ident(ident: &str) -> String {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+
+    fn sqlite_value(v: SqliteValueRef<'_>) -> CellValue {
+        if v.is_null() {
+            return CellValue::Null;
+        }
+
+        // SQLite is dynamically typed, so use the storage class of the value itself
+        let type_name = v.type_info().name().to_string();
+        let value = match type_name.as_str() {
+            "INTEGER" => <i64 as Decode<Sqlite>>::decode(v).map(CellValue::Int),
+            "REAL" => <f64 as Decode<Sqlite>>::decode(v).map(CellValue::Float),
+            "BLOB" => <Vec<u8> as Decode<Sqlite>>::decode(v).map(CellValue::Bytes),
+            _ => <String as Decode<Sqlite>>::decode(v).map(CellValue::Text),
+        };
+        value.unwrap_or_else(|_| CellValue::Text("<err>".to_string()))
+    }
+
+    fn format_row(row: &SqliteRow) -> Vec<CellValue> {
+        (0..row.len())
+            .map(|idx| match row.try_get_raw(idx) {
+                Ok(v) => Self::sqlite_value(v),
+                Err(_) => CellValue::Text("<err>".to_string()),
+            })
+            .collect()
+    }
+
+    fn build_pool_options(&self) -> SqlitePoolOptions {
+        // Every connection to `:memory:` opens a separate database, so keep a single one
+        let max_connections = if self.is_memory() { 1 } else { 5 };
+        SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .acquire_timeout(Duration::from_secs(10))
+    }
+
+    fn build_connect_options(&self) -> SqliteConnectOptions {
+        if self.is_memory() {
+            SqliteConnectOptions::new().in_memory(true)
+        } else {
+            SqliteConnectOptions::new()
+                .filename(&self.path)
+                .read_only(self.read_only)
+        }
+    }
+
+    fn map_connect_error(e: SqlxError) -> DBError {
+        if let SqlxError::PoolTimedOut = e {
+            DBError::ConnectionTimeout
+        } else {
+            DBError::ConnectionError(format!("{:?}", e))
+        }
+    }
+
+    async fn table_indexes(
+        &self,
+        schema: &str,
+        table: &str,
+        columns: &[TableColumn],
+    ) -> Result<Vec<TableIndex>, DBError> {
+        // Key columns only, index_xinfo also lists the rowid and any auxiliary columns
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                il.name AS index_name,
+                il."unique" AS is_unique,
+                il.origin AS origin,
+                ii.name AS column_name,
+                ii."desc" AS descending
+            FROM pragma_index_list(?1, ?2) il
+            JOIN pragma_index_xinfo(il.name, ?2) ii
+            WHERE ii.key = 1
+            ORDER BY il.origin <> 'pk', il.name, ii.seqno
+            "#,
+        )
+        .bind(table)
+        .bind(schema)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        let mut indexes = TableIndex::group(rows.into_iter().map(|row| {
+            let index = TableIndex {
+                name: row.get("index_name"),
+                columns: Vec::new(),
+                unique: row.get::<i64, _>("is_unique") == 1,
+                primary: row.get::<String, _>("origin") == "pk",
+                method: None,
+            };
+            // Expression key columns have no name
+            let column = IndexColumn {
+                descending: row.get::<i64, _>("descending") == 1,
+                ..IndexColumn::new(
+                    row.try_get::<Option<String>, _>("column_name")
+                        .ok()
+                        .flatten()
+                        .unwrap_or_else(|| "(expression)".to_string()),
+                )
+            };
+            (index, column)
+        }));
+
+        // An INTEGER PRIMARY KEY is the rowid itself and has no index of its own
+        let primary_key = TableColumn::primary_key(columns);
+        if !primary_key.is_empty() && !indexes.iter().any(|index| index.primary) {
+            indexes.insert(
+                0,
+                TableIndex {
+                    name: "PRIMARY".to_string(),
+                    columns: primary_key.into_iter().map(IndexColumn::new).collect(),
+                    unique: true,
+                    primary: true,
+                    method: None,
+                },
+            );
+        }
+        Ok(indexes)
+    }
+
+    async fn table_foreign_keys(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<ForeignKey>, DBError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, "table", "from", "to", on_update, on_delete
+            FROM pragma_foreign_key_list(?, ?)
+            ORDER BY id, seq
+            "#,
+        )
+        .bind(table)
+        .bind(schema)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        // SQLite keeps no constraint names, the keys are numbered instead
+        Ok(ForeignKey::group(rows.into_iter().map(|row| {
+            let key = ForeignKey {
+                name: format!("fk_{}", row.get::<i64, _>("id")),
+                columns: Vec::new(),
+                referenced_schema: schema.to_string(),
+                referenced_table: row.get("table"),
+                referenced_columns: Vec::new(),
+                on_update: row.get("on_update"),
+                on_delete: row.get("on_delete"),
+            };
+            // No target column means the primary key of the referenced table
+            let referenced = row
+                .try_get::<Option<String>, _>("to")
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+            (key, row.get("from"), referenced)
+        })))
+    }
+
+    async fn table_triggers(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableTrigger>, DBError> {
+        let sql = format!(
+            "SELECT name, sql FROM {}.sqlite_master \
+             WHERE type = 'trigger' AND tbl_name = ? ORDER BY name",
+            Self::quote_ident(schema)
+        );
+        let rows = sqlx::query(&sql)
+            .bind(table)
+            .fetch_all(self.pool()?)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let statement: String = row.get("sql");
+                let (timing, event) = Self::trigger_timing(&statement);
+                TableTrigger {
+                    name: row.get("name"),
+                    timing: timing.to_string(),
+                    event,
+                    statement,
+                }
+            })
+            .collect())
+    }
+
+    /// Timing and event of a `CREATE TRIGGER` statement, read from the part before `ON`
+    fn trigger_timing(sql: &str) -> (&'static str, String) {
+        let words: Vec<String> = sql
+            .split_whitespace()
+            .map(str::to_ascii_uppercase)
+            .take_while(|word| word != "ON")
+            .collect();
+        let has = |word: &str| words.iter().any(|w| w == word);
+        let timing = if has("INSTEAD") {
+            "INSTEAD OF"
+        } else if has("BEFORE") {
+            "BEFORE"
+        } else {
+            "AFTER"
+        };
+        let event = ["INSERT", "UPDATE", "DELETE"]
+            .into_iter()
+            .find(|event| has(event))
+            .unwrap_or_default()
+            .to_string();
+        (timing, event)
+    }
+}
+
+#[async_trait]
+impl DatabaseDriver for SqliteDriver {
+    fn name(&self) -> &'static str {
+        "SQLite"
+    }
+
+    async fn connect(&mut self) -> Result<(), DBError> {
+        let pool = self
+            .build_pool_options()
+            .connect_with(self.build_connect_options())
+            .await
+            .map_err(Self::map_connect_error)?;
+        self.pool = Some(pool);
+        Ok(())
+    }
+
+    async fn test_connection(&self) -> Result<(), DBError> {
+        match self
+            .build_pool_options()
+            .connect_with(self.build_connect_options())
+            .await
+        {
+            Ok(pool) => {
+                sqlx::query("SELECT 1").fetch_one(&pool).await?;
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("Test Connection Failed, Reason: {:?}", e);
+                Err(Self::map_connect_error(e))
+            }
+        }
+    }
+
+    fn as_schema_browser(&self) -> Option<&dyn SchemaBrowser> {
+        Some(self)
+    }
+
+    fn as_sql_executor(&self) -> Option<&dyn SqlExecutor> {
+        Some(self)
+    }
+
+    fn as_table_editor(&self) -> Option<&dyn TableEditor> {
+        (!self.read_only).then_some(self as &dyn TableEditor)
+    }
+
+    fn as_table_designer(&self) -> Option<&dyn TableDesigner> {
+        (!self.read_only).then_some(self as &dyn TableDesigner)
+    }
+
+    /// An in-memory database has a single connection, pinning it would stall browsing
+    /// until the transaction ends
+    fn as_transactional(&self) -> Option<&dyn Transactional> {
+        (!self.is_memory()).then_some(self as &dyn Transactional)
+    }
+}
+
+#[async_trait]
+impl SqlExecutor for SqliteDriver {
+    async fn execute(
+        &self,
+        sql: &str,
+        params: &[QueryParam],
+    ) -> Result<Vec<StatementResult>, DBError> {
+        let statements = script_statements(sql, params, DatabaseType::Sqlite, self.read_only)?;
+        // Run the whole script on one connection, so `ATTACH` and temp tables carry over.
+        // Inside a transaction that is the connection pinned for it.
+        let mut conn = self.transaction.session(self.pool()?).await?;
+        // The only connection of an in-memory database holds the data, it has to stay
+        if changes_session(&statements, DatabaseType::Sqlite) && !self.is_memory() {
+            conn.close_on_drop();
+        }
+        let mut results = Vec::new();
+
+        for statement in statements {
+            let stream = if params.is_empty() {
+                conn.fetch_many(statement)
+            } else {
+                conn.fetch_many(bind_params(sqlx::query(statement), params))
+            };
+            // The rowid of the last insert sticks to the connection, only report it for inserts
+            let is_insert = matches!(first_keyword(statement).as_str(), "INSERT" | "REPLACE");
+            let mut outcomes = collect_results(stream, Self::format_row, |done| ExecSummary {
+                rows_affected: done.rows_affected(),
+                last_insert_id: is_insert.then_some(done.last_insert_rowid() as u64),
+                warnings: Vec::new(),
+            })
+            .await?;
+
+            if is_empty_summary(&outcomes) {
+                if let Ok(describe) = conn.describe(statement).await {
+                    restore_empty_result(&mut outcomes, describe);
+                }
+            }
+
+            results.extend(outcomes.into_iter().map(|result| StatementResult {
+                statement: statement.to_string(),
+                result,
+            }));
+        }
+        Ok(results)
+    }
+}
+
+#[async_trait]
+impl SchemaBrowser for SqliteDriver {
+    async fn list_schemas(&self) -> Result<Vec<DBSchema>, DBError> {
+        // Attached databases act as schemas: `main`, `temp` and any `ATTACH`ed file
+        let rows = sqlx::query("SELECT name FROM pragma_database_list ORDER BY seq")
+            .fetch_all(self.pool()?)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DBSchema {
+                name: row.get("name"),
+            })
+            .collect())
+    }
+
+    async fn list_tables(&self, schema: &str) -> Result<Vec<TableInfo>, DBError> {
+        // Map sqlite_master types to the same table type names MySQL reports
+        let sql = format!(
+            r#"
+            SELECT
+                name,
+                CASE type WHEN 'view' THEN 'VIEW' ELSE 'BASE TABLE' END AS table_type
+            FROM {}.sqlite_master
+            WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite\_%' ESCAPE '\'
+            ORDER BY name
+            "#,
+            Self::quote_ident(schema)
+        );
+
+        let rows = sqlx::query(&sql).fetch_all(self.pool()?).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TableInfo {
+                name: row.get("name"),
+                table_type: row.get("table_type"),
+            })
+            .collect())
+    }
+
+    async fn list_triggers(&self, schema: &str) -> Result<Vec<String>, DBError> {
+        let sql = format!(
+            "SELECT name FROM {}.sqlite_master WHERE type = 'trigger' ORDER BY name",
+            Self::quote_ident(schema)
+        );
+        Ok(sqlx::query_scalar(&sql).fetch_all(self.pool()?).await?)
+    }
+
+    async fn get_table_columns(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableColumn>, DBError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT name, type, "notnull", dflt_value, pk
+            FROM pragma_table_info(?, ?)
+            ORDER BY cid
+            "#,
+        )
+        .bind(table)
+        .bind(schema)
+        .fetch_all(self.pool()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TableColumn {
+                name: row.get("name"),
+                data_type: row.get("type"),
+                nullable: row.get::<i64, _>("notnull") == 0,
+                default: row.try_get("dflt_value").ok().flatten(),
+                // 0 for columns outside the primary key
+                primary_key: u32::try_from(row.get::<i64, _>("pk"))
+                    .ok()
+                    .filter(|position| *position > 0),
+            })
+            .collect())
+    }
+
+    async fn fetch_table_data(
+        &self,
+        schema: &str,
+        table: &str,
+        query: &TableQuery,
+    ) -> Result<TableDataPage, DBError> {
+        // 1. Get columns
+        let columns = self.get_table_columns(schema, table).await?;
+        if columns.is_empty() {
+            return Ok(TableDataPage {
+                columns: vec![],
+                rows: vec![],
+                total: 0,
+                total_estimated: false,
+                primary_key: vec![],
+                unique_key: vec![],
+            });
+        }
+        let primary_key = TableColumn::primary_key(&columns);
+        let unique_key = if primary_key.is_empty() {
+            let indexes = self.table_indexes(schema, table, &columns).await?;
+            TableIndex::unique_key(&indexes, &columns)
+        } else {
+            Vec::new()
+        };
+
+        let col_names: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+        let quoted_cols = col_names
+            .iter()
+            .map(|c| Self::quote_ident(c))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let full_table = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+
+        let mut compiled = CompiledQuery::new(query, &columns, DatabaseType::Sqlite)?;
+
+        // 2. Fetch rows
+        let limit = compiled.bind(QueryParam::Int(query.limit as i64));
+        let offset = compiled.bind(QueryParam::Int(compiled.offset as i64));
+        let sql = format!(
+            "SELECT {} FROM {}{}{} LIMIT {} OFFSET {}",
+            quoted_cols, full_table, compiled.rows_where_sql, compiled.order_sql, limit, offset
+        );
+
+        let rows = bind_params(sqlx::query(&sql), &compiled.params)
+            .fetch_all(self.pool()?)
+            .await?;
+
+        let parsed_rows = rows
+            .into_iter()
+            .map(|row| {
+                (0..col_names.len())
+                    .map(|idx| match row.try_get_raw(idx) {
+                        Ok(v) => Self::sqlite_value(v),
+                        Err(_) => CellValue::Text("<err>".to_string()),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // 3. Count the matching rows, SQLite keeps no row statistics to estimate from
+        let total_sql = format!(
+            "SELECT COUNT(*) AS cnt FROM {}{}",
+            full_table, compiled.where_sql
+        );
+        let total: i64 = bind_params(sqlx::query(&total_sql), compiled.count_params())
+            .fetch_one(self.pool()?)
+            .await?
+            .try_get(0)?;
+
+        Ok(TableDataPage {
+            columns: columns
+                .into_iter()
+                .map(|c| ResultColumn::new(c.name, c.data_type))
+                .collect(),
+            rows: parsed_rows,
+            total: total as u64,
+            total_estimated: false,
+            primary_key,
+            unique_key,
+        })
+    }
+
+    /// SQLite keeps no catalog of check constraints and no table statistics, so the
+    /// structure has neither
+    async fn get_table_structure(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<TableStructure, DBError> {
+        let columns = self.get_table_columns(schema, table).await?;
+        let indexes = self.table_indexes(schema, table, &columns).await?;
+        // Only a lone INTEGER PRIMARY KEY column aliases the auto-assigned rowid
+        let rowid_alias = match TableColumn::primary_key(&columns).as_slice() {
+            [key] => columns
+                .iter()
+                .find(|c| &c.name == key && c.data_type.eq_ignore_ascii_case("INTEGER"))
+                .map(|c| c.name.clone()),
+            _ => None,
+        };
+
+        Ok(TableStructure {
+            columns: columns
+                .into_iter()
+                .map(|column| ColumnDetail {
+                    auto_increment: rowid_alias.as_ref() == Some(&column.name),
+                    ..ColumnDetail::from(column)
+                })
+                .collect(),
+            indexes,
+            foreign_keys: self.table_foreign_keys(schema, table).await?,
+            checks: Vec::new(),
+            triggers: self.table_triggers(schema, table).await?,
+            options: Default::default(),
+        })
+    }
+
+    /// SQLite keeps the original `CREATE` statements, tables get those of their
+    /// indexes and triggers after them
+    async fn get_object_ddl(
+        &self,
+        schema: &str,
+        name: &str,
+        kind: ObjectKind,
+    ) -> Result<String, DBError> {
+        let object_type = match kind {
+            ObjectKind::Table => "table",
+            ObjectKind::View => "view",
+            ObjectKind::Trigger => "trigger",
+            ObjectKind::Procedure
+            | ObjectKind::Function
+            | ObjectKind::Event
+            | ObjectKind::Sequence => {
+                return Err(DBError::QueryError(
+                    "SQLite has no stored procedures, functions, events or sequences".to_string(),
+                ))
+            }
+        };
+        let master = format!("{}.sqlite_master", Self::quote_ident(schema));
+        let ddl: Option<String> = sqlx::query_scalar(&format!(
+            "SELECT sql FROM {} WHERE type = ? AND name = ?",
+            master
+        ))
+        .bind(object_type)
+        .bind(name)
+        .fetch_optional(self.pool()?)
+        .await?
+        .flatten();
+        let Some(ddl) = ddl else {
+            return Err(object_not_found(kind, schema, name));
+        };
+
+        // Automatic indexes of PRIMARY KEY and UNIQUE constraints have no statement
+        let dependents: Vec<String> = if kind == ObjectKind::Table {
+            sqlx::query_scalar(&format!(
+                "SELECT sql FROM {} WHERE type IN ('index', 'trigger') AND tbl_name = ? \
+                 AND sql IS NOT NULL ORDER BY type, name",
+                master
+            ))
+            .bind(name)
+            .fetch_all(self.pool()?)
+            .await?
+        } else {
+            Vec::new()
+        };
+
+        Ok(join_statements(std::iter::once(ddl).chain(dependents)))
+    }
+}
+
+#[async_trait]
+impl TableEditor for SqliteDriver {
+    async fn preview_changes(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+    ) -> Result<Vec<BoundStatement>, DBError> {
+        let table_ident = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+        change_statements(changes, &table_ident, DatabaseType::Sqlite, &HashMap::new())
+    }
+
+    async fn apply_changes(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+    ) -> Result<u64, DBError> {
+        let statements = self.preview_changes(schema, table, changes).await?;
+        apply_statements(self.pool()?, &self.transaction, &statements, |done| {
+            done.rows_affected()
+        })
+        .await
+    }
+}
+
+/// `ALTER TABLE` of SQLite can only add, rename and drop columns, other changes need
+/// the table to be rebuilt
+#[async_trait]
+impl TableDesigner for SqliteDriver {
+    async fn preview_design(
+        &self,
+        schema: &str,
+        table: &str,
+        design: &TableDesign,
+    ) -> Result<Vec<AlterStatement>, DBError> {
+        if !design.foreign_keys.is_empty() {
+            return Err(DBError::QueryError(
+                "SQLite can't add foreign keys to an existing table".to_string(),
+            ));
+        }
+        let table_ident = format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table));
+        let current = self.get_table_columns(schema, table).await?;
+
+        let mut statements = Vec::new();
+        for change in column_changes(&current, design)? {
+            let action = match change {
+                ColumnChange::Drop(column) => {
+                    format!("DROP COLUMN {}", Self::quote_ident(&column.name))
+                }
+                ColumnChange::Rename { from, to } => format!(
+                    "RENAME COLUMN {} TO {}",
+                    Self::quote_ident(&from),
+                    Self::quote_ident(&to)
+                ),
+                ColumnChange::Alter { column, .. } => {
+                    return Err(DBError::QueryError(format!(
+                        "SQLite can't change the type, nullability or default of column {}",
+                        column.name
+                    )))
+                }
+                ColumnChange::Add(column) => format!(
+                    "ADD COLUMN {}",
+                    column_definition(column, DatabaseType::Sqlite)
+                ),
+            };
+            statements.push(AlterStatement::new(format!(
+                "ALTER TABLE {} {}",
+                table_ident, action
+            )));
+        }
+        // The schema qualifies the index, its table is always in the same schema
+        for index in &design.indexes {
+            statements.push(AlterStatement::new(create_index(
+                index,
+                &format!(
+                    "{}.{}",
+                    Self::quote_ident(schema),
+                    Self::quote_ident(&index.name)
+                ),
+                &Self::quote_ident(table),
+                DatabaseType::Sqlite,
+            )));
+        }
+        Ok(statements)
+    }
+
+    async fn apply_design(
+        &self,
+        schema: &str,
+        table: &str,
+        design: &TableDesign,
+    ) -> Result<(), DBError> {
+        // The open transaction holds the write lock
+        if self.transaction.is_open() {
+            return Err(transaction_open_error());
+        }
+        let statements = self.preview_design(schema, table, design).await?;
+        run_migration(self.pool()?, &statements, true).await
+    }
+}
+
+#[async_trait]
+impl Transactional for SqliteDriver {
+    async fn begin(&self) -> Result<(), DBError> {
+        self.transaction.begin(self.pool()?, "BEGIN").await
+    }
+
+    async fn commit(&self) -> Result<(), DBError> {
+        self.transaction.finish("COMMIT").await
+    }
+
+    async fn rollback(&self) -> Result<(), DBError> {
+        self.transaction.finish("ROLLBACK").await
+    }
+
+    async fn in_transaction(&self) -> bool {
+        self.transaction.is_open()
+    }
+}
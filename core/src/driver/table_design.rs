@@ -0,0 +1,528 @@
+use std::{borrow::Cow, collections::HashSet};
+
+use sqlx::{Database, Executor, Pool};
+
+use crate::{
+    database::DatabaseType,
+    driver::{table_query::quote_ident, DBError},
+    model::{
+        design::{AlterStatement, ColumnDesign, TableDesign},
+        structure::{ForeignKey, TableIndex},
+        table::TableColumn,
+    },
+};
+
+/// Change of one column, see `column_changes`
+pub(crate) enum ColumnChange<'a> {
+    Drop(&'a TableColumn),
+    /// Either name may be a temporary one, see `rename_order`
+    Rename {
+        from: Cow<'a, str>,
+        to: Cow<'a, str>,
+    },
+    /// Column taking the name of the design, after renames
+    Alter {
+        column: &'a ColumnDesign,
+        current: &'a TableColumn,
+        data_type: bool,
+        nullable: bool,
+        default: bool,
+    },
+    Add(&'a ColumnDesign),
+}
+
+/// Changes turning the `current` columns into the ones of `design`, ordered so that
+/// each applies to the result of the previous: drops, renames, altered columns and
+/// added columns
+pub(crate) fn column_changes<'a>(
+    current: &'a [TableColumn],
+    design: &'a TableDesign,
+) -> Result<Vec<ColumnChange<'a>>, DBError> {
+    validate(current, design)?;
+    let kept: HashSet<&str> = design
+        .columns
+        .iter()
+        .filter_map(|column| column.original.as_deref())
+        .collect();
+
+    let mut drops = Vec::new();
+    let mut renames = Vec::new();
+    let mut alters = Vec::new();
+    let mut adds = Vec::new();
+    for column in current {
+        if !kept.contains(column.name.as_str()) {
+            drops.push(ColumnChange::Drop(column));
+        }
+    }
+    for column in &design.columns {
+        let Some(original) = &column.original else {
+            adds.push(ColumnChange::Add(column));
+            continue;
+        };
+        let Some(current) = current.iter().find(|c| &c.name == original) else {
+            continue;
+        };
+        if column.name != current.name {
+            renames.push((current.name.as_str(), column.name.as_str()));
+        }
+        let data_type = !column
+            .data_type
+            .trim()
+            .eq_ignore_ascii_case(current.data_type.trim());
+        let nullable = column.nullable != current.nullable;
+        let default =
+            column.default.as_deref().map(str::trim) != current.default.as_deref().map(str::trim);
+        if data_type || nullable || default {
+            alters.push(ColumnChange::Alter {
+                column,
+                current,
+                data_type,
+                nullable,
+                default,
+            });
+        }
+    }
+
+    let taken: HashSet<String> = current
+        .iter()
+        .map(|column| column.name.to_lowercase())
+        .chain(
+            design
+                .columns
+                .iter()
+                .map(|column| column.name.to_lowercase()),
+        )
+        .collect();
+    Ok(drops
+        .into_iter()
+        .chain(rename_order(renames, &taken))
+        .chain(alters)
+        .chain(adds)
+        .collect())
+}
+
+/// Order `(from, to)` renames so that no column takes a name before the column holding
+/// it was renamed away. Names swapped in a cycle, e.g. `a` to `b` and `b` to `a`, go
+/// through a temporary name that is none of `taken`.
+fn rename_order<'a>(
+    renames: Vec<(&'a str, &'a str)>,
+    taken: &HashSet<String>,
+) -> Vec<ColumnChange<'a>> {
+    let mut taken = taken.clone();
+    let mut pending: Vec<(Cow<'a, str>, &'a str)> = renames
+        .into_iter()
+        .map(|(from, to)| (Cow::Borrowed(from), to))
+        .collect();
+    let mut ordered = Vec::new();
+    while !pending.is_empty() {
+        let free = (0..pending.len()).find(|&i| {
+            let to = pending[i].1.to_lowercase();
+            !pending
+                .iter()
+                .enumerate()
+                .any(|(j, (from, _))| j != i && from.to_lowercase() == to)
+        });
+        if let Some(i) = free {
+            let (from, to) = pending.remove(i);
+            ordered.push(ColumnChange::Rename {
+                from,
+                to: Cow::Borrowed(to),
+            });
+            continue;
+        }
+        // Every name is still in use, so all of them are in cycles. Parking one column
+        // under a temporary name frees the name the next one waits for.
+        let from = pending[0].0.clone();
+        let mut n = 1;
+        let temporary = loop {
+            let name = format!("{}_tmp{}", from, n);
+            if !taken.contains(&name.to_lowercase()) {
+                break name;
+            }
+            n += 1;
+        };
+        taken.insert(temporary.to_lowercase());
+        ordered.push(ColumnChange::Rename {
+            from,
+            to: Cow::Owned(temporary.clone()),
+        });
+        pending[0].0 = Cow::Owned(temporary);
+    }
+    ordered
+}
+
+fn validate(current: &[TableColumn], design: &TableDesign) -> Result<(), DBError> {
+    let invalid = |message: String| Err(DBError::QueryError(message));
+    if design.columns.is_empty() {
+        return invalid("A table needs at least one column".to_string());
+    }
+    let mut names = HashSet::new();
+    let mut originals = HashSet::new();
+    for column in &design.columns {
+        if column.name.trim().is_empty() {
+            return invalid("Every column needs a name".to_string());
+        }
+        if column.data_type.trim().is_empty() {
+            return invalid(format!("Column {} needs a type", column.name));
+        }
+        if !names.insert(column.name.to_lowercase()) {
+            return invalid(format!("There is more than one column {}", column.name));
+        }
+        if let Some(original) = &column.original {
+            if !current.iter().any(|c| &c.name == original) || !originals.insert(original) {
+                return invalid(format!("Column {} is not in the table", original));
+            }
+        }
+    }
+
+    let has_column = |name: &String| names.contains(&name.to_lowercase());
+    for index in &design.indexes {
+        if index.name.trim().is_empty() || index.columns.is_empty() {
+            return invalid("Every index needs a name and columns".to_string());
+        }
+        if let Some(column) = index.columns.iter().find(|c| !has_column(&c.name)) {
+            return invalid(format!(
+                "Index {} uses column {}, which is not in the table",
+                index.name, column.name
+            ));
+        }
+    }
+    for key in &design.foreign_keys {
+        if key.name.trim().is_empty()
+            || key.columns.is_empty()
+            || key.referenced_table.trim().is_empty()
+        {
+            return invalid("Every foreign key needs a name, columns and a table".to_string());
+        }
+        if key.columns.len() != key.referenced_columns.len() {
+            return invalid(format!(
+                "Foreign key {} references {} columns for {} columns",
+                key.name,
+                key.referenced_columns.len(),
+                key.columns.len()
+            ));
+        }
+        if let Some(column) = key.columns.iter().find(|c| !has_column(c)) {
+            return invalid(format!(
+                "Foreign key {} uses column {}, which is not in the table",
+                key.name, column
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Column as in `ADD COLUMN`, e.g. `name varchar(20) NOT NULL DEFAULT ''`
+pub(crate) fn column_definition(column: &ColumnDesign, db_type: DatabaseType) -> String {
+    let mut definition = format!(
+        "{} {}",
+        quote_ident(&column.name, db_type),
+        column.data_type.trim()
+    );
+    if !column.nullable {
+        definition.push_str(" NOT NULL");
+    }
+    if let Some(default) = &column.default {
+        definition.push_str(&format!(" DEFAULT {}", default.trim()));
+    }
+    definition
+}
+
+/// `CREATE INDEX` of an index to add. The name is given as the engine wants it, most
+/// create the index in the schema of the table and take it unqualified.
+pub(crate) fn create_index(
+    index: &TableIndex,
+    index_ident: &str,
+    table_ident: &str,
+    db_type: DatabaseType,
+) -> String {
+    let columns = index
+        .columns
+        .iter()
+        .map(|column| {
+            let mut text = quote_ident(&column.name, db_type);
+            if column.descending {
+                text.push_str(" DESC");
+            }
+            text
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "CREATE {}INDEX {} ON {} ({})",
+        if index.unique { "UNIQUE " } else { "" },
+        index_ident,
+        table_ident,
+        columns.join(", ")
+    )
+}
+
+/// `ALTER TABLE .. ADD CONSTRAINT .. FOREIGN KEY` of a key to add. The referenced
+/// table is in the same schema unless the key names another.
+pub(crate) fn add_foreign_key(
+    key: &ForeignKey,
+    schema: &str,
+    table_ident: &str,
+    db_type: DatabaseType,
+) -> String {
+    let quote_all = |columns: &[String]| {
+        columns
+            .iter()
+            .map(|column| quote_ident(column, db_type))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let referenced_schema = if key.referenced_schema.trim().is_empty() {
+        schema
+    } else {
+        key.referenced_schema.trim()
+    };
+    let mut sql = format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {}.{} ({})",
+        table_ident,
+        quote_ident(&key.name, db_type),
+        quote_all(&key.columns),
+        quote_ident(referenced_schema, db_type),
+        quote_ident(key.referenced_table.trim(), db_type),
+        quote_all(&key.referenced_columns)
+    );
+    for (clause, action) in [("ON UPDATE", &key.on_update), ("ON DELETE", &key.on_delete)] {
+        if !action.trim().is_empty() {
+            sql.push_str(&format!(" {} {}", clause, action.trim()));
+        }
+    }
+    sql
+}
+
+/// Run the statements of a migration in order. With `transactional` DDL a failure
+/// takes back the statements before it, otherwise they stay applied and the error
+/// says how many did.
+pub(crate) async fn run_migration<DB>(
+    pool: &Pool<DB>,
+    statements: &[AlterStatement],
+    transactional: bool,
+) -> Result<(), DBError>
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    if transactional {
+        let mut tx = pool.begin().await?;
+        for statement in statements {
+            (&mut *tx).execute(statement.sql.as_str()).await?;
+        }
+        tx.commit().await?;
+        return Ok(());
+    }
+
+    let mut conn = pool.acquire().await?;
+    for (idx, statement) in statements.iter().enumerate() {
+        if let Err(e) = (&mut *conn).execute(statement.sql.as_str()).await {
+            return Err(partial_migration_error(idx, e.into()));
+        }
+    }
+    Ok(())
+}
+
+/// Error of the `idx`th statement of a migration that can't be taken back
+pub(crate) fn partial_migration_error(idx: usize, error: DBError) -> DBError {
+    if idx == 0 {
+        return error;
+    }
+    DBError::QueryError(format!(
+        "Statement {} failed, the {} before it were applied: {}",
+        idx + 1,
+        idx,
+        error
+    ))
+}
+
+/// Error of a migration started while a transaction is open, which DDL would end or
+/// get mixed into
+pub(crate) fn transaction_open_error() -> DBError {
+    DBError::TransactionError(
+        "Commit or roll back the open transaction before changing the table".to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::structure::IndexColumn;
+
+    fn column(name: &str, data_type: &str) -> TableColumn {
+        TableColumn {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            default: None,
+            primary_key: None,
+        }
+    }
+
+    fn design(columns: Vec<ColumnDesign>) -> TableDesign {
+        TableDesign {
+            columns,
+            ..Default::default()
+        }
+    }
+
+    /// Column `original` of the table, renamed to `name`
+    fn renamed(original: &str, name: &str, data_type: &str) -> ColumnDesign {
+        ColumnDesign {
+            original: Some(original.to_string()),
+            name: name.to_string(),
+            ..ColumnDesign::from(&column(original, data_type))
+        }
+    }
+
+    fn describe(changes: &[ColumnChange]) -> Vec<String> {
+        changes
+            .iter()
+            .map(|change| match change {
+                ColumnChange::Drop(column) => format!("drop {}", column.name),
+                ColumnChange::Rename { from, to } => format!("rename {} to {}", from, to),
+                ColumnChange::Alter { column, .. } => format!("alter {}", column.name),
+                ColumnChange::Add(column) => format!("add {}", column.name),
+            })
+            .collect()
+    }
+
+    fn changes(current: &[TableColumn], design: &TableDesign) -> Vec<String> {
+        describe(&column_changes(current, design).unwrap())
+    }
+
+    #[test]
+    fn unchanged_design_has_no_changes() {
+        let current = vec![column("id", "int"), column("name", "text")];
+        assert!(changes(&current, &TableDesign::new(&current)).is_empty());
+    }
+
+    #[test]
+    fn drops_renames_alters_then_adds() {
+        let current = vec![
+            column("id", "int"),
+            column("name", "text"),
+            column("legacy", "text"),
+        ];
+        let mut added = ColumnDesign::from(&column("email", "text"));
+        added.original = None;
+        let design = design(vec![
+            added,
+            renamed("name", "full_name", "varchar(100)"),
+            renamed("id", "id", "bigint"),
+        ]);
+        assert_eq!(
+            changes(&current, &design),
+            [
+                "drop legacy",
+                "rename name to full_name",
+                "alter full_name",
+                "alter id",
+                "add email",
+            ]
+        );
+    }
+
+    #[test]
+    fn type_comparison_ignores_case_and_spaces() {
+        let current = vec![column("id", "INT")];
+        let design = design(vec![renamed("id", "id", " int ")]);
+        assert!(changes(&current, &design).is_empty());
+    }
+
+    #[test]
+    fn renamed_into_a_dropped_name() {
+        let current = vec![column("a", "int"), column("b", "int")];
+        let design = design(vec![renamed("a", "b", "int")]);
+        assert_eq!(changes(&current, &design), ["drop b", "rename a to b"]);
+    }
+
+    #[test]
+    fn chained_renames_free_the_name_first() {
+        let current = vec![column("a", "int"), column("b", "int")];
+        let design = design(vec![renamed("a", "b", "int"), renamed("b", "c", "int")]);
+        assert_eq!(
+            changes(&current, &design),
+            ["rename b to c", "rename a to b"]
+        );
+    }
+
+    #[test]
+    fn swapped_names_go_through_a_temporary_name() {
+        let current = vec![
+            column("a", "int"),
+            column("b", "int"),
+            column("a_tmp1", "int"),
+        ];
+        let design = design(vec![
+            renamed("a", "b", "int"),
+            renamed("b", "a", "int"),
+            renamed("a_tmp1", "a_tmp1", "int"),
+        ]);
+        assert_eq!(
+            changes(&current, &design),
+            ["rename a to a_tmp2", "rename b to a", "rename a_tmp2 to b"]
+        );
+    }
+
+    #[test]
+    fn rotated_names_go_through_a_temporary_name() {
+        let current = vec![column("a", "int"), column("b", "int"), column("c", "int")];
+        let design = design(vec![
+            renamed("a", "b", "int"),
+            renamed("b", "c", "int"),
+            renamed("c", "a", "int"),
+        ]);
+        assert_eq!(
+            changes(&current, &design),
+            [
+                "rename a to a_tmp1",
+                "rename c to a",
+                "rename b to c",
+                "rename a_tmp1 to b",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_designs_are_refused() {
+        let current = vec![column("id", "int"), column("name", "text")];
+        let refused = |columns: Vec<ColumnDesign>| {
+            matches!(
+                column_changes(&current, &design(columns)),
+                Err(DBError::QueryError(_))
+            )
+        };
+        assert!(refused(vec![]));
+        assert!(refused(vec![renamed("id", " ", "int")]));
+        assert!(refused(vec![renamed("id", "id", "")]));
+        // Names differing only in case
+        assert!(refused(vec![
+            renamed("id", "ID", "int"),
+            renamed("name", "id", "text"),
+        ]));
+        assert!(refused(vec![renamed("missing", "missing", "int")]));
+        // The same column kept twice
+        assert!(refused(vec![
+            renamed("id", "id", "int"),
+            renamed("id", "copy", "int"),
+        ]));
+    }
+
+    #[test]
+    fn indexes_and_keys_need_existing_columns() {
+        let current = vec![column("id", "int")];
+        let mut design = TableDesign::new(&current);
+        design.indexes.push(TableIndex {
+            name: "name_idx".to_string(),
+            columns: vec![IndexColumn::new("name")],
+            unique: false,
+            primary: false,
+            method: None,
+        });
+        assert!(column_changes(&current, &design).is_err());
+
+        design.indexes[0].columns = vec![IndexColumn::new("ID")];
+        assert!(column_changes(&current, &design).is_ok());
+    }
+}
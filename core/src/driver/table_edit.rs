@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+
+use sqlx::{Connection, Database, Encode, Executor, IntoArguments, Pool, Type};
+
+use crate::{
+    database::DatabaseType,
+    driver::{
+        script::bind_params,
+        table_query::{placeholder, quote_ident},
+        transaction::{PinnedTransaction, Session},
+        DBError,
+    },
+    model::{
+        query::{BoundStatement, QueryParam},
+        table::RowChange,
+        value::CellValue,
+    },
+};
+
+/// Statements applying `changes` to the table `table_ident`, with every value bound as
+/// a parameter. `casts` maps columns to the type their values are cast to, for
+/// PostgreSQL which doesn't convert text parameters to the column's type by itself.
+/// Updates without changed columns are left out.
+pub(crate) fn change_statements(
+    changes: &[RowChange],
+    table_ident: &str,
+    db_type: DatabaseType,
+    casts: &HashMap<String, String>,
+) -> Result<Vec<BoundStatement>, DBError> {
+    let mut statements = Vec::new();
+    for change in changes {
+        let mut builder = StatementBuilder {
+            db_type,
+            casts,
+            params: Vec::new(),
+        };
+        let sql = match change {
+            RowChange::Update { values, .. } if values.is_empty() => continue,
+            RowChange::Update { key, values } => {
+                let assignments = values
+                    .iter()
+                    .map(|(column, value)| builder.condition(column, value))
+                    .collect::<Vec<_>>();
+                let condition = builder.key_condition(key)?;
+                format!(
+                    "UPDATE {} SET {} WHERE {}",
+                    table_ident,
+                    assignments.join(", "),
+                    condition
+                )
+            }
+            // Every column takes its default
+            RowChange::Insert { values } if values.is_empty() => match db_type {
+                DatabaseType::MySql | DatabaseType::MariaDB => {
+                    format!("INSERT INTO {} () VALUES ()", table_ident)
+                }
+                _ => format!("INSERT INTO {} DEFAULT VALUES", table_ident),
+            },
+            RowChange::Insert { values } => {
+                let columns = values
+                    .iter()
+                    .map(|(column, _)| quote_ident(column, db_type))
+                    .collect::<Vec<_>>();
+                let placeholders = values
+                    .iter()
+                    .map(|(column, value)| builder.value(column, value))
+                    .collect::<Vec<_>>();
+                format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    table_ident,
+                    columns.join(", "),
+                    placeholders.join(", ")
+                )
+            }
+            RowChange::Delete { key } => {
+                let condition = builder.key_condition(key)?;
+                format!("DELETE FROM {} WHERE {}", table_ident, condition)
+            }
+        };
+        statements.push(BoundStatement {
+            sql,
+            params: builder.params,
+        });
+    }
+    Ok(statements)
+}
+
+struct StatementBuilder<'a> {
+    db_type: DatabaseType,
+    casts: &'a HashMap<String, String>,
+    params: Vec<QueryParam>,
+}
+
+impl StatementBuilder<'_> {
+    /// Bind the value of a column and return its placeholder
+    fn value(&mut self, column: &str, value: &CellValue) -> String {
+        self.params.push(value.to_param());
+        let placeholder = placeholder(self.params.len(), self.db_type);
+        match self.casts.get(column) {
+            Some(data_type) => format!("CAST({} AS {})", placeholder, data_type),
+            None => placeholder,
+        }
+    }
+
+    /// `column = value`, used both for assignments and for finding the row
+    fn condition(&mut self, column: &str, value: &CellValue) -> String {
+        let value = self.value(column, value);
+        format!("{} = {}", quote_ident(column, self.db_type), value)
+    }
+
+    fn key_condition(&mut self, key: &[(String, CellValue)]) -> Result<String, DBError> {
+        if key.is_empty() {
+            return Err(DBError::QueryError(
+                "Rows can only be changed in tables with a primary key".to_string(),
+            ));
+        }
+        Ok(key
+            .iter()
+            .map(|(column, value)| self.condition(column, value))
+            .collect::<Vec<_>>()
+            .join(" AND "))
+    }
+}
+
+/// Run `statements` in one transaction. Each has to change exactly one row, a row
+/// that was changed or deleted since it was read rolls everything back. While
+/// `transaction` is open the changes join it, and a savepoint takes back just them.
+pub(crate) async fn apply_statements<'q, DB>(
+    pool: &Pool<DB>,
+    transaction: &PinnedTransaction<DB>,
+    statements: &'q [BoundStatement],
+    rows_affected: impl Fn(&DB::QueryResult) -> u64,
+) -> Result<u64, DBError>
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    <DB as Database>::Arguments<'q>: IntoArguments<'q, DB>,
+    bool: Encode<'q, DB> + Type<DB>,
+    i64: Encode<'q, DB> + Type<DB>,
+    f64: Encode<'q, DB> + Type<DB>,
+    &'q str: Encode<'q, DB> + Type<DB>,
+    Option<&'q str>: Encode<'q, DB> + Type<DB>,
+    &'q [u8]: Encode<'q, DB> + Type<DB>,
+{
+    let mut conn = transaction.session(pool).await?;
+    if matches!(conn, Session::Pooled(_)) {
+        let mut tx = conn.begin().await?;
+        return match run_changes(&mut tx, statements, rows_affected).await {
+            Ok(total) => {
+                tx.commit().await?;
+                Ok(total)
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                Err(e)
+            }
+        };
+    }
+    (&mut *conn).execute("SAVEPOINT table_edit").await?;
+    match run_changes(&mut conn, statements, rows_affected).await {
+        Ok(total) => {
+            (&mut *conn).execute("RELEASE SAVEPOINT table_edit").await?;
+            Ok(total)
+        }
+        Err(e) => {
+            (&mut *conn)
+                .execute("ROLLBACK TO SAVEPOINT table_edit")
+                .await?;
+            (&mut *conn).execute("RELEASE SAVEPOINT table_edit").await?;
+            Err(e)
+        }
+    }
+}
+
+/// Run `statements` on `conn`, stopping at the first that doesn't change one row.
+/// Ending the transaction is up to the caller.
+async fn run_changes<'q, DB>(
+    conn: &mut DB::Connection,
+    statements: &'q [BoundStatement],
+    rows_affected: impl Fn(&DB::QueryResult) -> u64,
+) -> Result<u64, DBError>
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    <DB as Database>::Arguments<'q>: IntoArguments<'q, DB>,
+    bool: Encode<'q, DB> + Type<DB>,
+    i64: Encode<'q, DB> + Type<DB>,
+    f64: Encode<'q, DB> + Type<DB>,
+    &'q str: Encode<'q, DB> + Type<DB>,
+    Option<&'q str>: Encode<'q, DB> + Type<DB>,
+    &'q [u8]: Encode<'q, DB> + Type<DB>,
+{
+    let mut total = 0;
+    for (idx, statement) in statements.iter().enumerate() {
+        let result = bind_params(sqlx::query(&statement.sql), &statement.params)
+            .execute(&mut *conn)
+            .await?;
+        let changed = rows_affected(&result);
+        if changed != 1 {
+            return Err(stale_row_error(idx, changed));
+        }
+        total += changed;
+    }
+    Ok(total)
+}
+
+/// Error of the `idx`th statement changing `changed` rows instead of one
+pub(crate) fn stale_row_error(idx: usize, changed: u64) -> DBError {
+    DBError::TransactionError(format!(
+        "Change {} matched {} rows instead of one, the row may have been changed \
+         since it was read. Nothing was applied.",
+        idx + 1,
+        changed
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        query::ResultColumn,
+        structure::{IndexColumn, TableIndex},
+        table::{TableColumn, TableDataPage},
+    };
+
+    fn text(value: &str) -> CellValue {
+        CellValue::Text(value.to_string())
+    }
+
+    fn update() -> RowChange {
+        RowChange::Update {
+            key: vec![
+                ("tenant".to_string(), CellValue::Int(7)),
+                ("id".to_string(), CellValue::Int(42)),
+            ],
+            values: vec![("name".to_string(), text("Ada"))],
+        }
+    }
+
+    fn statements(changes: &[RowChange], db_type: DatabaseType) -> Vec<BoundStatement> {
+        change_statements(changes, "t", db_type, &HashMap::new()).unwrap()
+    }
+
+    #[test]
+    fn placeholders_follow_the_dialect() {
+        let sql = |db_type| statements(&[update()], db_type).remove(0).sql;
+        assert_eq!(
+            sql(DatabaseType::Postgre),
+            r#"UPDATE t SET "name" = $1 WHERE "tenant" = $2 AND "id" = $3"#
+        );
+        assert_eq!(
+            sql(DatabaseType::MicrosoftSQLServer),
+            "UPDATE t SET [name] = @P1 WHERE [tenant] = @P2 AND [id] = @P3"
+        );
+        assert_eq!(
+            sql(DatabaseType::MySql),
+            "UPDATE t SET `name` = ? WHERE `tenant` = ? AND `id` = ?"
+        );
+        assert_eq!(
+            sql(DatabaseType::Sqlite),
+            r#"UPDATE t SET "name" = ? WHERE "tenant" = ? AND "id" = ?"#
+        );
+    }
+
+    #[test]
+    fn params_are_in_placeholder_order() {
+        let statement = statements(&[update()], DatabaseType::Postgre).remove(0);
+        assert_eq!(
+            statement.params,
+            vec![
+                QueryParam::Text("Ada".to_string()),
+                QueryParam::Int(7),
+                QueryParam::Int(42),
+            ]
+        );
+    }
+
+    #[test]
+    fn numbering_restarts_for_each_statement() {
+        let changes = [
+            update(),
+            RowChange::Delete {
+                key: vec![("id".to_string(), CellValue::Int(1))],
+            },
+        ];
+        let statements = statements(&changes, DatabaseType::Postgre);
+        assert_eq!(statements[1].sql, r#"DELETE FROM t WHERE "id" = $1"#);
+        assert_eq!(statements[1].params, vec![QueryParam::Int(1)]);
+    }
+
+    #[test]
+    fn postgres_values_are_cast() {
+        let casts = HashMap::from([
+            ("id".to_string(), "bigint".to_string()),
+            ("born".to_string(), "date".to_string()),
+        ]);
+        let insert = RowChange::Insert {
+            values: vec![
+                ("id".to_string(), CellValue::Int(1)),
+                ("born".to_string(), text("1815-12-10")),
+                ("name".to_string(), text("Ada")),
+            ],
+        };
+        let statements = change_statements(&[insert], "t", DatabaseType::Postgre, &casts).unwrap();
+        assert_eq!(
+            statements[0].sql,
+            r#"INSERT INTO t ("id", "born", "name") VALUES (CAST($1 AS bigint), CAST($2 AS date), $3)"#
+        );
+    }
+
+    #[test]
+    fn empty_insert_takes_the_defaults() {
+        let insert = [RowChange::Insert { values: vec![] }];
+        assert_eq!(
+            statements(&insert, DatabaseType::MySql)[0].sql,
+            "INSERT INTO t () VALUES ()"
+        );
+        assert_eq!(
+            statements(&insert, DatabaseType::MariaDB)[0].sql,
+            "INSERT INTO t () VALUES ()"
+        );
+        for db_type in [
+            DatabaseType::Postgre,
+            DatabaseType::Sqlite,
+            DatabaseType::MicrosoftSQLServer,
+        ] {
+            let statement = statements(&insert, db_type).remove(0);
+            assert_eq!(statement.sql, "INSERT INTO t DEFAULT VALUES");
+            assert!(statement.params.is_empty());
+        }
+    }
+
+    #[test]
+    fn update_without_changes_is_skipped() {
+        let unchanged = RowChange::Update {
+            key: vec![("id".to_string(), CellValue::Int(1))],
+            values: vec![],
+        };
+        assert!(statements(&[unchanged], DatabaseType::Postgre).is_empty());
+    }
+
+    #[test]
+    fn changes_need_a_key() {
+        let keyless = [
+            RowChange::Update {
+                key: vec![],
+                values: vec![("name".to_string(), text("Ada"))],
+            },
+            RowChange::Delete { key: vec![] },
+        ];
+        for change in keyless {
+            let result = change_statements(&[change], "t", DatabaseType::MySql, &HashMap::new());
+            assert!(matches!(result, Err(DBError::QueryError(_))));
+        }
+    }
+
+    #[test]
+    fn not_null_unique_key_stands_in_for_the_primary_key() {
+        let column = |name: &str, nullable| TableColumn {
+            name: name.to_string(),
+            data_type: "text".to_string(),
+            nullable,
+            default: None,
+            primary_key: None,
+        };
+        let index = |name: &str, column: &str| TableIndex {
+            name: name.to_string(),
+            columns: vec![IndexColumn::new(column)],
+            unique: true,
+            primary: false,
+            method: None,
+        };
+        let columns = vec![column("nickname", true), column("email", false)];
+        let indexes = vec![
+            index("nickname_key", "nickname"),
+            index("email_key", "email"),
+        ];
+        let page = TableDataPage {
+            columns: vec![
+                ResultColumn::new("nickname", "text"),
+                ResultColumn::new("email", "text"),
+            ],
+            rows: vec![vec![CellValue::Null, text("ada@example.com")]],
+            total: 1,
+            total_estimated: false,
+            primary_key: vec![],
+            unique_key: TableIndex::unique_key(&indexes, &columns),
+        };
+        assert_eq!(page.key_columns(), ["email".to_string()]);
+
+        let delete = RowChange::Delete {
+            key: page.row_key(0).unwrap(),
+        };
+        let statement = statements(&[delete], DatabaseType::Postgre).remove(0);
+        assert_eq!(statement.sql, r#"DELETE FROM t WHERE "email" = $1"#);
+        assert_eq!(
+            statement.params,
+            vec![QueryParam::Text("ada@example.com".to_string())]
+        );
+    }
+}
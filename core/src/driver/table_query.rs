@@ -0,0 +1,440 @@
+use crate::{
+    database::DatabaseType,
+    driver::DBError,
+    model::{
+        query::QueryParam,
+        table::{Filter, SortDirection, TableColumn, TableQuery},
+        value::ColumnKind,
+    },
+};
+
+/// Quote an identifier for the dialect of `db_type`, doubling embedded quotes
+pub(crate) fn quote_ident(ident: &str, db_type: DatabaseType) -> String {
+    match db_type {
+        DatabaseType::MySql | DatabaseType::MariaDB => format!("`{}`", ident.replace('`', "``")),
+        DatabaseType::MicrosoftSQLServer => format!("[{}]", ident.replace(']', "]]")),
+        _ => format!("\"{}\"", ident.replace('"', "\"\"")),
+    }
+}
+
+/// Placeholder of the `index`th parameter of a statement, counting from 1
+pub(crate) fn placeholder(index: usize, db_type: DatabaseType) -> String {
+    match db_type {
+        DatabaseType::Postgre => format!("${}", index),
+        DatabaseType::MicrosoftSQLServer => format!("@P{}", index),
+        _ => "?".to_string(),
+    }
+}
+
+/// `WHERE` and `ORDER BY` clauses of a [`TableQuery`], with the values of the filter
+/// collected as parameters. Column names are checked against the table's columns
+/// and quoted, so neither names nor values are ever spliced into the statement.
+pub(crate) struct CompiledQuery<'a> {
+    db_type: DatabaseType,
+    columns: &'a [TableColumn],
+    /// ` WHERE ...` of the filter alone, for counting the matching rows
+    pub where_sql: String,
+    /// ` WHERE ...` of the rows to fetch, the filter plus the keyset seek
+    pub rows_where_sql: String,
+    /// ` ORDER BY ...`, empty when there are neither sort columns nor a primary key
+    pub order_sql: String,
+    pub params: Vec<QueryParam>,
+    /// Number of leading `params` bound by the filter
+    filter_params: usize,
+    /// Rows to skip, none when seeking past a key
+    pub offset: u64,
+}
+
+impl<'a> CompiledQuery<'a> {
+    pub fn new(
+        query: &TableQuery,
+        columns: &'a [TableColumn],
+        db_type: DatabaseType,
+    ) -> Result<Self, DBError> {
+        let mut compiled = Self {
+            db_type,
+            columns,
+            where_sql: String::new(),
+            rows_where_sql: String::new(),
+            order_sql: String::new(),
+            params: Vec::new(),
+            filter_params: 0,
+            offset: query.offset,
+        };
+        if let Some(filter) = &query.filter {
+            let condition = compiled.condition(filter)?;
+            compiled.where_sql = format!(" WHERE {}", condition);
+        }
+        compiled.filter_params = compiled.params.len();
+        compiled.rows_where_sql = compiled.where_sql.clone();
+
+        let primary_key = TableColumn::primary_key(columns);
+        if let Some(after) = &query.after {
+            if !query.order_by.is_empty() {
+                return Err(DBError::QueryError(
+                    "Keyset paging only follows the primary key order".to_string(),
+                ));
+            }
+            if primary_key.is_empty() || primary_key.len() != after.len() {
+                return Err(DBError::QueryError(
+                    "Keyset paging needs a value for each primary key column".to_string(),
+                ));
+            }
+            let seek = compiled.seek(&primary_key, after)?;
+            compiled.rows_where_sql = if compiled.where_sql.is_empty() {
+                format!(" WHERE {}", seek)
+            } else {
+                format!("{} AND {}", compiled.where_sql, seek)
+            };
+            compiled.offset = 0;
+        }
+
+        let keys = if query.order_by.is_empty() {
+            primary_key
+                .iter()
+                .map(|key| format!("{} ASC", quote_ident(key, db_type)))
+                .collect()
+        } else {
+            query
+                .order_by
+                .iter()
+                .map(|order| {
+                    let column = compiled.column(&order.column)?;
+                    let direction = match order.direction {
+                        SortDirection::Asc => "ASC",
+                        SortDirection::Desc => "DESC",
+                    };
+                    Ok(format!(
+                        "{} {}",
+                        quote_ident(&column.name, db_type),
+                        direction
+                    ))
+                })
+                .collect::<Result<Vec<_>, DBError>>()?
+        };
+        if !keys.is_empty() {
+            compiled.order_sql = format!(" ORDER BY {}", keys.join(", "));
+        }
+        Ok(compiled)
+    }
+
+    /// Parameters of `where_sql`
+    pub fn count_params(&self) -> &[QueryParam] {
+        &self.params[..self.filter_params]
+    }
+
+    /// Add a parameter and return its placeholder
+    pub fn bind(&mut self, value: QueryParam) -> String {
+        self.params.push(value);
+        placeholder(self.params.len(), self.db_type)
+    }
+
+    /// Look a column up by name, falling back to a case-insensitive match
+    fn column(&self, name: &str) -> Result<&'a TableColumn, DBError> {
+        let columns = self.columns;
+        columns
+            .iter()
+            .find(|c| c.name == name)
+            .or_else(|| columns.iter().find(|c| c.name.eq_ignore_ascii_case(name)))
+            .ok_or_else(|| DBError::QueryError(format!("Unknown column: {}", name)))
+    }
+
+    fn condition(&mut self, filter: &Filter) -> Result<String, DBError> {
+        let sql = match filter {
+            Filter::Eq(column, QueryParam::Null) | Filter::IsNull(column) => {
+                format!("{} IS NULL", self.operand(column, false)?)
+            }
+            Filter::NotEq(column, QueryParam::Null) | Filter::IsNotNull(column) => {
+                format!("{} IS NOT NULL", self.operand(column, false)?)
+            }
+            Filter::Eq(column, value) => self.comparison(column, "=", &[value])?,
+            Filter::NotEq(column, value) => self.comparison(column, "<>", &[value])?,
+            Filter::Like(column, pattern) => {
+                let operand = self.operand(column, true)?;
+                let placeholder = self.bind(QueryParam::Text(pattern.clone()));
+                format!("{} LIKE {}", operand, placeholder)
+            }
+            Filter::In(_, values) if values.is_empty() => "1 = 0".to_string(),
+            Filter::In(column, values) => {
+                self.comparison(column, "IN", &values.iter().collect::<Vec<_>>())?
+            }
+            Filter::Between(column, low, high) => {
+                self.comparison(column, "BETWEEN", &[low, high])?
+            }
+            Filter::And(filters) => self.group(filters, "AND", "1 = 1")?,
+            Filter::Or(filters) => self.group(filters, "OR", "1 = 0")?,
+        };
+        Ok(sql)
+    }
+
+    /// Rows whose key sorts after `values`, spelled out as
+    /// `(a > ?) OR (a = ? AND b > ?)` since SQL Server has no row value comparison
+    fn seek(&mut self, keys: &[String], values: &[QueryParam]) -> Result<String, DBError> {
+        let mut branches = Vec::new();
+        for idx in 0..keys.len() {
+            let mut terms = keys[..idx]
+                .iter()
+                .zip(values)
+                .map(|(key, value)| self.comparison(key, "=", &[value]))
+                .collect::<Result<Vec<_>, DBError>>()?;
+            terms.push(self.comparison(&keys[idx], ">", &[&values[idx]])?);
+            branches.push(format!("({})", terms.join(" AND ")));
+        }
+        Ok(format!("({})", branches.join(" OR ")))
+    }
+
+    fn group(&mut self, filters: &[Filter], joiner: &str, empty: &str) -> Result<String, DBError> {
+        if filters.is_empty() {
+            return Ok(empty.to_string());
+        }
+        let parts = filters
+            .iter()
+            .map(|filter| self.condition(filter))
+            .collect::<Result<Vec<_>, DBError>>()?;
+        Ok(format!("({})", parts.join(&format!(" {} ", joiner))))
+    }
+
+    /// Compare a column to values, which are matched to the column's type when they
+    /// don't fit it, e.g. a number typed into the filter of a text column
+    fn comparison(
+        &mut self,
+        column: &str,
+        operator: &str,
+        values: &[&QueryParam],
+    ) -> Result<String, DBError> {
+        let kind = ColumnKind::from_type_name(&self.column(column)?.data_type);
+        let values = values
+            .iter()
+            .map(|value| Self::coerce(kind, value))
+            .collect::<Vec<_>>();
+        let as_text = values.iter().any(|v| matches!(v, QueryParam::Text(_)));
+        let operand = self.operand(column, as_text)?;
+        let placeholders = values
+            .into_iter()
+            .map(|value| self.bind(value))
+            .collect::<Vec<_>>();
+        Ok(match operator {
+            "IN" => format!("{} IN ({})", operand, placeholders.join(", ")),
+            "BETWEEN" => format!(
+                "{} BETWEEN {} AND {}",
+                operand, placeholders[0], placeholders[1]
+            ),
+            _ => format!("{} {} {}", operand, operator, placeholders[0]),
+        })
+    }
+
+    /// Quoted column, PostgreSQL has no implicit casts to text so the column is cast
+    /// when it is compared to text
+    fn operand(&self, column: &str, as_text: bool) -> Result<String, DBError> {
+        let ident = quote_ident(&self.column(column)?.name, self.db_type);
+        if as_text && self.db_type == DatabaseType::Postgre {
+            Ok(format!("{}::text", ident))
+        } else {
+            Ok(ident)
+        }
+    }
+
+    fn coerce(kind: ColumnKind, value: &QueryParam) -> QueryParam {
+        match (kind, value) {
+            (_, QueryParam::Null | QueryParam::Bytes(_)) => value.clone(),
+            (
+                ColumnKind::Integer | ColumnKind::Float | ColumnKind::Decimal,
+                QueryParam::Bool(b),
+            ) => QueryParam::Int(*b as i64),
+            (
+                ColumnKind::Integer | ColumnKind::Float | ColumnKind::Decimal,
+                QueryParam::Int(_) | QueryParam::Float(_),
+            )
+            | (ColumnKind::Bool, QueryParam::Bool(_)) => value.clone(),
+            (ColumnKind::Bool, QueryParam::Int(n)) => QueryParam::Bool(*n != 0),
+            (_, QueryParam::Bool(b)) => QueryParam::Text(b.to_string()),
+            (_, QueryParam::Int(n)) => QueryParam::Text(n.to_string()),
+            (_, QueryParam::Float(f)) => QueryParam::Text(f.to_string()),
+            (_, QueryParam::Text(_)) => value.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::table::OrderBy, sql::parse_filter};
+
+    fn column(name: &str, data_type: &str, primary_key: Option<u32>) -> TableColumn {
+        TableColumn {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: primary_key.is_none(),
+            default: None,
+            primary_key,
+        }
+    }
+
+    fn columns() -> Vec<TableColumn> {
+        vec![
+            column("tenant", "int", Some(1)),
+            column("id", "bigint", Some(2)),
+            column("Name", "varchar(50)", None),
+            column("active", "boolean", None),
+        ]
+    }
+
+    fn filtered(filter: &str) -> TableQuery {
+        TableQuery {
+            filter: Some(parse_filter(filter).unwrap()),
+            ..TableQuery::new(0, 100)
+        }
+    }
+
+    #[test]
+    fn filter_values_are_bound() {
+        let columns = columns();
+        let query = filtered("name = 'x' AND (id IN (1, 2) OR active IS NULL)");
+        let compiled = CompiledQuery::new(&query, &columns, DatabaseType::Postgre).unwrap();
+        assert_eq!(
+            compiled.where_sql,
+            r#" WHERE ("Name"::text = $1 AND ("id" IN ($2, $3) OR "active" IS NULL))"#
+        );
+        assert_eq!(compiled.rows_where_sql, compiled.where_sql);
+        assert_eq!(
+            compiled.params,
+            vec![
+                QueryParam::Text("x".to_string()),
+                QueryParam::Int(1),
+                QueryParam::Int(2)
+            ]
+        );
+        assert_eq!(compiled.count_params().len(), 3);
+    }
+
+    #[test]
+    fn placeholders_and_quotes_follow_the_dialect() {
+        let columns = columns();
+        let query = filtered("Name LIKE 'a%' AND id BETWEEN 1 AND 9");
+        let mysql = CompiledQuery::new(&query, &columns, DatabaseType::MySql).unwrap();
+        assert_eq!(
+            mysql.where_sql,
+            " WHERE (`Name` LIKE ? AND `id` BETWEEN ? AND ?)"
+        );
+        let mssql = CompiledQuery::new(&query, &columns, DatabaseType::MicrosoftSQLServer).unwrap();
+        assert_eq!(
+            mssql.where_sql,
+            " WHERE ([Name] LIKE @P1 AND [id] BETWEEN @P2 AND @P3)"
+        );
+        let postgres = CompiledQuery::new(&query, &columns, DatabaseType::Postgre).unwrap();
+        assert_eq!(
+            postgres.where_sql,
+            r#" WHERE ("Name"::text LIKE $1 AND "id" BETWEEN $2 AND $3)"#
+        );
+    }
+
+    #[test]
+    fn values_are_matched_to_the_column_type() {
+        let columns = columns();
+        let query = filtered("Name = 5 AND active = 1 AND id = true");
+        let compiled = CompiledQuery::new(&query, &columns, DatabaseType::Postgre).unwrap();
+        assert_eq!(
+            compiled.where_sql,
+            r#" WHERE ("Name"::text = $1 AND "active" = $2 AND "id" = $3)"#
+        );
+        assert_eq!(
+            compiled.params,
+            vec![
+                QueryParam::Text("5".to_string()),
+                QueryParam::Bool(true),
+                QueryParam::Int(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_columns_are_refused() {
+        let columns = columns();
+        let query = filtered("missing = 1");
+        assert!(CompiledQuery::new(&query, &columns, DatabaseType::Postgre).is_err());
+
+        let query = TableQuery {
+            order_by: vec![OrderBy {
+                column: "\"; DROP TABLE t; --".to_string(),
+                direction: SortDirection::Asc,
+            }],
+            ..TableQuery::new(0, 100)
+        };
+        assert!(CompiledQuery::new(&query, &columns, DatabaseType::Postgre).is_err());
+    }
+
+    #[test]
+    fn rows_are_ordered_by_sort_columns_or_primary_key() {
+        let columns = columns();
+        let compiled =
+            CompiledQuery::new(&TableQuery::new(0, 100), &columns, DatabaseType::MySql).unwrap();
+        assert_eq!(compiled.order_sql, " ORDER BY `tenant` ASC, `id` ASC");
+
+        let query = TableQuery {
+            order_by: vec![OrderBy {
+                column: "name".to_string(),
+                direction: SortDirection::Desc,
+            }],
+            ..TableQuery::new(0, 100)
+        };
+        let compiled = CompiledQuery::new(&query, &columns, DatabaseType::MySql).unwrap();
+        assert_eq!(compiled.order_sql, " ORDER BY `Name` DESC");
+
+        let keyless = vec![column("a", "int", None)];
+        let compiled =
+            CompiledQuery::new(&TableQuery::new(0, 100), &keyless, DatabaseType::MySql).unwrap();
+        assert!(compiled.order_sql.is_empty());
+    }
+
+    #[test]
+    fn keyset_seek_after_the_last_key() {
+        let columns = columns();
+        let query = TableQuery {
+            after: Some(vec![QueryParam::Int(7), QueryParam::Int(42)]),
+            offset: 500,
+            ..filtered("active = true")
+        };
+        let compiled =
+            CompiledQuery::new(&query, &columns, DatabaseType::MicrosoftSQLServer).unwrap();
+        assert_eq!(compiled.where_sql, " WHERE [active] = @P1");
+        assert_eq!(
+            compiled.rows_where_sql,
+            " WHERE [active] = @P1 AND (([tenant] > @P2) OR ([tenant] = @P3 AND [id] > @P4))"
+        );
+        assert_eq!(compiled.count_params(), &[QueryParam::Bool(true)]);
+        assert_eq!(compiled.params.len(), 4);
+        assert_eq!(compiled.offset, 0);
+    }
+
+    #[test]
+    fn keyset_seek_needs_the_whole_key_in_key_order() {
+        let columns = columns();
+        let query = TableQuery {
+            after: Some(vec![QueryParam::Int(7)]),
+            ..TableQuery::new(0, 100)
+        };
+        assert!(CompiledQuery::new(&query, &columns, DatabaseType::Postgre).is_err());
+
+        let query = TableQuery {
+            after: Some(vec![QueryParam::Int(7), QueryParam::Int(42)]),
+            order_by: vec![OrderBy {
+                column: "id".to_string(),
+                direction: SortDirection::Asc,
+            }],
+            ..TableQuery::new(0, 100)
+        };
+        assert!(CompiledQuery::new(&query, &columns, DatabaseType::Postgre).is_err());
+    }
+
+    #[test]
+    fn empty_groups() {
+        let columns = columns();
+        let query = TableQuery {
+            filter: Some(Filter::In("id".to_string(), Vec::new())),
+            ..TableQuery::new(0, 100)
+        };
+        let compiled = CompiledQuery::new(&query, &columns, DatabaseType::Sqlite).unwrap();
+        assert_eq!(compiled.where_sql, " WHERE 1 = 0");
+        assert!(compiled.params.is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use sqlx::{pool::PoolConnection, Database, Executor, Pool};
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+
+use crate::driver::DBError;
+
+/// Connection taken from the pool for an explicit transaction. Statements run on it
+/// until the transaction is committed or rolled back, then it goes back to the pool.
+pub(crate) struct PinnedTransaction<DB: Database> {
+    conn: Mutex<Option<PoolConnection<DB>>>,
+    /// Kept apart from `conn` so it can be read while a statement holds the lock
+    open: AtomicBool,
+}
+
+impl<DB: Database> PinnedTransaction<DB>
+where
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    pub fn new() -> Self {
+        Self {
+            conn: Mutex::new(None),
+            open: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open.load(Ordering::SeqCst)
+    }
+
+    pub async fn begin(&self, pool: &Pool<DB>, sql: &str) -> Result<(), DBError> {
+        let mut pinned = self.conn.lock().await;
+        if pinned.is_some() {
+            return Err(DBError::TransactionError(
+                "A transaction is already open".to_string(),
+            ));
+        }
+        let mut conn = pool.acquire().await?;
+        (&mut *conn).execute(sql).await?;
+        *pinned = Some(conn);
+        self.open.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// End the transaction with `COMMIT` or `ROLLBACK`. A connection that fails to end
+    /// it is closed rather than returned to the pool mid-transaction.
+    pub async fn finish(&self, sql: &str) -> Result<(), DBError> {
+        let Some(mut conn) = self.conn.lock().await.take() else {
+            return Err(DBError::TransactionError(
+                "No transaction is open".to_string(),
+            ));
+        };
+        self.open.store(false, Ordering::SeqCst);
+        if let Err(e) = (&mut *conn).execute(sql).await {
+            let _ = conn.close().await;
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Connection for the next statements: the pinned one while a transaction is open,
+    /// otherwise one from the pool
+    pub async fn session(&self, pool: &Pool<DB>) -> Result<Session<'_, DB>, DBError> {
+        match MutexGuard::try_map(self.conn.lock().await, Option::as_mut) {
+            Ok(conn) => Ok(Session::Pinned(conn)),
+            Err(unpinned) => {
+                drop(unpinned);
+                Ok(Session::Pooled(pool.acquire().await?))
+            }
+        }
+    }
+}
+
+pub(crate) enum Session<'a, DB: Database> {
+    /// Holds the lock, so statements of other callers wait until these are done
+    Pinned(MappedMutexGuard<'a, PoolConnection<DB>>),
+    Pooled(PoolConnection<DB>),
+}
+
+impl<DB: Database> Session<'_, DB> {
+    /// Close the connection instead of returning it to the pool, so session state set on
+    /// it doesn't leak to later callers. A pinned one is closed when its transaction ends.
+    pub fn close_on_drop(&mut self) {
+        match self {
+            Session::Pinned(conn) => conn.close_on_drop(),
+            Session::Pooled(conn) => conn.close_on_drop(),
+        }
+    }
+}
+
+impl<DB: Database> Deref for Session<'_, DB> {
+    type Target = DB::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Session::Pinned(conn) => conn,
+            Session::Pooled(conn) => conn,
+        }
+    }
+}
+
+impl<DB: Database> DerefMut for Session<'_, DB> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Session::Pinned(conn) => conn,
+            Session::Pooled(conn) => conn,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ConnectionConfig;
+use crate::{ConnectionConfig, ObjectRef};
 
 /// Event emitted when active connections list changes
 #[derive(Debug, Clone)]
@@ -12,8 +12,21 @@ pub struct SelectedConnectionChanged {
     pub id: Option<uuid::Uuid>,
 }
 
-/// Event emitted when selected table changes
+/// Event emitted when selected table or view changes
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SelectedTableChanged {
-    pub table_name: String,
+    pub object: ObjectRef,
+}
+
+/// Event emitted when the definition of a database object is requested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectDdlRequested {
+    pub object: ObjectRef,
+}
+
+/// Event emitted when selected key of a key-value store changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectedKeyChanged {
+    pub schema: String,
+    pub key: String,
 }
@@ -1,13 +1,37 @@
 // Unified Database Interface Layer
+pub mod completion;
 mod database;
 mod db_config;
 mod db_manager;
 mod driver;
 pub mod events;
+mod metadata;
 mod model;
+pub mod sql;
 
 pub use database::{ConnectionConfig, DatabaseType, Endpoint};
 pub use db_config::DBConfig;
 pub use db_manager::DBManager;
-pub use driver::{DBError, DatabaseDriver, MySqlDriver};
-pub use model::table::{TableDataPage, TableInfo};
+pub use driver::{
+    CancelHandle, DBError, DatabaseDriver, DocumentBrowser, DriverCapabilities, DriverFactory,
+    KeyValueBrowser, MongoDriver, MsSqlDriver, MySqlDriver, MySqlFlavor, PostgresDriver,
+    QueryCanceller, RedisDriver, SchemaBrowser, SqlExecutor, SqliteDriver, TableDesigner,
+    TableEditor, Transactional, SQLITE_MEMORY_PATH,
+};
+pub use metadata::MetadataCache;
+pub use model::design::{AlterStatement, ColumnDesign, TableDesign};
+pub use model::document::DocumentPage;
+pub use model::key_value::{KeyEntry, KeyNode, KeyType, KeyValue};
+pub use model::query::{
+    BoundStatement, ExecSummary, QueryParam, QueryResult, ResultColumn, ResultSet, StatementResult,
+};
+pub use model::schema::{ObjectKind, ObjectRef};
+pub use model::structure::{
+    CheckConstraint, ColumnDetail, ForeignKey, IndexColumn, TableIndex, TableOptions,
+    TableStructure, TableTrigger,
+};
+pub use model::table::{
+    Filter, OrderBy, RowChange, RowCount, SortDirection, TableColumn, TableDataPage, TableInfo,
+    TableQuery,
+};
+pub use model::value::{CellValue, ColumnKind};
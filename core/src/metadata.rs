@@ -0,0 +1,140 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    driver::{DBError, SchemaBrowser},
+    model::table::{TableColumn, TableInfo},
+};
+
+/// Schemas, tables and columns of one connection, each listed on first use
+#[derive(Debug, Default)]
+struct ConnectionMetadata {
+    /// Bumped by `invalidate`, so a list started before it isn't cached after it
+    generation: u64,
+    schemas: Option<Vec<String>>,
+    tables: HashMap<String, Vec<TableInfo>>,
+    columns: HashMap<(String, String), Vec<TableColumn>>,
+}
+
+/// Names of the schema objects of each connection, for completion. Filled from the
+/// schema browser as they are asked for, and dropped with `invalidate` once the schema
+/// of the connection changed.
+#[derive(Clone, Default)]
+pub struct MetadataCache {
+    connections: Arc<RwLock<HashMap<Uuid, ConnectionMetadata>>>,
+}
+
+impl MetadataCache {
+    /// Generation of the connection's metadata, with what `get` finds of it cached
+    async fn cached<T>(
+        &self,
+        connection_id: Uuid,
+        get: impl FnOnce(&ConnectionMetadata) -> Option<T>,
+    ) -> (u64, Option<T>) {
+        match self.connections.read().await.get(&connection_id) {
+            Some(metadata) => (metadata.generation, get(metadata)),
+            None => (0, None),
+        }
+    }
+
+    /// Cache a list with `fill`, unless the connection was invalidated since its
+    /// `generation` was read
+    async fn fill(
+        &self,
+        connection_id: Uuid,
+        generation: u64,
+        fill: impl FnOnce(&mut ConnectionMetadata),
+    ) {
+        let mut connections = self.connections.write().await;
+        let metadata = connections.entry(connection_id).or_default();
+        if metadata.generation == generation {
+            fill(metadata);
+        }
+    }
+
+    pub async fn schemas(
+        &self,
+        connection_id: Uuid,
+        browser: &dyn SchemaBrowser,
+    ) -> Result<Vec<String>, DBError> {
+        let (generation, cached) = self
+            .cached(connection_id, |metadata| metadata.schemas.clone())
+            .await;
+        if let Some(schemas) = cached {
+            return Ok(schemas);
+        }
+
+        let schemas: Vec<String> = browser
+            .list_schemas()
+            .await?
+            .into_iter()
+            .map(|schema| schema.name)
+            .collect();
+        self.fill(connection_id, generation, |metadata| {
+            metadata.schemas = Some(schemas.clone())
+        })
+        .await;
+        Ok(schemas)
+    }
+
+    /// Tables and views of a schema
+    pub async fn tables(
+        &self,
+        connection_id: Uuid,
+        browser: &dyn SchemaBrowser,
+        schema: &str,
+    ) -> Result<Vec<TableInfo>, DBError> {
+        let (generation, cached) = self
+            .cached(connection_id, |metadata| {
+                metadata.tables.get(schema).cloned()
+            })
+            .await;
+        if let Some(tables) = cached {
+            return Ok(tables);
+        }
+
+        let tables = browser.list_tables(schema).await?;
+        self.fill(connection_id, generation, |metadata| {
+            metadata.tables.insert(schema.to_string(), tables.clone());
+        })
+        .await;
+        Ok(tables)
+    }
+
+    pub async fn columns(
+        &self,
+        connection_id: Uuid,
+        browser: &dyn SchemaBrowser,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableColumn>, DBError> {
+        let key = (schema.to_string(), table.to_string());
+        let (generation, cached) = self
+            .cached(connection_id, |metadata| {
+                metadata.columns.get(&key).cloned()
+            })
+            .await;
+        if let Some(columns) = cached {
+            return Ok(columns);
+        }
+
+        let columns = browser.get_table_columns(schema, table).await?;
+        self.fill(connection_id, generation, |metadata| {
+            metadata.columns.insert(key, columns.clone());
+        })
+        .await;
+        Ok(columns)
+    }
+
+    /// Forget what was listed of a connection, it is listed again when next asked for
+    pub async fn invalidate(&self, connection_id: &Uuid) {
+        let mut connections = self.connections.write().await;
+        let metadata = connections.entry(*connection_id).or_default();
+        *metadata = ConnectionMetadata {
+            generation: metadata.generation + 1,
+            ..ConnectionMetadata::default()
+        };
+    }
+}
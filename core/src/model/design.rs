@@ -0,0 +1,64 @@
+use crate::model::{
+    structure::{ForeignKey, TableIndex},
+    table::TableColumn,
+};
+
+/// Desired state of a table in the designer. Columns of the table missing from
+/// `columns` are dropped, `indexes` and `foreign_keys` are added to the existing ones.
+#[derive(Debug, Clone, Default)]
+pub struct TableDesign {
+    pub columns: Vec<ColumnDesign>,
+    pub indexes: Vec<TableIndex>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+impl TableDesign {
+    /// Design of a table left as it is
+    pub fn new(columns: &[TableColumn]) -> Self {
+        Self {
+            columns: columns.iter().map(ColumnDesign::from).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnDesign {
+    /// Name of the column in the table, `None` for a column to add
+    pub original: Option<String>,
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    /// SQL expression, e.g. `'draft'` or `0`
+    pub default: Option<String>,
+}
+
+impl From<&TableColumn> for ColumnDesign {
+    fn from(column: &TableColumn) -> Self {
+        Self {
+            original: Some(column.name.clone()),
+            name: column.name.clone(),
+            data_type: column.data_type.clone(),
+            nullable: column.nullable,
+            default: column.default.clone(),
+        }
+    }
+}
+
+/// Statement of a table migration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlterStatement {
+    pub sql: String,
+    /// Whether MySQL can run it with `ALGORITHM=INSTANT`, changing only metadata
+    /// instead of rebuilding the table. `None` for other engines.
+    pub instant: Option<bool>,
+}
+
+impl AlterStatement {
+    pub fn new(sql: impl Into<String>) -> Self {
+        Self {
+            sql: sql.into(),
+            instant: None,
+        }
+    }
+}
@@ -0,0 +1,6 @@
+/// A page of raw documents, each rendered as pretty-printed JSON
+#[derive(Debug, Clone)]
+pub struct DocumentPage {
+    pub documents: Vec<String>,
+    pub total: u64,
+}
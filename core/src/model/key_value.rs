@@ -0,0 +1,141 @@
+use std::fmt::Display;
+
+use crate::model::{query::ResultColumn, table::TableDataPage, value::CellValue};
+
+/// Separator used to group keys into a prefix tree, e.g. `user:1:name`
+pub const KEY_SEPARATOR: char = ':';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    String,
+    Hash,
+    List,
+    Set,
+    ZSet,
+    Stream,
+    Unknown,
+}
+
+impl KeyType {
+    /// Parse the reply of the Redis `TYPE` command
+    pub fn from_type_name(name: &str) -> Self {
+        match name {
+            "string" => KeyType::String,
+            "hash" => KeyType::Hash,
+            "list" => KeyType::List,
+            "set" => KeyType::Set,
+            "zset" => KeyType::ZSet,
+            "stream" => KeyType::Stream,
+            _ => KeyType::Unknown,
+        }
+    }
+}
+
+impl Display for KeyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyType::String => "string",
+            KeyType::Hash => "hash",
+            KeyType::List => "list",
+            KeyType::Set => "set",
+            KeyType::ZSet => "zset",
+            KeyType::Stream => "stream",
+            KeyType::Unknown => "unknown",
+        }
+        .fmt(f)
+    }
+}
+
+/// A node of the key-space tree, either a shared prefix or a concrete key
+#[derive(Debug, Clone)]
+pub struct KeyNode {
+    /// Last segment shown in the tree
+    pub label: String,
+    /// Full prefix (ending with the separator) or full key name
+    pub key: String,
+    pub is_prefix: bool,
+    /// Number of keys found below a prefix, `1` for a key
+    pub key_count: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum KeyValue {
+    String(String),
+    Hash(Vec<(String, String)>),
+    List(Vec<String>),
+    Set(Vec<String>),
+    ZSet(Vec<(String, f64)>),
+    Stream(Vec<(String, Vec<(String, String)>)>),
+    /// The key does not exist (anymore)
+    Missing,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyEntry {
+    pub key: String,
+    pub key_type: KeyType,
+    /// Remaining time to live in seconds, `None` when the key never expires
+    pub ttl: Option<i64>,
+    pub value: KeyValue,
+}
+
+impl KeyEntry {
+    /// Lay the value out as rows so it can be shown in the table grid
+    pub fn to_page(&self) -> TableDataPage {
+        let text = |s: &String| CellValue::Text(s.clone());
+        // Column names with the type of their values
+        let (columns, rows): (Vec<(&str, &str)>, Vec<Vec<CellValue>>) = match &self.value {
+            KeyValue::String(s) => (vec![("value", "string")], vec![vec![text(s)]]),
+            KeyValue::Hash(fields) => (
+                vec![("field", "string"), ("value", "string")],
+                fields
+                    .iter()
+                    .map(|(field, value)| vec![text(field), text(value)])
+                    .collect(),
+            ),
+            KeyValue::List(items) => (
+                vec![("index", "integer"), ("value", "string")],
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, value)| vec![CellValue::UInt(idx as u64), text(value)])
+                    .collect(),
+            ),
+            KeyValue::Set(members) => (
+                vec![("member", "string")],
+                members.iter().map(|member| vec![text(member)]).collect(),
+            ),
+            KeyValue::ZSet(members) => (
+                vec![("member", "string"), ("score", "double")],
+                members
+                    .iter()
+                    .map(|(member, score)| vec![text(member), CellValue::Float(*score)])
+                    .collect(),
+            ),
+            KeyValue::Stream(entries) => (
+                vec![("id", "string"), ("field", "string"), ("value", "string")],
+                entries
+                    .iter()
+                    .flat_map(|(id, fields)| {
+                        fields
+                            .iter()
+                            .map(move |(field, value)| vec![text(id), text(field), text(value)])
+                    })
+                    .collect(),
+            ),
+            KeyValue::Missing => (vec![("value", "string")], vec![]),
+        };
+
+        TableDataPage {
+            columns: columns
+                .into_iter()
+                .map(|(name, data_type)| ResultColumn::new(name, data_type))
+                .collect(),
+            total: rows.len() as u64,
+            total_estimated: false,
+            primary_key: Vec::new(),
+            unique_key: Vec::new(),
+            rows,
+        }
+    }
+}
@@ -1,2 +1,8 @@
+pub mod design;
+pub mod document;
+pub mod key_value;
+pub mod query;
 pub mod schema;
+pub mod structure;
 pub mod table;
+pub mod value;
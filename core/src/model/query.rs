@@ -0,0 +1,103 @@
+use std::fmt::{self, Display};
+
+use crate::model::{
+    table::TableDataPage,
+    value::{CellValue, ColumnKind},
+};
+
+/// Value bound to a statement placeholder (`?`, `$1`, `@P1`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryParam {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+/// Written as a SQL literal, for showing the values of a statement next to it
+impl Display for QueryParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryParam::Null => write!(f, "NULL"),
+            QueryParam::Bool(b) => write!(f, "{}", b),
+            QueryParam::Int(n) => write!(f, "{}", n),
+            QueryParam::Float(n) => write!(f, "{}", n),
+            QueryParam::Text(s) => write!(f, "'{}'", s.replace('\'', "''")),
+            QueryParam::Bytes(bytes) => {
+                write!(f, "0x")?;
+                bytes.iter().try_for_each(|b| write!(f, "{:02X}", b))
+            }
+        }
+    }
+}
+
+/// Statement together with the values of its placeholders
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundStatement {
+    pub sql: String,
+    pub params: Vec<QueryParam>,
+}
+
+/// Column of a result set with the type name reported by the server
+#[derive(Debug, Clone)]
+pub struct ResultColumn {
+    pub name: String,
+    pub data_type: String,
+    pub kind: ColumnKind,
+}
+
+impl ResultColumn {
+    /// Column of a SQL type, classified by [`ColumnKind::from_type_name`]
+    pub fn new(name: impl Into<String>, data_type: impl Into<String>) -> Self {
+        let data_type = data_type.into();
+        Self {
+            name: name.into(),
+            kind: ColumnKind::from_type_name(&data_type),
+            data_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResultSet {
+    pub columns: Vec<ResultColumn>,
+    pub rows: Vec<Vec<CellValue>>,
+}
+
+impl ResultSet {
+    /// Lay the rows out for the table grid
+    pub fn to_page(&self) -> TableDataPage {
+        TableDataPage {
+            columns: self.columns.clone(),
+            rows: self.rows.clone(),
+            total: self.rows.len() as u64,
+            total_estimated: false,
+            primary_key: Vec::new(),
+            unique_key: Vec::new(),
+        }
+    }
+}
+
+/// Outcome of a statement that returns no rows
+#[derive(Debug, Clone, Default)]
+pub struct ExecSummary {
+    pub rows_affected: u64,
+    /// Generated key of an INSERT, when the backend reports one
+    pub last_insert_id: Option<u64>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum QueryResult {
+    Rows(ResultSet),
+    Affected(ExecSummary),
+}
+
+/// Result of one statement of an executed script
+#[derive(Debug, Clone)]
+pub struct StatementResult {
+    pub statement: String,
+    pub result: QueryResult,
+}
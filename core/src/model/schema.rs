@@ -1,3 +1,71 @@
+use std::fmt::{self, Display};
+
+use uuid::Uuid;
+
 pub struct DBSchema {
     pub name: String,
 }
+
+/// Kind of a schema object, as named in its `CREATE` statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectKind {
+    Table,
+    View,
+    Procedure,
+    Function,
+    Trigger,
+    /// Scheduled event of MySQL
+    Event,
+    /// Sequence of MariaDB
+    Sequence,
+}
+
+impl Display for ObjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectKind::Table => write!(f, "TABLE"),
+            ObjectKind::View => write!(f, "VIEW"),
+            ObjectKind::Procedure => write!(f, "PROCEDURE"),
+            ObjectKind::Function => write!(f, "FUNCTION"),
+            ObjectKind::Trigger => write!(f, "TRIGGER"),
+            ObjectKind::Event => write!(f, "EVENT"),
+            ObjectKind::Sequence => write!(f, "SEQUENCE"),
+        }
+    }
+}
+
+/// Fully qualified schema object of a connection
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ObjectRef {
+    pub connection_id: Uuid,
+    pub schema: String,
+    pub name: String,
+    pub kind: ObjectKind,
+}
+
+impl ObjectRef {
+    pub fn new(
+        connection_id: Uuid,
+        schema: impl Into<String>,
+        name: impl Into<String>,
+        kind: ObjectKind,
+    ) -> Self {
+        Self {
+            connection_id,
+            schema: schema.into(),
+            name: name.into(),
+            kind,
+        }
+    }
+
+    /// Whether the object has rows to browse
+    pub fn has_rows(&self) -> bool {
+        matches!(self.kind, ObjectKind::Table | ObjectKind::View)
+    }
+}
+
+impl Display for ObjectRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.schema, self.name)
+    }
+}
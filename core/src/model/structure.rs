@@ -0,0 +1,165 @@
+use crate::model::table::TableColumn;
+
+/// Definition of a table as shown by the structure view
+#[derive(Debug, Clone, Default)]
+pub struct TableStructure {
+    pub columns: Vec<ColumnDetail>,
+    pub indexes: Vec<TableIndex>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub checks: Vec<CheckConstraint>,
+    pub triggers: Vec<TableTrigger>,
+    pub options: TableOptions,
+}
+
+#[derive(Debug, Clone)]
+pub struct ColumnDetail {
+    pub column: TableColumn,
+    pub comment: Option<String>,
+    pub auto_increment: bool,
+    pub charset: Option<String>,
+    pub collation: Option<String>,
+}
+
+impl From<TableColumn> for ColumnDetail {
+    fn from(column: TableColumn) -> Self {
+        Self {
+            column,
+            comment: None,
+            auto_increment: false,
+            charset: None,
+            collation: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TableIndex {
+    pub name: String,
+    /// Key columns in index order
+    pub columns: Vec<IndexColumn>,
+    pub unique: bool,
+    pub primary: bool,
+    /// Access method or index type, e.g. BTREE, FULLTEXT, gin, CLUSTERED
+    pub method: Option<String>,
+}
+
+impl TableIndex {
+    pub fn is_composite(&self) -> bool {
+        self.columns.len() > 1
+    }
+
+    /// Columns of the first unique index made of NOT NULL columns only, which identify
+    /// rows as well as a primary key. Empty when there is none.
+    pub(crate) fn unique_key(indexes: &[TableIndex], columns: &[TableColumn]) -> Vec<String> {
+        indexes
+            .iter()
+            .filter(|index| index.unique && !index.primary)
+            .find(|index| {
+                index.columns.iter().all(|key| {
+                    columns
+                        .iter()
+                        .any(|column| column.name == key.name && !column.nullable)
+                })
+            })
+            .map(|index| index.columns.iter().map(|key| key.name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Group `(index, column)` rows ordered by index and then key position into indexes,
+    /// the columns of the first row of each index are ignored
+    pub(crate) fn group(rows: impl IntoIterator<Item = (TableIndex, IndexColumn)>) -> Vec<Self> {
+        let mut indexes: Vec<TableIndex> = Vec::new();
+        for (index, column) in rows {
+            match indexes.last_mut() {
+                Some(last) if last.name == index.name => last.columns.push(column),
+                _ => indexes.push(TableIndex {
+                    columns: vec![column],
+                    ..index
+                }),
+            }
+        }
+        indexes
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexColumn {
+    /// Column name, or the expression of an expression index
+    pub name: String,
+    /// Number of leading characters or bytes indexed, for prefix indexes
+    pub prefix_length: Option<u32>,
+    pub descending: bool,
+}
+
+impl IndexColumn {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            prefix_length: None,
+            descending: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ForeignKey {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub referenced_schema: String,
+    pub referenced_table: String,
+    /// Referenced column of each column, in the same order
+    pub referenced_columns: Vec<String>,
+    /// Referential actions, e.g. CASCADE or NO ACTION
+    pub on_update: String,
+    pub on_delete: String,
+}
+
+impl ForeignKey {
+    /// Group `(key, column, referenced column)` rows ordered by key and then position into
+    /// foreign keys, the columns of the first row of each key are ignored
+    pub(crate) fn group(rows: impl IntoIterator<Item = (ForeignKey, String, String)>) -> Vec<Self> {
+        let mut keys: Vec<ForeignKey> = Vec::new();
+        for (key, column, referenced) in rows {
+            match keys.last_mut() {
+                Some(last) if last.name == key.name => {
+                    last.columns.push(column);
+                    last.referenced_columns.push(referenced);
+                }
+                _ => keys.push(ForeignKey {
+                    columns: vec![column],
+                    referenced_columns: vec![referenced],
+                    ..key
+                }),
+            }
+        }
+        keys
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckConstraint {
+    pub name: String,
+    pub expression: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct TableTrigger {
+    pub name: String,
+    /// BEFORE, AFTER or INSTEAD OF
+    pub timing: String,
+    /// The statements firing the trigger, e.g. INSERT OR UPDATE
+    pub event: String,
+    /// Body of the trigger, or its whole definition where the engine has no separate body
+    pub statement: String,
+}
+
+/// Storage properties of a table, each one only where the engine has it
+#[derive(Debug, Clone, Default)]
+pub struct TableOptions {
+    pub engine: Option<String>,
+    pub row_format: Option<String>,
+    pub collation: Option<String>,
+    pub comment: Option<String>,
+    /// Data and indexes, in bytes
+    pub size_bytes: Option<u64>,
+}
@@ -1,19 +1,196 @@
+use crate::model::{
+    query::{QueryParam, ResultColumn},
+    value::CellValue,
+};
+
 #[derive(Debug, Clone)]
 pub struct TableInfo {
     pub name: String,
     pub table_type: String,
 }
 
+impl TableInfo {
+    /// Views and materialized views, which list along with the tables
+    pub fn is_view(&self) -> bool {
+        self.table_type.to_uppercase().contains("VIEW")
+    }
+
+    /// Sequences of MariaDB, which list along with the tables
+    pub fn is_sequence(&self) -> bool {
+        self.table_type.eq_ignore_ascii_case("SEQUENCE")
+    }
+
+    /// Tables of MariaDB keeping the history of their rows
+    pub fn is_system_versioned(&self) -> bool {
+        self.table_type.eq_ignore_ascii_case("SYSTEM VERSIONED")
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct TableColumn {
     pub name: String,
     pub data_type: String,
     pub nullable: bool,
     pub default: Option<String>,
+    /// Position of the column in the primary key, starting at 1
+    pub primary_key: Option<u32>,
+}
+
+impl TableColumn {
+    /// Names of the primary key columns, in key order
+    pub fn primary_key(columns: &[TableColumn]) -> Vec<String> {
+        let mut keys: Vec<&TableColumn> =
+            columns.iter().filter(|c| c.primary_key.is_some()).collect();
+        keys.sort_by_key(|c| c.primary_key);
+        keys.into_iter().map(|c| c.name.clone()).collect()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct TableDataPage {
-    pub columns: Vec<String>,
-    pub rows: Vec<Vec<String>>,
+    pub columns: Vec<ResultColumn>,
+    pub rows: Vec<Vec<CellValue>>,
     pub total: u64,
+    /// `total` comes from table statistics rather than counting the rows
+    pub total_estimated: bool,
+    /// Primary key columns, in key order, empty when the rows have no key
+    pub primary_key: Vec<String>,
+    /// Without a primary key, the columns of the first unique key whose columns are
+    /// all NOT NULL
+    pub unique_key: Vec<String>,
+}
+
+impl TableDataPage {
+    /// Columns identifying a row: the primary key, or else the unique key
+    pub fn key_columns(&self) -> &[String] {
+        if self.primary_key.is_empty() {
+            &self.unique_key
+        } else {
+            &self.primary_key
+        }
+    }
+
+    /// Key columns of a row with their values, `None` when the rows have no key
+    pub fn row_key(&self, row_ix: usize) -> Option<Vec<(String, CellValue)>> {
+        if self.key_columns().is_empty() {
+            return None;
+        }
+        let row = self.rows.get(row_ix)?;
+        self.key_columns()
+            .iter()
+            .map(|key| {
+                let idx = self.columns.iter().position(|c| &c.name == key)?;
+                Some((key.clone(), row.get(idx)?.clone()))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderBy {
+    pub column: String,
+    pub direction: SortDirection,
+}
+
+/// Row condition of a [`TableQuery`]. Values are always bound as parameters, never
+/// spliced into the statement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Eq(String, QueryParam),
+    NotEq(String, QueryParam),
+    /// SQL `LIKE` pattern, `%` and `_` are the wildcards
+    Like(String, String),
+    In(String, Vec<QueryParam>),
+    IsNull(String),
+    IsNotNull(String),
+    Between(String, QueryParam, QueryParam),
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+}
+
+impl Filter {
+    /// Every column the condition refers to
+    pub fn columns(&self) -> Vec<&str> {
+        match self {
+            Filter::Eq(column, _)
+            | Filter::NotEq(column, _)
+            | Filter::Like(column, _)
+            | Filter::In(column, _)
+            | Filter::IsNull(column)
+            | Filter::IsNotNull(column)
+            | Filter::Between(column, _, _) => vec![column.as_str()],
+            Filter::And(filters) | Filter::Or(filters) => {
+                filters.iter().flat_map(Filter::columns).collect()
+            }
+        }
+    }
+}
+
+/// How the `total` of a [`TableDataPage`] is computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RowCount {
+    /// Read from the table statistics, which is instant but may be off. Only the
+    /// whole table has statistics, so filtered rows are always counted.
+    #[default]
+    Estimated,
+    /// `COUNT(*)` of the matching rows, which scans the table
+    Exact,
+}
+
+/// Which rows of a table to fetch: filter, sort order and the page window
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableQuery {
+    pub filter: Option<Filter>,
+    /// Without sort columns rows are read in primary key order
+    pub order_by: Vec<OrderBy>,
+    pub offset: u64,
+    pub limit: u64,
+    /// Primary key of the last row already read. Rows then start right after it
+    /// instead of skipping `offset` rows, which stays fast however deep the page is.
+    /// Only valid in primary key order.
+    pub after: Option<Vec<QueryParam>>,
+    pub count: RowCount,
+}
+
+impl TableQuery {
+    pub fn new(offset: u64, limit: u64) -> Self {
+        Self {
+            filter: None,
+            order_by: Vec::new(),
+            offset,
+            limit,
+            after: None,
+            count: RowCount::default(),
+        }
+    }
+
+    /// Whether the total may come from table statistics
+    pub fn estimate_total(&self) -> bool {
+        self.count == RowCount::Estimated && self.filter.is_none()
+    }
+}
+
+/// Staged change to one row of a table. Existing rows are found again by their
+/// primary key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowChange {
+    Update {
+        /// Primary key columns with the values the row was read with
+        key: Vec<(String, CellValue)>,
+        /// Changed columns with their new values
+        values: Vec<(String, CellValue)>,
+    },
+    /// New row, columns left out take their default
+    Insert {
+        values: Vec<(String, CellValue)>,
+    },
+    Delete {
+        key: Vec<(String, CellValue)>,
+    },
 }
@@ -0,0 +1,205 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::model::query::QueryParam;
+
+/// Typed value of a result cell
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Null,
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    /// Exact numeric, kept as the server's text so that no digit is lost
+    Decimal(String),
+    Bool(bool),
+    Text(String),
+    Bytes(Vec<u8>),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(NaiveDateTime),
+    /// Timestamp carrying its offset from UTC
+    DateTimeTz(DateTime<FixedOffset>),
+    Json(JsonValue),
+    Uuid(Uuid),
+}
+
+impl CellValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, CellValue::Null)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            CellValue::Int(_) | CellValue::UInt(_) | CellValue::Float(_) | CellValue::Decimal(_)
+        )
+    }
+
+    /// Parse a value from its textual form, e.g. as sent by the PostgreSQL simple
+    /// query protocol. Text that doesn't fit `kind` is kept as is.
+    pub fn parse(text: &str, kind: ColumnKind) -> Self {
+        let value = match kind {
+            ColumnKind::Integer => text
+                .parse()
+                .map(CellValue::Int)
+                .or_else(|_| text.parse().map(CellValue::UInt))
+                .ok(),
+            ColumnKind::Float => text.parse().map(CellValue::Float).ok(),
+            ColumnKind::Decimal => Some(CellValue::Decimal(text.to_string())),
+            ColumnKind::Bool => match text.to_ascii_lowercase().as_str() {
+                "t" | "true" | "1" => Some(CellValue::Bool(true)),
+                "f" | "false" | "0" => Some(CellValue::Bool(false)),
+                _ => None,
+            },
+            ColumnKind::Bytes => text
+                .strip_prefix("\\x")
+                .or_else(|| text.strip_prefix("0x"))
+                .and_then(Self::parse_hex)
+                .map(CellValue::Bytes),
+            ColumnKind::Date => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .map(CellValue::Date)
+                .ok(),
+            ColumnKind::Time => NaiveTime::parse_from_str(text, "%H:%M:%S%.f")
+                .map(CellValue::Time)
+                .ok(),
+            ColumnKind::DateTime => DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f%#z")
+                .map(CellValue::DateTimeTz)
+                .or_else(|_| {
+                    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+                        .map(CellValue::DateTime)
+                })
+                .or_else(|_| {
+                    NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+                        .map(CellValue::DateTime)
+                })
+                .ok(),
+            ColumnKind::Json => serde_json::from_str(text).map(CellValue::Json).ok(),
+            ColumnKind::Uuid => Uuid::parse_str(text).map(CellValue::Uuid).ok(),
+            ColumnKind::Text => None,
+        };
+        value.unwrap_or_else(|| CellValue::Text(text.to_string()))
+    }
+
+    /// Bind the value back into a statement, e.g. to find its row again. Values
+    /// without a parameter type of their own are bound in their text form.
+    pub fn to_param(&self) -> QueryParam {
+        match self {
+            CellValue::Null => QueryParam::Null,
+            CellValue::Int(n) => QueryParam::Int(*n),
+            CellValue::UInt(n) => match i64::try_from(*n) {
+                Ok(n) => QueryParam::Int(n),
+                Err(_) => QueryParam::Text(n.to_string()),
+            },
+            CellValue::Float(f) => QueryParam::Float(*f),
+            CellValue::Bool(b) => QueryParam::Bool(*b),
+            CellValue::Text(s) => QueryParam::Text(s.clone()),
+            CellValue::Bytes(bytes) => QueryParam::Bytes(bytes.clone()),
+            other => QueryParam::Text(other.to_string()),
+        }
+    }
+
+    fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+        if !hex.len().is_multiple_of(2) {
+            return None;
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|idx| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok())
+            .collect()
+    }
+}
+
+impl Display for CellValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellValue::Null => write!(f, "NULL"),
+            CellValue::Int(n) => write!(f, "{}", n),
+            CellValue::UInt(n) => write!(f, "{}", n),
+            CellValue::Float(n) => write!(f, "{}", n),
+            CellValue::Decimal(s) | CellValue::Text(s) => write!(f, "{}", s),
+            CellValue::Bool(b) => write!(f, "{}", b),
+            CellValue::Bytes(bytes) => {
+                write!(f, "0x")?;
+                bytes.iter().try_for_each(|b| write!(f, "{:02X}", b))
+            }
+            CellValue::Date(d) => write!(f, "{}", d.format("%Y-%m-%d")),
+            CellValue::Time(t) => write!(f, "{}", t.format("%H:%M:%S%.f")),
+            CellValue::DateTime(dt) => write!(f, "{}", dt.format("%Y-%m-%d %H:%M:%S%.f")),
+            CellValue::DateTimeTz(dt) => write!(f, "{}", dt.format("%Y-%m-%d %H:%M:%S%.f%:z")),
+            CellValue::Json(json) => write!(f, "{}", json),
+            CellValue::Uuid(uuid) => write!(f, "{}", uuid),
+        }
+    }
+}
+
+impl From<String> for CellValue {
+    fn from(value: String) -> Self {
+        CellValue::Text(value)
+    }
+}
+
+/// Widen through the shortest decimal form, so that `0.1f32` stays `0.1`
+impl From<f32> for CellValue {
+    fn from(value: f32) -> Self {
+        CellValue::Float(value.to_string().parse().unwrap_or(f64::from(value)))
+    }
+}
+
+/// Broad type of a column, decides how its values are parsed and aligned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnKind {
+    Integer,
+    Float,
+    Decimal,
+    Bool,
+    #[default]
+    Text,
+    Bytes,
+    Date,
+    Time,
+    DateTime,
+    Json,
+    Uuid,
+}
+
+impl ColumnKind {
+    /// Classify a SQL type name, e.g. `BIGINT UNSIGNED`, `numeric(10,2)` or
+    /// `timestamp with time zone`. Unknown types are treated as text.
+    pub fn from_type_name(name: &str) -> Self {
+        let base = name
+            .trim()
+            .split(|c: char| c == '(' || c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        match base.as_str() {
+            "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "INTEGER" | "BIGINT" | "INT2"
+            | "INT4" | "INT8" | "SMALLSERIAL" | "SERIAL" | "BIGSERIAL" | "OID" | "YEAR" | "BIT" => {
+                ColumnKind::Integer
+            }
+            "FLOAT" | "FLOAT4" | "FLOAT8" | "DOUBLE" | "REAL" => ColumnKind::Float,
+            "DECIMAL" | "NUMERIC" | "MONEY" | "SMALLMONEY" => ColumnKind::Decimal,
+            "BOOL" | "BOOLEAN" => ColumnKind::Bool,
+            "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BYTEA"
+            | "IMAGE" => ColumnKind::Bytes,
+            "DATE" => ColumnKind::Date,
+            "TIME" | "TIMETZ" => ColumnKind::Time,
+            "DATETIME" | "DATETIME2" | "SMALLDATETIME" | "DATETIMEOFFSET" | "TIMESTAMP"
+            | "TIMESTAMPTZ" => ColumnKind::DateTime,
+            "JSON" | "JSONB" => ColumnKind::Json,
+            "UUID" | "UNIQUEIDENTIFIER" => ColumnKind::Uuid,
+            _ => ColumnKind::Text,
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            ColumnKind::Integer | ColumnKind::Float | ColumnKind::Decimal
+        )
+    }
+}
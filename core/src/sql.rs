@@ -0,0 +1,1329 @@
+use std::{fmt::Display, ops::Range};
+
+use sqlparser::{
+    ast::{CopyTarget, Query, SetExpr, Statement},
+    dialect::{
+        Dialect, GenericDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect,
+    },
+    keywords::Keyword,
+    parser::Parser,
+    tokenizer::{Location, Token, TokenWithSpan, Tokenizer, Whitespace, Word},
+};
+
+use crate::{
+    driver::DBError,
+    model::{query::QueryParam, table::Filter},
+    DatabaseType,
+};
+
+/// Dialect sqlparser reads the SQL of a database in
+fn dialect(db_type: DatabaseType) -> Box<dyn Dialect> {
+    match db_type {
+        DatabaseType::MySql | DatabaseType::MariaDB => Box::new(MySqlDialect {}),
+        DatabaseType::Postgre => Box::new(PostgreSqlDialect {}),
+        DatabaseType::Sqlite => Box::new(SQLiteDialect {}),
+        DatabaseType::MicrosoftSQLServer => Box::new(MsSqlDialect {}),
+        _ => Box::new(GenericDialect {}),
+    }
+}
+
+/// Byte ranges of the statements in `sql`, trimmed and without their delimiter.
+/// Delimiters inside literals, quoted names, comments and dollar-quoted bodies are not
+/// statement boundaries, nor are the ones inside `BEGIN ... END` bodies of SQLite
+/// triggers and T-SQL blocks. MySQL scripts may change the delimiter with `DELIMITER`,
+/// the way the mysql client does around routine bodies.
+pub fn statement_ranges(sql: &str, db_type: DatabaseType) -> Vec<Range<usize>> {
+    let dialect = dialect(db_type);
+    let delimiter_commands = matches!(db_type, DatabaseType::MySql | DatabaseType::MariaDB);
+    let blocks = matches!(
+        db_type,
+        DatabaseType::Sqlite | DatabaseType::MicrosoftSQLServer
+    );
+    let mut delimiter = ";";
+    let mut opaque = opaque_parts(sql, &*dialect, blocks).into_iter().peekable();
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    // Statements holding nothing but comments are dropped
+    let mut has_code = false;
+
+    while let Some(c) = sql[i..].chars().next() {
+        if let Some(part) = opaque.next_if(|part| part.range.start <= i) {
+            has_code |= part.code;
+            i = i.max(part.range.end);
+            continue;
+        }
+        if sql[i..].starts_with(delimiter) {
+            if has_code {
+                push_trimmed(sql, start..i, &mut ranges);
+            }
+            has_code = false;
+            i += delimiter.len();
+            start = i;
+            continue;
+        }
+        if delimiter_commands && !has_code {
+            if let Some((next, len)) = delimiter_command(&sql[i..]) {
+                delimiter = next;
+                i += len;
+                start = i;
+                while opaque.next_if(|part| part.range.start < i).is_some() {}
+                continue;
+            }
+        }
+        has_code |= !c.is_whitespace();
+        i += c.len_utf8();
+    }
+    if has_code {
+        push_trimmed(sql, start..sql.len(), &mut ranges);
+    }
+    ranges
+}
+
+/// The statements of `sql`, see [`statement_ranges`]
+pub fn split_statements(sql: &str, db_type: DatabaseType) -> Vec<&str> {
+    statement_ranges(sql, db_type)
+        .into_iter()
+        .map(|range| &sql[range])
+        .collect()
+}
+
+/// Range of the statement under the byte `offset` of `sql`. Between two statements
+/// the one before the offset wins, so a cursor right after a `;` picks the statement
+/// it terminates.
+pub fn statement_at(sql: &str, offset: usize, db_type: DatabaseType) -> Option<Range<usize>> {
+    let ranges = statement_ranges(sql, db_type);
+    ranges
+        .iter()
+        .rev()
+        .find(|range| range.start <= offset)
+        .or(ranges.first())
+        .cloned()
+}
+
+/// Part of a script a delimiter can't be found in
+struct OpaquePart {
+    range: Range<usize>,
+    /// Literals and quoted names are code, comments are not
+    code: bool,
+}
+
+/// Literals, quoted names and comments of `sql`, in order of their start. With
+/// `blocks`, also the outermost `BEGIN ... END` bodies, inside which `CASE ... END` is
+/// counted so its `END` doesn't close the body.
+fn opaque_parts(sql: &str, dialect: &dyn Dialect, blocks: bool) -> Vec<OpaquePart> {
+    let mut tokens = Vec::new();
+    let failed = Tokenizer::new(dialect, sql)
+        .tokenize_with_location_into_buf(&mut tokens)
+        .is_err();
+    let mut offsets = Offsets::new(sql);
+    let mut parts = Vec::new();
+    let mut bodies = Vec::new();
+    let mut depth = 0;
+    let mut body_start = 0;
+    for (ix, token) in tokens.iter().enumerate() {
+        if let Token::Word(Word {
+            keyword,
+            quote_style: None,
+            ..
+        }) = &token.token
+        {
+            let opens = match keyword {
+                Keyword::BEGIN => blocks && !begins_transaction(&tokens[ix + 1..]),
+                Keyword::CASE => depth > 0,
+                _ => false,
+            };
+            if opens {
+                if depth == 0 {
+                    body_start = offsets.of(token.span.start);
+                }
+                depth += 1;
+            } else if *keyword == Keyword::END && depth > 0 {
+                depth -= 1;
+                if depth == 0 {
+                    bodies.push(OpaquePart {
+                        range: body_start..offsets.of(token.span.end),
+                        code: true,
+                    });
+                }
+            }
+        }
+        let code = match &token.token {
+            Token::Whitespace(
+                Whitespace::SingleLineComment { .. } | Whitespace::MultiLineComment(_),
+            ) => false,
+            Token::Word(Word {
+                quote_style: Some(_),
+                ..
+            })
+            | Token::SingleQuotedString(_)
+            | Token::DoubleQuotedString(_)
+            | Token::TripleSingleQuotedString(_)
+            | Token::TripleDoubleQuotedString(_)
+            | Token::DollarQuotedString(_)
+            | Token::SingleQuotedByteStringLiteral(_)
+            | Token::DoubleQuotedByteStringLiteral(_)
+            | Token::TripleSingleQuotedByteStringLiteral(_)
+            | Token::TripleDoubleQuotedByteStringLiteral(_)
+            | Token::SingleQuotedRawStringLiteral(_)
+            | Token::DoubleQuotedRawStringLiteral(_)
+            | Token::TripleSingleQuotedRawStringLiteral(_)
+            | Token::TripleDoubleQuotedRawStringLiteral(_)
+            | Token::NationalStringLiteral(_)
+            | Token::EscapedStringLiteral(_)
+            | Token::UnicodeStringLiteral(_)
+            | Token::HexStringLiteral(_) => true,
+            _ => continue,
+        };
+        let start = offsets.of(token.span.start);
+        parts.push(OpaquePart {
+            range: start..offsets.of(token.span.end),
+            code,
+        });
+    }
+    // A body still being typed runs to the end
+    if depth > 0 {
+        bodies.push(OpaquePart {
+            range: body_start..sql.len(),
+            code: true,
+        });
+    }
+    // What the tokenizer could not read, e.g. an unterminated string, runs to the end
+    if failed {
+        let end = tokens.last().map_or(0, |token| offsets.of(token.span.end));
+        parts.push(OpaquePart {
+            range: end..sql.len(),
+            code: true,
+        });
+    }
+    parts.extend(bodies);
+    parts.sort_by_key(|part| part.range.start);
+    parts
+}
+
+/// Whether the `BEGIN` before `rest` starts a transaction rather than a block
+fn begins_transaction(rest: &[TokenWithSpan]) -> bool {
+    const TRANSACTION_WORDS: &[&str] = &[
+        "TRAN",
+        "TRANSACTION",
+        "DISTRIBUTED",
+        "WORK",
+        "DEFERRED",
+        "IMMEDIATE",
+        "EXCLUSIVE",
+        // Transaction modes of PostgreSQL
+        "ISOLATION",
+        "READ",
+        "NOT",
+        "DEFERRABLE",
+    ];
+    let next = rest
+        .iter()
+        .find(|token| !matches!(token.token, Token::Whitespace(_)));
+    match next.map(|token| &token.token) {
+        None | Some(Token::SemiColon) | Some(Token::EOF) => true,
+        Some(Token::Word(word)) => {
+            word.quote_style.is_none()
+                && TRANSACTION_WORDS
+                    .iter()
+                    .any(|transaction| word.value.eq_ignore_ascii_case(transaction))
+        }
+        _ => false,
+    }
+}
+
+/// Byte offsets of tokenizer locations, which count lines and characters from 1.
+/// Locations have to be asked for in order.
+struct Offsets<'a> {
+    sql: &'a str,
+    offset: usize,
+    line: u64,
+    column: u64,
+}
+
+impl<'a> Offsets<'a> {
+    fn new(sql: &'a str) -> Self {
+        Self {
+            sql,
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn of(&mut self, location: Location) -> usize {
+        while (self.line, self.column) < (location.line, location.column) {
+            let Some(c) = self.sql[self.offset..].chars().next() else {
+                break;
+            };
+            self.offset += c.len_utf8();
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.offset
+    }
+}
+
+/// `DELIMITER <delimiter>` at the start of `sql`, with the new delimiter and the
+/// length of the command line
+fn delimiter_command(sql: &str) -> Option<(&str, usize)> {
+    const COMMAND: &str = "DELIMITER";
+    if !sql.get(..COMMAND.len())?.eq_ignore_ascii_case(COMMAND) {
+        return None;
+    }
+    let line_end = sql.find('\n').map_or(sql.len(), |end| end + 1);
+    let rest = &sql[COMMAND.len()..line_end];
+    // Not a column or table that merely starts with the word
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let delimiter = rest.split_whitespace().next()?;
+    Some((delimiter, line_end))
+}
+
+/// What running a statement does to the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementKind {
+    /// Reads data only: queries, `SHOW`, `EXPLAIN`
+    Read,
+    /// Changes rows: `INSERT`, `UPDATE`, `DELETE`, `CALL` and the like
+    Write,
+    /// Creates, alters or drops schema objects, or grants access to them
+    Ddl,
+    /// Transaction control and session settings
+    Session,
+}
+
+/// Why a statement deserves a second look before it runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementRisk {
+    /// `UPDATE` or `DELETE` without `WHERE`, touching every row of the table
+    Unfiltered,
+    /// `DROP` or `TRUNCATE`, the data is gone for good
+    Destructive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Classification {
+    pub kind: StatementKind,
+    pub risk: Option<StatementRisk>,
+}
+
+impl Classification {
+    fn new(kind: StatementKind) -> Self {
+        Self { kind, risk: None }
+    }
+
+    /// Whether to ask before running the statement. Strict connections, e.g. the ones
+    /// marked as production, ask before every write and DDL, not only the risky ones.
+    pub fn needs_confirmation(&self, strict: bool) -> bool {
+        self.risk.is_some()
+            || (strict && matches!(self.kind, StatementKind::Write | StatementKind::Ddl))
+    }
+}
+
+/// Classify one statement, parsed in the dialect of the database. Statements the
+/// parser can't read, e.g. routine bodies, are classified by their first keyword.
+pub fn classify_statement(statement: &str, db_type: DatabaseType) -> Classification {
+    let dialect = dialect(db_type);
+    match Parser::parse_sql(&*dialect, statement).as_deref() {
+        Ok([parsed]) => classify_parsed(parsed, statement, &*dialect),
+        _ => classify_keywords(statement, &*dialect),
+    }
+}
+
+/// Statements of `sql` to confirm before they run, see
+/// [`Classification::needs_confirmation`]
+pub fn statements_to_confirm(sql: &str, db_type: DatabaseType, strict: bool) -> Vec<&str> {
+    split_statements(sql, db_type)
+        .into_iter()
+        .filter(|statement| classify_statement(statement, db_type).needs_confirmation(strict))
+        .collect()
+}
+
+fn classify_parsed(parsed: &Statement, statement: &str, dialect: &dyn Dialect) -> Classification {
+    let unfiltered = |selection: bool| Classification {
+        kind: StatementKind::Write,
+        risk: (!selection).then_some(StatementRisk::Unfiltered),
+    };
+    match parsed {
+        Statement::Query(query) if query_writes(query) => Classification::new(StatementKind::Write),
+        Statement::Query(_) => Classification::new(StatementKind::Read),
+        Statement::Update { selection, .. } => unfiltered(selection.is_some()),
+        Statement::Delete(delete) => unfiltered(delete.selection.is_some()),
+        Statement::Drop { .. }
+        | Statement::DropFunction { .. }
+        | Statement::DropProcedure { .. }
+        | Statement::DropTrigger { .. }
+        | Statement::DropPolicy { .. }
+        | Statement::DropSecret { .. }
+        | Statement::Truncate { .. } => Classification {
+            kind: StatementKind::Ddl,
+            risk: Some(StatementRisk::Destructive),
+        },
+        // EXPLAIN ANALYZE runs the statement it explains
+        Statement::Explain {
+            analyze: true,
+            statement: explained,
+            ..
+        } => classify_parsed(explained, &explained.to_string(), dialect),
+        Statement::Explain { .. } | Statement::ExplainTable { .. } => {
+            Classification::new(StatementKind::Read)
+        }
+        // Only to the client, a file or program is written by the server
+        Statement::Copy {
+            to: true,
+            target: CopyTarget::Stdout,
+            ..
+        } => Classification::new(StatementKind::Read),
+        _ => classify_keywords(statement, dialect),
+    }
+}
+
+/// Queries write with `SELECT ... INTO` or an `INSERT`/`UPDATE` in a CTE
+fn query_writes(query: &Query) -> bool {
+    let ctes_write = query
+        .with
+        .as_ref()
+        .is_some_and(|with| with.cte_tables.iter().any(|cte| query_writes(&cte.query)));
+    ctes_write || set_expr_writes(&query.body)
+}
+
+fn set_expr_writes(body: &SetExpr) -> bool {
+    match body {
+        SetExpr::Insert(_) | SetExpr::Update(_) => true,
+        SetExpr::Select(select) => select.into.is_some(),
+        SetExpr::Query(query) => query_writes(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            set_expr_writes(left) || set_expr_writes(right)
+        }
+        _ => false,
+    }
+}
+
+fn classify_keywords(statement: &str, dialect: &dyn Dialect) -> Classification {
+    let keyword = first_keyword(statement);
+    let kind = match keyword.as_str() {
+        // `SELECT ... INTO OUTFILE` and the like, which the parser doesn't read
+        "SELECT" if has_keyword(statement, dialect, Keyword::INTO) => StatementKind::Write,
+        "SELECT" | "SHOW" | "EXPLAIN" | "DESCRIBE" | "DESC" | "VALUES" | "TABLE" => {
+            StatementKind::Read
+        }
+        // A T-SQL block, not a transaction
+        "BEGIN" if opens_block(statement, dialect) => StatementKind::Write,
+        "CREATE" | "ALTER" | "DROP" | "TRUNCATE" | "RENAME" | "COMMENT" | "GRANT" | "REVOKE" => {
+            StatementKind::Ddl
+        }
+        "BEGIN" | "START" | "COMMIT" | "ROLLBACK" | "END" | "SAVEPOINT" | "RELEASE" | "SET"
+        | "USE" | "PRAGMA" | "DECLARE" | "PREPARE" | "DEALLOCATE" | "DISCARD" | "LOCK"
+        | "UNLOCK" => StatementKind::Session,
+        // INSERT, UPDATE, DELETE, MERGE, CALL, EXEC, and anything not known to be safe
+        _ => StatementKind::Write,
+    };
+    let risk = match keyword.as_str() {
+        "DROP" | "TRUNCATE" => Some(StatementRisk::Destructive),
+        "UPDATE" | "DELETE" if !has_keyword(statement, dialect, Keyword::WHERE) => {
+            Some(StatementRisk::Unfiltered)
+        }
+        _ => None,
+    };
+    Classification { kind, risk }
+}
+
+/// Tokens of a statement, as far as the tokenizer can read them
+fn statement_tokens(statement: &str, dialect: &dyn Dialect) -> Vec<TokenWithSpan> {
+    let mut tokens = Vec::new();
+    let _ = Tokenizer::new(dialect, statement).tokenize_with_location_into_buf(&mut tokens);
+    tokens
+}
+
+fn is_keyword(token: &TokenWithSpan, keyword: Keyword) -> bool {
+    matches!(
+        &token.token,
+        Token::Word(word) if word.keyword == keyword && word.quote_style.is_none()
+    )
+}
+
+/// Whether `keyword` appears outside literals and comments
+fn has_keyword(statement: &str, dialect: &dyn Dialect, keyword: Keyword) -> bool {
+    statement_tokens(statement, dialect)
+        .iter()
+        .any(|token| is_keyword(token, keyword))
+}
+
+/// Whether the statement's first `BEGIN` opens a block rather than a transaction
+fn opens_block(statement: &str, dialect: &dyn Dialect) -> bool {
+    let tokens = statement_tokens(statement, dialect);
+    tokens
+        .iter()
+        .position(|token| is_keyword(token, Keyword::BEGIN))
+        .is_some_and(|ix| !begins_transaction(&tokens[ix + 1..]))
+}
+
+/// Whether a read-only connection may run the statement: reads, and the session
+/// statements that neither write nor lift the read-only setting of the session.
+/// Words in literals and comments count too, which refuses a few harmless statements
+/// rather than let a spelling slip through.
+pub fn allowed_read_only(statement: &str, db_type: DatabaseType) -> bool {
+    let words: Vec<String> = statement
+        .split(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '$' | '@')))
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_uppercase)
+        .collect();
+    let has = |word: &str| words.iter().any(|w| w == word);
+    // `READ WRITE` transactions, the `read_only` settings and PostgreSQL's `set_config`
+    let lifts_read_only = has("WRITE")
+        || has("SET_CONFIG")
+        || words
+            .iter()
+            .any(|word| word.contains("READ_ONLY") || word.contains("READONLY"));
+    if lifts_read_only {
+        return false;
+    }
+    match classify_statement(statement, db_type).kind {
+        StatementKind::Read => return true,
+        StatementKind::Session => {}
+        _ => return false,
+    }
+    match first_keyword(statement).as_str() {
+        "USE" | "BEGIN" | "START" | "COMMIT" | "ROLLBACK" | "END" | "SAVEPOINT" | "RELEASE"
+        | "DECLARE" | "PREPARE" | "DEALLOCATE" | "UNLOCK" => true,
+        // Server-wide settings outlive the session
+        "SET" => !words.iter().any(|word| {
+            matches!(word.as_str(), "GLOBAL" | "PERSIST" | "PERSIST_ONLY")
+                || word.starts_with("@@GLOBAL")
+                || word.starts_with("@@PERSIST")
+        }),
+        "LOCK" => !has("EXCLUSIVE") && (has("READ") || has("SHARE")),
+        "PRAGMA" => pragma_reads(statement, &words),
+        // e.g. DISCARD ALL, which resets the read-only characteristics of the session
+        _ => false,
+    }
+}
+
+/// Whether a SQLite `PRAGMA` only reads. `PRAGMA name = value` and `PRAGMA name(value)`
+/// both set a value, except for the pragmas whose argument names what to read.
+fn pragma_reads(statement: &str, words: &[String]) -> bool {
+    const READ_WITH_ARGUMENT: &[&str] = &[
+        "TABLE_INFO",
+        "TABLE_XINFO",
+        "TABLE_LIST",
+        "INDEX_INFO",
+        "INDEX_XINFO",
+        "INDEX_LIST",
+        "FOREIGN_KEY_LIST",
+        "FOREIGN_KEY_CHECK",
+        "INTEGRITY_CHECK",
+        "QUICK_CHECK",
+    ];
+    const WRITING: &[&str] = &["OPTIMIZE", "INCREMENTAL_VACUUM", "WAL_CHECKPOINT"];
+    if statement.contains('=') || words.iter().any(|word| WRITING.contains(&word.as_str())) {
+        return false;
+    }
+    !statement.contains('(')
+        || words
+            .iter()
+            .any(|word| READ_WITH_ARGUMENT.contains(&word.as_str()))
+}
+
+/// Index just past the closing `quote`, a doubled quote is an escaped quote
+pub(crate) fn skip_quoted(bytes: &[u8], mut i: usize, quote: u8, backslash_escapes: bool) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if backslash_escapes => i += 2,
+            b if b == quote => {
+                if bytes.get(i + 1) == Some(&quote) {
+                    i += 2;
+                } else {
+                    return i + 1;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+pub(crate) fn skip_line(bytes: &[u8], i: usize) -> usize {
+    bytes[i..]
+        .iter()
+        .position(|b| *b == b'\n')
+        .map_or(bytes.len(), |end| i + end + 1)
+}
+
+fn push_trimmed(sql: &str, range: Range<usize>, ranges: &mut Vec<Range<usize>>) {
+    let text = &sql[range.clone()];
+    let start = range.start + (text.len() - text.trim_start().len());
+    ranges.push(start..start + text.trim().len());
+}
+
+/// First keyword of a statement, upper-cased, skipping leading comments
+pub fn first_keyword(statement: &str) -> String {
+    let mut rest = statement.trim_start();
+    loop {
+        if let Some(line) = rest.strip_prefix("--") {
+            rest = line
+                .split_once('\n')
+                .map_or("", |(_, tail)| tail)
+                .trim_start();
+        } else if let Some(block) = rest.strip_prefix("/*") {
+            rest = block
+                .split_once("*/")
+                .map_or("", |(_, tail)| tail)
+                .trim_start();
+        } else {
+            break;
+        }
+    }
+    rest.chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+/// Whether a statement creates, alters or drops schema objects
+pub fn changes_schema(statement: &str) -> bool {
+    matches!(
+        first_keyword(statement).as_str(),
+        "CREATE" | "ALTER" | "DROP" | "RENAME"
+    )
+}
+
+/// Parse the text of a filter bar into a [`Filter`], e.g.
+/// `name LIKE 'a%' AND (age BETWEEN 18 AND 30 OR id IN (1, 2)) AND deleted_at IS NULL`.
+/// Supports `=`, `<>`/`!=`, `LIKE`, `IN`, `IS [NOT] NULL`, `BETWEEN`, `AND`, `OR` and
+/// parentheses. Columns may be quoted with `"`, `` ` `` or `[]`, strings use `'`.
+pub fn parse_filter(input: &str) -> Result<Filter, DBError> {
+    let mut parser = FilterParser {
+        tokens: tokenize_filter(input)?,
+        pos: 0,
+    };
+    let filter = parser.or()?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(filter),
+        Some(token) => Err(filter_error(format!("unexpected {}", token))),
+    }
+}
+
+fn filter_error(message: impl Display) -> DBError {
+    DBError::QueryError(format!("Invalid filter: {}", message))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FilterToken {
+    /// Bare identifier or keyword
+    Word(String),
+    QuotedIdent(String),
+    Str(String),
+    Number(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl Display for FilterToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterToken::Word(s) | FilterToken::QuotedIdent(s) | FilterToken::Number(s) => {
+                write!(f, "'{}'", s)
+            }
+            FilterToken::Str(s) => write!(f, "string '{}'", s),
+            FilterToken::Op(op) => write!(f, "'{}'", op),
+            FilterToken::LParen => write!(f, "'('"),
+            FilterToken::RParen => write!(f, "')'"),
+            FilterToken::Comma => write!(f, "','"),
+        }
+    }
+}
+
+fn tokenize_filter(input: &str) -> Result<Vec<FilterToken>, DBError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => FilterToken::LParen,
+            ')' => FilterToken::RParen,
+            ',' => FilterToken::Comma,
+            '=' => FilterToken::Op("="),
+            '<' if chars.next_if(|(_, c)| *c == '>').is_some() => FilterToken::Op("<>"),
+            '!' if chars.next_if(|(_, c)| *c == '=').is_some() => FilterToken::Op("<>"),
+            '\'' | '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        // A doubled quote is an escaped quote
+                        Some((_, q)) if q == close => match chars.next_if(|(_, c)| *c == close) {
+                            Some(_) => text.push(close),
+                            None => break,
+                        },
+                        Some((_, ch)) => text.push(ch),
+                        None => return Err(filter_error("unterminated quote")),
+                    }
+                }
+                if c == '\'' {
+                    FilterToken::Str(text)
+                } else {
+                    FilterToken::QuotedIdent(text)
+                }
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let mut end = start + c.len_utf8();
+                while let Some((idx, _)) = chars.next_if(|(_, c)| c.is_ascii_digit() || *c == '.') {
+                    end = idx + 1;
+                }
+                FilterToken::Number(input[start..end].to_string())
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((idx, ch)) =
+                    chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '$')
+                {
+                    end = idx + ch.len_utf8();
+                }
+                FilterToken::Word(input[start..end].to_string())
+            }
+            other => return Err(filter_error(format!("unexpected '{}'", other))),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct FilterParser {
+    tokens: Vec<FilterToken>,
+    pos: usize,
+}
+
+impl FilterParser {
+    fn next(&mut self) -> Option<FilterToken> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Consume `keyword` when it is the next token
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.tokens.get(self.pos) {
+            Some(FilterToken::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, expected: FilterToken) -> Result<(), DBError> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(filter_error(format!(
+                "expected {}, found {}",
+                expected, token
+            ))),
+            None => Err(filter_error(format!("expected {}", expected))),
+        }
+    }
+
+    fn or(&mut self) -> Result<Filter, DBError> {
+        let mut filters = vec![self.and()?];
+        while self.keyword("OR") {
+            filters.push(self.and()?);
+        }
+        Ok(if filters.len() == 1 {
+            filters.remove(0)
+        } else {
+            Filter::Or(filters)
+        })
+    }
+
+    fn and(&mut self) -> Result<Filter, DBError> {
+        let mut filters = vec![self.primary()?];
+        while self.keyword("AND") {
+            filters.push(self.primary()?);
+        }
+        Ok(if filters.len() == 1 {
+            filters.remove(0)
+        } else {
+            Filter::And(filters)
+        })
+    }
+
+    fn primary(&mut self) -> Result<Filter, DBError> {
+        if self.tokens.get(self.pos) == Some(&FilterToken::LParen) {
+            self.pos += 1;
+            let filter = self.or()?;
+            self.expect(FilterToken::RParen)?;
+            return Ok(filter);
+        }
+        let column = match self.next() {
+            Some(FilterToken::Word(name) | FilterToken::QuotedIdent(name)) => name,
+            Some(token) => return Err(filter_error(format!("expected a column, found {}", token))),
+            None => return Err(filter_error("expected a column")),
+        };
+        match self.next() {
+            Some(FilterToken::Op("=")) => Ok(Filter::Eq(column, self.value()?)),
+            Some(FilterToken::Op("<>")) => Ok(Filter::NotEq(column, self.value()?)),
+            Some(FilterToken::Word(word)) => match word.to_ascii_uppercase().as_str() {
+                "LIKE" => match self.next() {
+                    Some(FilterToken::Str(pattern)) => Ok(Filter::Like(column, pattern)),
+                    _ => Err(filter_error("LIKE expects a string pattern")),
+                },
+                "IN" => {
+                    self.expect(FilterToken::LParen)?;
+                    let mut values = vec![self.value()?];
+                    while self.tokens.get(self.pos) == Some(&FilterToken::Comma) {
+                        self.pos += 1;
+                        values.push(self.value()?);
+                    }
+                    self.expect(FilterToken::RParen)?;
+                    Ok(Filter::In(column, values))
+                }
+                "IS" => {
+                    let negated = self.keyword("NOT");
+                    if !self.keyword("NULL") {
+                        return Err(filter_error("IS expects NULL or NOT NULL"));
+                    }
+                    Ok(if negated {
+                        Filter::IsNotNull(column)
+                    } else {
+                        Filter::IsNull(column)
+                    })
+                }
+                "BETWEEN" => {
+                    let low = self.value()?;
+                    if !self.keyword("AND") {
+                        return Err(filter_error("BETWEEN expects AND"));
+                    }
+                    Ok(Filter::Between(column, low, self.value()?))
+                }
+                _ => Err(filter_error(format!("unknown operator '{}'", word))),
+            },
+            Some(token) => Err(filter_error(format!(
+                "expected an operator after '{}', found {}",
+                column, token
+            ))),
+            None => Err(filter_error(format!(
+                "expected an operator after '{}'",
+                column
+            ))),
+        }
+    }
+
+    fn value(&mut self) -> Result<QueryParam, DBError> {
+        match self.next() {
+            Some(FilterToken::Str(text)) => Ok(QueryParam::Text(text)),
+            Some(FilterToken::Number(number)) => number
+                .parse()
+                .map(QueryParam::Int)
+                .or_else(|_| number.parse().map(QueryParam::Float))
+                .map_err(|_| filter_error(format!("invalid number '{}'", number))),
+            Some(FilterToken::Word(word)) => match word.to_ascii_uppercase().as_str() {
+                "NULL" => Ok(QueryParam::Null),
+                "TRUE" => Ok(QueryParam::Bool(true)),
+                "FALSE" => Ok(QueryParam::Bool(false)),
+                _ => Err(filter_error(format!(
+                    "expected a value, found '{}' (text must be in single quotes)",
+                    word
+                ))),
+            },
+            Some(token) => Err(filter_error(format!("expected a value, found {}", token))),
+            None => Err(filter_error("expected a value")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(statement: &str, db_type: DatabaseType) -> StatementKind {
+        classify_statement(statement, db_type).kind
+    }
+
+    fn risk(statement: &str, db_type: DatabaseType) -> Option<StatementRisk> {
+        classify_statement(statement, db_type).risk
+    }
+
+    #[test]
+    fn split_on_semicolons() {
+        assert_eq!(
+            split_statements("SELECT 1;\n  SELECT 2 ;\n\nSELECT 3", DatabaseType::Postgre),
+            vec!["SELECT 1", "SELECT 2", "SELECT 3"]
+        );
+        assert!(split_statements(" ;\n; ", DatabaseType::Postgre).is_empty());
+    }
+
+    #[test]
+    fn split_ignores_delimiters_in_literals_and_comments() {
+        assert_eq!(
+            split_statements(
+                "SELECT 'a;b', \"c;d\"; -- e;f\nSELECT 2 /* g; */",
+                DatabaseType::Postgre
+            ),
+            vec!["SELECT 'a;b', \"c;d\"", "-- e;f\nSELECT 2 /* g; */"]
+        );
+        assert_eq!(
+            split_statements(
+                "CREATE FUNCTION f() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql; SELECT f()",
+                DatabaseType::Postgre
+            ),
+            vec![
+                "CREATE FUNCTION f() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql",
+                "SELECT f()"
+            ]
+        );
+        // Nothing but comments is not a statement
+        assert_eq!(
+            split_statements("SELECT 1; -- done", DatabaseType::Postgre),
+            vec!["SELECT 1"]
+        );
+    }
+
+    #[test]
+    fn split_keeps_an_unterminated_literal_together() {
+        assert_eq!(
+            split_statements("SELECT 1; SELECT 'a; b", DatabaseType::Postgre),
+            vec!["SELECT 1", "SELECT 'a; b"]
+        );
+    }
+
+    #[test]
+    fn split_with_delimiter_commands() {
+        let sql = "DELIMITER //\n\
+                   CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END //\n\
+                   DELIMITER ;\n\
+                   CALL p();";
+        assert_eq!(
+            split_statements(sql, DatabaseType::MySql),
+            vec![
+                "CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END",
+                "CALL p()"
+            ]
+        );
+        // Only MySQL has the command, and a column may be named like it
+        assert_eq!(
+            split_statements("SELECT delimiter FROM t; SELECT 2", DatabaseType::MySql),
+            vec!["SELECT delimiter FROM t", "SELECT 2"]
+        );
+    }
+
+    #[test]
+    fn split_keeps_sqlite_trigger_bodies_together() {
+        let sql = "CREATE TRIGGER t AFTER INSERT ON a BEGIN UPDATE b SET x = 1; \
+                   DELETE FROM c; END; SELECT 1";
+        assert_eq!(
+            split_statements(sql, DatabaseType::Sqlite),
+            vec![
+                "CREATE TRIGGER t AFTER INSERT ON a BEGIN UPDATE b SET x = 1; DELETE FROM c; END",
+                "SELECT 1"
+            ]
+        );
+        assert_eq!(
+            split_statements(
+                "CREATE TRIGGER t AFTER INSERT ON a BEGIN \
+                 UPDATE b SET x = CASE WHEN new.y THEN 1 ELSE 2 END; SELECT 1; END; SELECT 2",
+                DatabaseType::Sqlite
+            )
+            .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn split_sqlite_transactions() {
+        assert_eq!(
+            split_statements(
+                "BEGIN; UPDATE a SET x = 1 WHERE id = 1; END; BEGIN IMMEDIATE; COMMIT",
+                DatabaseType::Sqlite
+            ),
+            vec![
+                "BEGIN",
+                "UPDATE a SET x = 1 WHERE id = 1",
+                "END",
+                "BEGIN IMMEDIATE",
+                "COMMIT"
+            ]
+        );
+    }
+
+    #[test]
+    fn split_keeps_tsql_blocks_together() {
+        assert_eq!(
+            split_statements(
+                "CREATE PROCEDURE p AS BEGIN SELECT 1; SELECT 2; END; EXEC p",
+                DatabaseType::MicrosoftSQLServer
+            ),
+            vec![
+                "CREATE PROCEDURE p AS BEGIN SELECT 1; SELECT 2; END",
+                "EXEC p"
+            ]
+        );
+        assert_eq!(
+            split_statements(
+                "BEGIN TRY SELECT 1; END TRY BEGIN CATCH SELECT 2; END CATCH; SELECT 3",
+                DatabaseType::MicrosoftSQLServer
+            ),
+            vec![
+                "BEGIN TRY SELECT 1; END TRY BEGIN CATCH SELECT 2; END CATCH",
+                "SELECT 3"
+            ]
+        );
+        assert_eq!(
+            split_statements(
+                "BEGIN TRAN; UPDATE a SET x = 1 WHERE id = 1; COMMIT",
+                DatabaseType::MicrosoftSQLServer
+            ),
+            vec!["BEGIN TRAN", "UPDATE a SET x = 1 WHERE id = 1", "COMMIT"]
+        );
+    }
+
+    #[test]
+    fn statement_under_the_cursor() {
+        let sql = "SELECT 1; SELECT 2";
+        assert_eq!(statement_at(sql, 3, DatabaseType::Postgre), Some(0..8));
+        assert_eq!(statement_at(sql, 9, DatabaseType::Postgre), Some(0..8));
+        assert_eq!(statement_at(sql, 12, DatabaseType::Postgre), Some(10..18));
+        assert_eq!(statement_at("", 0, DatabaseType::Postgre), None);
+    }
+
+    #[test]
+    fn classify_reads() {
+        for statement in [
+            "SELECT * FROM t",
+            "WITH x AS (SELECT 1) SELECT * FROM x",
+            "EXPLAIN SELECT 1",
+            "SHOW TABLES",
+        ] {
+            assert_eq!(kind(statement, DatabaseType::MySql), StatementKind::Read);
+        }
+    }
+
+    #[test]
+    fn classify_writes() {
+        for statement in [
+            "INSERT INTO t VALUES (1)",
+            "UPDATE t SET x = 1 WHERE id = 1",
+            "DELETE FROM t WHERE id = 1",
+            "CALL p()",
+            "WITH x AS (INSERT INTO t VALUES (1) RETURNING id) SELECT * FROM x",
+            "EXPLAIN ANALYZE DELETE FROM t WHERE id = 1",
+            "SELECT * INTO copy FROM t",
+        ] {
+            assert_eq!(
+                kind(statement, DatabaseType::Postgre),
+                StatementKind::Write,
+                "{statement}"
+            );
+        }
+        // Unknown statements are never taken for reads
+        assert_eq!(
+            kind("FROBNICATE t", DatabaseType::Postgre),
+            StatementKind::Write
+        );
+    }
+
+    #[test]
+    fn classify_ddl_and_session() {
+        assert_eq!(
+            kind("CREATE TABLE t (id int)", DatabaseType::Postgre),
+            StatementKind::Ddl
+        );
+        assert_eq!(
+            kind("ALTER TABLE t ADD c int", DatabaseType::Postgre),
+            StatementKind::Ddl
+        );
+        assert_eq!(kind("BEGIN", DatabaseType::Postgre), StatementKind::Session);
+        assert_eq!(
+            kind("USE shop", DatabaseType::MySql),
+            StatementKind::Session
+        );
+    }
+
+    #[test]
+    fn classify_risks() {
+        assert_eq!(
+            risk("DELETE FROM t", DatabaseType::Postgre),
+            Some(StatementRisk::Unfiltered)
+        );
+        assert_eq!(
+            risk("UPDATE t SET x = 1", DatabaseType::Postgre),
+            Some(StatementRisk::Unfiltered)
+        );
+        assert_eq!(
+            risk("DELETE FROM t WHERE id = 1", DatabaseType::Postgre),
+            None
+        );
+        assert_eq!(
+            risk("DROP TABLE t", DatabaseType::Postgre),
+            Some(StatementRisk::Destructive)
+        );
+        assert_eq!(
+            risk("TRUNCATE t", DatabaseType::Postgre),
+            Some(StatementRisk::Destructive)
+        );
+        // `where` in a literal doesn't filter an unparsed statement
+        assert_eq!(
+            risk(
+                "DELETE FROM t /* where */ LIMIT 'where' 1",
+                DatabaseType::Sqlite
+            ),
+            Some(StatementRisk::Unfiltered)
+        );
+    }
+
+    #[test]
+    fn confirm_risky_statements() {
+        let sql = "SELECT 1; DELETE FROM t; UPDATE t SET x = 1 WHERE id = 2; DROP TABLE u";
+        assert_eq!(
+            statements_to_confirm(sql, DatabaseType::Postgre, false),
+            vec!["DELETE FROM t", "DROP TABLE u"]
+        );
+        assert_eq!(
+            statements_to_confirm(sql, DatabaseType::Postgre, true),
+            vec![
+                "DELETE FROM t",
+                "UPDATE t SET x = 1 WHERE id = 2",
+                "DROP TABLE u"
+            ]
+        );
+    }
+
+    #[test]
+    fn confirm_nothing_inside_a_trigger_body() {
+        let sql = "CREATE TRIGGER t AFTER INSERT ON a BEGIN DELETE FROM c; END;";
+        assert!(statements_to_confirm(sql, DatabaseType::Sqlite, false).is_empty());
+    }
+
+    #[test]
+    fn classify_writes_out_of_the_database() {
+        for statement in [
+            "SELECT * FROM t INTO OUTFILE '/tmp/t.csv'",
+            "SELECT * INTO OUTFILE '/tmp/t.csv' FROM t",
+            "SELECT a FROM t INTO DUMPFILE '/tmp/a'",
+        ] {
+            assert_eq!(
+                kind(statement, DatabaseType::MySql),
+                StatementKind::Write,
+                "{statement}"
+            );
+        }
+        assert_eq!(
+            kind("COPY t TO '/tmp/t.csv'", DatabaseType::Postgre),
+            StatementKind::Write
+        );
+        assert_eq!(
+            kind("COPY t TO PROGRAM 'rm -rf /'", DatabaseType::Postgre),
+            StatementKind::Write
+        );
+        assert_eq!(
+            kind("COPY t TO STDOUT", DatabaseType::Postgre),
+            StatementKind::Read
+        );
+    }
+
+    #[test]
+    fn classify_tsql_blocks_as_writes() {
+        assert_eq!(
+            kind("BEGIN DELETE FROM t; END", DatabaseType::MicrosoftSQLServer),
+            StatementKind::Write
+        );
+        assert_eq!(
+            kind("BEGIN TRAN", DatabaseType::MicrosoftSQLServer),
+            StatementKind::Session
+        );
+        assert_eq!(
+            kind("BEGIN ISOLATION LEVEL SERIALIZABLE", DatabaseType::Postgre),
+            StatementKind::Session
+        );
+    }
+
+    #[test]
+    fn read_only_refuses_lifting_read_only() {
+        let cases = [
+            (
+                "SET default_transaction_read_only = off",
+                DatabaseType::Postgre,
+            ),
+            (
+                "SET SESSION default_transaction_read_only TO DEFAULT",
+                DatabaseType::Postgre,
+            ),
+            (
+                "SET SESSION CHARACTERISTICS AS TRANSACTION READ WRITE",
+                DatabaseType::Postgre,
+            ),
+            ("SET TRANSACTION READ WRITE", DatabaseType::Postgre),
+            ("BEGIN READ WRITE", DatabaseType::Postgre),
+            ("START TRANSACTION READ WRITE", DatabaseType::MySql),
+            ("SET SESSION TRANSACTION READ WRITE", DatabaseType::MySql),
+            (
+                "SET @@SESSION.transaction_read_only = 0",
+                DatabaseType::MySql,
+            ),
+            ("SET GLOBAL read_only = 0", DatabaseType::MySql),
+            ("SET GLOBAL max_connections = 1", DatabaseType::MySql),
+            ("SET @@global.max_connections = 1", DatabaseType::MySql),
+            ("SET PERSIST max_connections = 1", DatabaseType::MySql),
+            (
+                "SELECT set_config('default_transaction_read_only', 'off', false)",
+                DatabaseType::Postgre,
+            ),
+            ("DISCARD ALL", DatabaseType::Postgre),
+            ("RESET ALL", DatabaseType::Postgre),
+        ];
+        for (statement, db_type) in cases {
+            assert!(!allowed_read_only(statement, db_type), "{statement}");
+        }
+    }
+
+    #[test]
+    fn read_only_refuses_writing_session_statements() {
+        let cases = [
+            ("LOCK TABLES t WRITE", DatabaseType::MySql),
+            ("LOCK TABLES a READ, b WRITE", DatabaseType::MySql),
+            ("LOCK TABLE t", DatabaseType::Postgre),
+            (
+                "LOCK TABLE t IN ACCESS EXCLUSIVE MODE",
+                DatabaseType::Postgre,
+            ),
+            ("PRAGMA user_version = 5", DatabaseType::Sqlite),
+            ("PRAGMA writable_schema = 1", DatabaseType::Sqlite),
+            ("PRAGMA main.journal_mode(WAL)", DatabaseType::Sqlite),
+            ("PRAGMA optimize", DatabaseType::Sqlite),
+            ("BEGIN DELETE FROM t; END", DatabaseType::MicrosoftSQLServer),
+            (
+                "SELECT * FROM t INTO OUTFILE '/tmp/t.csv'",
+                DatabaseType::MySql,
+            ),
+            ("COPY t TO '/tmp/t.csv'", DatabaseType::Postgre),
+            ("DELETE FROM t WHERE id = 1", DatabaseType::Postgre),
+            ("CREATE TABLE t (id int)", DatabaseType::Postgre),
+        ];
+        for (statement, db_type) in cases {
+            assert!(!allowed_read_only(statement, db_type), "{statement}");
+        }
+    }
+
+    #[test]
+    fn read_only_allows_reads_and_harmless_session_statements() {
+        let cases = [
+            ("SELECT * FROM t", DatabaseType::Postgre),
+            ("USE shop", DatabaseType::MySql),
+            ("BEGIN", DatabaseType::Postgre),
+            ("BEGIN READ ONLY", DatabaseType::Postgre),
+            ("START TRANSACTION", DatabaseType::MySql),
+            ("BEGIN TRAN", DatabaseType::MicrosoftSQLServer),
+            ("COMMIT", DatabaseType::Postgre),
+            ("ROLLBACK", DatabaseType::Sqlite),
+            ("SET search_path = sales", DatabaseType::Postgre),
+            ("SET NAMES utf8mb4", DatabaseType::MySql),
+            ("SET @limit = 10", DatabaseType::MySql),
+            ("SET NOCOUNT ON", DatabaseType::MicrosoftSQLServer),
+            ("LOCK TABLES t READ", DatabaseType::MySql),
+            ("UNLOCK TABLES", DatabaseType::MySql),
+            ("PRAGMA user_version", DatabaseType::Sqlite),
+            ("PRAGMA table_info(t)", DatabaseType::Sqlite),
+            ("PRAGMA main.index_list('t')", DatabaseType::Sqlite),
+        ];
+        for (statement, db_type) in cases {
+            assert!(allowed_read_only(statement, db_type), "{statement}");
+        }
+    }
+
+    #[test]
+    fn filter_comparisons() {
+        assert_eq!(
+            parse_filter("name = 'a''b'").unwrap(),
+            Filter::Eq("name".to_string(), QueryParam::Text("a'b".to_string()))
+        );
+        assert_eq!(
+            parse_filter("age != 3").unwrap(),
+            Filter::NotEq("age".to_string(), QueryParam::Int(3))
+        );
+        assert_eq!(
+            parse_filter("[order total] <> -1.5").unwrap(),
+            Filter::NotEq("order total".to_string(), QueryParam::Float(-1.5))
+        );
+        assert_eq!(
+            parse_filter("`flag` = true").unwrap(),
+            Filter::Eq("flag".to_string(), QueryParam::Bool(true))
+        );
+        assert_eq!(
+            parse_filter("name like 'a%'").unwrap(),
+            Filter::Like("name".to_string(), "a%".to_string())
+        );
+    }
+
+    #[test]
+    fn filter_in_is_and_between() {
+        assert_eq!(
+            parse_filter("id IN (1, 'x', NULL)").unwrap(),
+            Filter::In(
+                "id".to_string(),
+                vec![
+                    QueryParam::Int(1),
+                    QueryParam::Text("x".to_string()),
+                    QueryParam::Null
+                ]
+            )
+        );
+        assert_eq!(
+            parse_filter("a IS NULL").unwrap(),
+            Filter::IsNull("a".to_string())
+        );
+        assert_eq!(
+            parse_filter("\"a\" is not null").unwrap(),
+            Filter::IsNotNull("a".to_string())
+        );
+        assert_eq!(
+            parse_filter("age BETWEEN 18 AND 30").unwrap(),
+            Filter::Between("age".to_string(), QueryParam::Int(18), QueryParam::Int(30))
+        );
+    }
+
+    #[test]
+    fn filter_and_binds_tighter_than_or() {
+        assert_eq!(
+            parse_filter("a = 1 OR b = 2 AND (c = 3 OR d IS NULL)").unwrap(),
+            Filter::Or(vec![
+                Filter::Eq("a".to_string(), QueryParam::Int(1)),
+                Filter::And(vec![
+                    Filter::Eq("b".to_string(), QueryParam::Int(2)),
+                    Filter::Or(vec![
+                        Filter::Eq("c".to_string(), QueryParam::Int(3)),
+                        Filter::IsNull("d".to_string()),
+                    ]),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn filter_errors() {
+        for input in [
+            "",
+            "name = 'open",
+            "name = bob",
+            "name ~ 1",
+            "name",
+            "a = 1 b = 2",
+            "(a = 1",
+            "id IN (1, 2",
+            "a IS 1",
+            "a BETWEEN 1 OR 2",
+            "a LIKE 1",
+        ] {
+            let error = parse_filter(input).unwrap_err();
+            assert!(
+                matches!(&error, DBError::QueryError(message) if message.starts_with("Invalid filter")),
+                "{input}: {error:?}"
+            );
+        }
+    }
+}